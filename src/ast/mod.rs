@@ -3,14 +3,17 @@ use std::ops::{Deref, DerefMut};
 use anyhow::bail;
 use log::error;
 mod token;
+mod visit;
 
 use crate::die;
+use crate::source_map::Span;
 
 pub use token::{LiteralValue, Token, TokenType};
+pub use visit::{visit_exprs, visit_types_mut};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Metadata {
-    pub line: usize,
+    pub span: Span,
     pub node_type: Option<DType>,
 }
 
@@ -44,6 +47,7 @@ impl TryFrom<&Token> for EveTypes {
         match &value.ttype {
             TokenType::TypeInt => Ok(EveTypes::Int),
             TokenType::TypeFloat => Ok(EveTypes::Float),
+            TokenType::TypeString => Ok(EveTypes::String),
             TokenType::TypeVoid => Ok(EveTypes::Void),
             TokenType::String => Ok(EveTypes::String),
             ty => bail!("EveTypes::TryFrom<Token>  recieved type = {}", ty),
@@ -58,6 +62,32 @@ impl TryFrom<&DType> for EveTypes {
         match &value {
             DType::Primitive(e) => Ok(e.to_owned()),
             DType::Derived(_) => bail!("EveTypes::TryFrom<DType> recieved derived type"),
+            DType::Array(..) => bail!("EveTypes::TryFrom<DType> recieved array type"),
+            DType::Vector(_) => bail!("EveTypes::TryFrom<DType> recieved vector type"),
+            DType::Pointer(_) => bail!("EveTypes::TryFrom<DType> recieved pointer type"),
+            DType::Enum(_) => bail!("EveTypes::TryFrom<DType> recieved enum type"),
+        }
+    }
+}
+
+impl DType {
+    /// Name of the struct stored inline by a value of this type, arrays store their elements inline.
+    pub fn inline_struct(&self) -> Option<&str> {
+        match self {
+            DType::Derived(name) => Some(name),
+            DType::Array(elem, _) => elem.inline_struct(),
+            DType::Primitive(_) | DType::Vector(_) | DType::Pointer(_) | DType::Enum(_) => None,
+        }
+    }
+
+    /// Name of the struct this type refers to, looking through arrays and vectors.
+    pub fn struct_name(&self) -> Option<&str> {
+        match self {
+            DType::Derived(name) => Some(name),
+            DType::Array(elem, _) | DType::Vector(elem) | DType::Pointer(elem) => {
+                elem.struct_name()
+            }
+            DType::Primitive(_) | DType::Enum(_) => None,
         }
     }
 }
@@ -66,7 +96,10 @@ impl std::fmt::Display for DType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             DType::Primitive(p) => write!(f, "{}", p),
-            DType::Derived(d) => write!(f, "{}", d),
+            DType::Derived(d) | DType::Enum(d) => write!(f, "{}", d),
+            DType::Array(elem, len) => write!(f, "[{}; {}]", elem, len),
+            DType::Vector(elem) => write!(f, "Vec<{}>", elem),
+            DType::Pointer(pointee) => write!(f, "*{}", pointee),
         }
     }
 }
@@ -123,11 +156,11 @@ impl std::fmt::Display for BinOp {
 impl From<&TokenType> for BinOp {
     fn from(value: &TokenType) -> Self {
         match value {
-            TokenType::Plus => BinOp::Add,
-            TokenType::Minus => BinOp::Sub,
-            TokenType::Slash => BinOp::Div,
-            TokenType::Star => BinOp::Mul,
-            TokenType::Mod => BinOp::Mod,
+            TokenType::Plus | TokenType::PlusEqual | TokenType::PlusPlus => BinOp::Add,
+            TokenType::Minus | TokenType::MinusEqual | TokenType::MinusMinus => BinOp::Sub,
+            TokenType::Slash | TokenType::SlashEqual => BinOp::Div,
+            TokenType::Star | TokenType::StarEqual => BinOp::Mul,
+            TokenType::Mod | TokenType::ModEqual => BinOp::Mod,
             TokenType::Less => BinOp::Less,
             TokenType::LessEqual => BinOp::LessEqual,
             TokenType::Greater => BinOp::Greater,
@@ -193,7 +226,7 @@ pub struct GroupExpr {
 #[derive(Debug, Clone, PartialEq)]
 pub struct CallExpr {
     pub callee: Expr,
-    pub arg: Option<Expr>,
+    pub args: Vec<Expr>,
     pub metadata: Metadata,
 }
 
@@ -208,6 +241,8 @@ pub struct FieldAccessExpr {
 pub struct NativeCallExpr {
     pub callee: Expr,
     pub args: Vec<Expr>,
+    // declared result type, 'extern f() -> T', Int if not given.
+    pub return_type: Option<DType>,
     pub metadata: Metadata,
 }
 
@@ -217,13 +252,138 @@ pub struct VariableExpr {
     pub metadata: Metadata,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexExpr {
+    pub object: Expr,
+    pub index: Expr,
+    pub metadata: Metadata,
+}
+
+/// Array literal, either a list of elements or '[value; count]'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayInitExpr {
+    pub elements: Vec<Expr>,
+    /// set for '[value; count]', 'elements' then holds the single value.
+    pub count: Option<usize>,
+    pub metadata: Metadata,
+}
+
+/// Creates an empty vector, 'Vec<T>()'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VecNewExpr {
+    pub elem_type: DType,
+    pub metadata: Metadata,
+}
+
+/// Address of a place, '&place'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddrOfExpr {
+    pub target: LValue,
+    pub metadata: Metadata,
+}
+
+/// Value a pointer points to, '*pointer'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerefExpr {
+    pub pointer: Expr,
+    pub metadata: Metadata,
+}
+
+/// Variant of an enum, 'Name::Variant'.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariantExpr {
+    pub enum_name: String,
+    pub variant: String,
+    pub metadata: Metadata,
+}
+
+/// Places which can be assigned to.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LValue {
+    Variable(VariableExpr),
+    FieldAccess(FieldAccessExpr),
+    Index(IndexExpr),
+    Deref(DerefExpr),
+}
+
+impl std::fmt::Display for LValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LValue::Variable(var) => write!(f, "{}", var.name),
+            LValue::FieldAccess(fac) => write!(f, "{}.{}", fac.parent, fac.field),
+            LValue::Index(idx) => write!(f, "{}[{}]", idx.object, idx.index),
+            LValue::Deref(deref) => write!(f, "*{}", deref.pointer),
+        }
+    }
+}
+
+impl TryFrom<Expr> for LValue {
+    type Error = Expr;
+
+    /// Gives back the expression if it can't be assigned to.
+    fn try_from(value: Expr) -> Result<Self, Self::Error> {
+        match value {
+            Expr::Variable(var) => Ok(LValue::Variable(*var)),
+            Expr::FieldAccess(fac) => Ok(LValue::FieldAccess(*fac)),
+            Expr::Index(idx) => Ok(LValue::Index(*idx)),
+            Expr::Deref(deref) => Ok(LValue::Deref(*deref)),
+            expr => Err(expr),
+        }
+    }
+}
+
+impl Deref for LValue {
+    type Target = Metadata;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            LValue::Variable(var) => &var.metadata,
+            LValue::FieldAccess(fac) => &fac.metadata,
+            LValue::Index(idx) => &idx.metadata,
+            LValue::Deref(deref) => &deref.metadata,
+        }
+    }
+}
+
+/// How an assignment combines its value with the place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignKind {
+    /// 'target = value'
+    Plain,
+    /// 'target op= value'
+    Compound(BinOp),
+    /// 'target++' and 'target--' with a value of 1,
+    /// these evaluate to the value of the place before the update.
+    Postfix(BinOp),
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct AssignmentExpr {
-    pub name: String,
+    pub target: LValue,
+    pub kind: AssignKind,
     pub value: Expr,
     pub metadata: Metadata,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct StInitField {
+    pub field_name: String,
+    pub field_expr: Expr,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructInitExpr {
+    pub struct_name: String,
+    pub arguments: Vec<StInitField>,
+    pub metadata: Metadata,
+}
+
+/// Placeholder for an expression which failed to parse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ErrorExpr {
+    pub metadata: Metadata,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Expr {
     Binary(Box<BinExpr>),
@@ -235,16 +395,30 @@ pub enum Expr {
     Variable(Box<VariableExpr>),
     Literal(LiteralExpr),
     Assignment(Box<AssignmentExpr>),
+    StructInit(Box<StructInitExpr>),
+    Index(Box<IndexExpr>),
+    ArrayInit(Box<ArrayInitExpr>),
+    VecNew(Box<VecNewExpr>),
+    AddrOf(Box<AddrOfExpr>),
+    Deref(Box<DerefExpr>),
+    EnumVariant(Box<EnumVariantExpr>),
+    Error(ErrorExpr),
 }
 
 impl std::fmt::Display for Expr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Expr::Binary(bin) => write!(f, "{} {} {}", bin.left, bin.op, bin.right),
-            Expr::Call(call) => match &call.arg {
-                Some(arg) => write!(f, "{}({})", call.callee, arg),
-                None => write!(f, "{}()", call.callee),
-            },
+            Expr::Call(call) => write!(
+                f,
+                "{}({})",
+                call.callee,
+                call.args
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
             Expr::FieldAccess(fac) => write!(f, "{}.{}", fac.parent, fac.field),
             Expr::NativeCall(call) => write!(
                 f,
@@ -260,7 +434,40 @@ impl std::fmt::Display for Expr {
             Expr::Grouping(gr) => write!(f, "({})", gr.value),
             Expr::Variable(var) => write!(f, "{}", var.name),
             Expr::Literal(lit) => write!(f, "{}", lit.value),
-            Expr::Assignment(ass) => write!(f, "{} = {}", ass.name, ass.value),
+            Expr::Assignment(ass) => match ass.kind {
+                AssignKind::Plain => write!(f, "{} = {}", ass.target, ass.value),
+                AssignKind::Compound(op) => write!(f, "{} {}= {}", ass.target, op, ass.value),
+                AssignKind::Postfix(op) => write!(f, "{}{}{}", ass.target, op, op),
+            },
+            Expr::StructInit(st_init) => write!(
+                f,
+                "{} {{ {} }}",
+                st_init.struct_name,
+                st_init
+                    .arguments
+                    .iter()
+                    .map(|x| format!("{}: {}", x.field_name, x.field_expr))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Expr::Index(idx) => write!(f, "{}[{}]", idx.object, idx.index),
+            Expr::ArrayInit(arr) => match arr.count {
+                Some(count) => write!(f, "[{}; {}]", arr.elements[0], count),
+                None => write!(
+                    f,
+                    "[{}]",
+                    arr.elements
+                        .iter()
+                        .map(|x| x.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            Expr::VecNew(vec) => write!(f, "Vec<{}>()", vec.elem_type),
+            Expr::AddrOf(addr) => write!(f, "&{}", addr.target),
+            Expr::Deref(deref) => write!(f, "*{}", deref.pointer),
+            Expr::EnumVariant(var) => write!(f, "{}::{}", var.enum_name, var.variant),
+            Expr::Error(_) => write!(f, "<error>"),
         }
     }
 }
@@ -278,6 +485,14 @@ impl Deref for Expr {
             Expr::Variable(var) => &var.metadata,
             Expr::Literal(lit) => &lit.metadata,
             Expr::Assignment(ass) => &ass.metadata,
+            Expr::StructInit(st_init) => &st_init.metadata,
+            Expr::Index(idx) => &idx.metadata,
+            Expr::ArrayInit(arr) => &arr.metadata,
+            Expr::VecNew(vec) => &vec.metadata,
+            Expr::AddrOf(addr) => &addr.metadata,
+            Expr::Deref(deref) => &deref.metadata,
+            Expr::EnumVariant(var) => &var.metadata,
+            Expr::Error(err) => &err.metadata,
         }
     }
 }
@@ -294,6 +509,14 @@ impl DerefMut for Expr {
             Expr::Variable(var) => &mut var.metadata,
             Expr::Literal(lit) => &mut lit.metadata,
             Expr::Assignment(ass) => &mut ass.metadata,
+            Expr::StructInit(st_init) => &mut st_init.metadata,
+            Expr::Index(idx) => &mut idx.metadata,
+            Expr::ArrayInit(arr) => &mut arr.metadata,
+            Expr::VecNew(vec) => &mut vec.metadata,
+            Expr::AddrOf(addr) => &mut addr.metadata,
+            Expr::Deref(deref) => &mut deref.metadata,
+            Expr::EnumVariant(var) => &mut var.metadata,
+            Expr::Error(err) => &mut err.metadata,
         }
     }
 }
@@ -301,66 +524,114 @@ impl DerefMut for Expr {
 #[derive(Debug, Clone)]
 pub struct BlockStmt {
     pub stmts: Vec<Stmt>,
-    #[allow(dead_code)]
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
 pub struct LetStmt {
     pub name: String,
+    // type annotation, if any.
+    pub ty: Option<DType>,
     pub initialiser: Expr,
-    #[allow(dead_code)]
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
-pub struct StInitField {
-    pub field_name: String,
-    pub field_expr: Expr,
+pub struct IfStmt {
+    pub condition: Expr,
+    pub if_branch: Stmt,
+    pub else_branch: Option<Stmt>,
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
-pub struct StructInitStmt {
-    pub name: String,
-    pub struct_name: String,
-    pub arguments: Vec<StInitField>,
+pub struct LoopStmt {
+    pub label: Option<String>,
+    pub body: Stmt,
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
-pub struct IfStmt {
+pub struct WhileStmt {
+    pub label: Option<String>,
     pub condition: Expr,
-    pub if_branch: Stmt,
-    pub else_branch: Option<Stmt>,
-    #[allow(dead_code)]
+    pub body: Stmt,
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
-pub struct LoopStmt {
+pub struct ForStmt {
+    pub label: Option<String>,
+    pub initialiser: Option<Stmt>,
+    pub condition: Option<Expr>,
+    pub increment: Option<Expr>,
+    pub body: Stmt,
+    pub metadata: Metadata,
+}
+
+/// Values a match arm is taken for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MatchPattern {
+    Int(i32),
+    // both bounds are inclusive.
+    Range(i32, i32),
+    // enum name and variant name.
+    Variant(String, String),
+    Wildcard,
+}
+
+impl std::fmt::Display for MatchPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MatchPattern::Int(v) => write!(f, "{}", v),
+            MatchPattern::Range(lo, hi) => write!(f, "{}..={}", lo, hi),
+            MatchPattern::Variant(enum_name, variant) => write!(f, "{}::{}", enum_name, variant),
+            MatchPattern::Wildcard => write!(f, "_"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchArm {
+    pub pattern: MatchPattern,
     pub body: Stmt,
-    #[allow(dead_code)]
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchStmt {
+    pub value: Expr,
+    pub arms: Vec<MatchArm>,
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
 pub struct BreakStmt {
-    #[allow(dead_code)]
+    pub label: Option<String>,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContinueStmt {
+    pub label: Option<String>,
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
 pub struct PrintStmt {
     pub value: Expr,
-    #[allow(dead_code)]
     pub metadata: Metadata,
 }
 
 #[derive(Debug, Clone)]
 pub struct ReturnStmt {
     pub value: Option<Expr>,
-    #[allow(dead_code)]
+    pub metadata: Metadata,
+}
+
+/// Placeholder for a statement which failed to parse.
+#[derive(Debug, Clone)]
+pub struct ErrorStmt {
     pub metadata: Metadata,
 }
 
@@ -368,27 +639,62 @@ pub struct ReturnStmt {
 pub enum Stmt {
     Block(BlockStmt),
     Let(LetStmt),
-    StructInit(StructInitStmt),
     If(Box<IfStmt>),
     Loop(Box<LoopStmt>),
+    While(Box<WhileStmt>),
+    For(Box<ForStmt>),
+    Match(Box<MatchStmt>),
     Break(BreakStmt),
+    Continue(ContinueStmt),
     Print(PrintStmt),
     Return(ReturnStmt),
     Expression(Expr),
+    Error(ErrorStmt),
+}
+
+impl Deref for Stmt {
+    type Target = Metadata;
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Stmt::Block(block) => &block.metadata,
+            Stmt::Let(le) => &le.metadata,
+            Stmt::If(ifst) => &ifst.metadata,
+            Stmt::Loop(lop) => &lop.metadata,
+            Stmt::While(whil) => &whil.metadata,
+            Stmt::For(fo) => &fo.metadata,
+            Stmt::Match(mat) => &mat.metadata,
+            Stmt::Break(bre) => &bre.metadata,
+            Stmt::Continue(cont) => &cont.metadata,
+            Stmt::Print(p) => &p.metadata,
+            Stmt::Return(ret) => &ret.metadata,
+            Stmt::Expression(expr) => expr,
+            Stmt::Error(err) => &err.metadata,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DType {
     Primitive(EveTypes),
     Derived(String),
+    /// fixed size array, element type and length.
+    Array(Box<DType>, usize),
+    /// growable vector living on the heap, element type.
+    Vector(Box<DType>),
+    /// address of a value of the pointee type.
+    Pointer(Box<DType>),
+    /// enum declared by the user, its values are ints.
+    Enum(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct FnStDeclField {
     pub field_name: String,
-    // This is only Primitive in case of structs for now.
     pub field_type: DType,
     pub metadata: Metadata,
+    // lines of the field's doc comments.
+    #[allow(dead_code)]
+    pub docs: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -396,13 +702,66 @@ pub struct StructDecl {
     pub name: String,
     pub fields: Vec<FnStDeclField>,
     pub metadata: Metadata,
+    #[allow(dead_code)]
+    pub docs: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct FnDecl {
     pub name: String,
-    pub parameter: Option<FnStDeclField>,
+    pub parameters: Vec<FnStDeclField>,
     pub return_type: DType,
     pub body: Vec<Stmt>,
     pub metadata: Metadata,
+    #[allow(dead_code)]
+    pub docs: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumVariant {
+    pub name: String,
+    pub value: i32,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnumDecl {
+    pub name: String,
+    pub variants: Vec<EnumVariant>,
+    pub metadata: Metadata,
+    #[allow(dead_code)]
+    pub docs: Vec<String>,
+}
+
+/// Kind of a module level binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalKind {
+    /// evaluated at compile time and inlined at every use.
+    Const,
+    /// emitted as data, can be updated at runtime.
+    Static,
+}
+
+impl std::fmt::Display for GlobalKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                GlobalKind::Const => "constant",
+                GlobalKind::Static => "static",
+            }
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GlobalDecl {
+    pub kind: GlobalKind,
+    pub name: String,
+    pub ty: DType,
+    pub value: Expr,
+    pub metadata: Metadata,
+    #[allow(dead_code)]
+    pub docs: Vec<String>,
 }
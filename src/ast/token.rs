@@ -1,19 +1,24 @@
+use crate::source_map::Span;
+
 /// All types of token.
 #[derive(Debug, Clone, PartialEq)]
 pub enum TokenType {
     // single-character tokens.
-    LeftParen,  // (
-    RightParen, // )
-    LeftBrace,  // {
-    RightBrace, // }
-    Comma,      // ,
-    Dot,        // .
-    Plus,       // +
-    Colon,      // :
-    Semicolon,  // ;
-    Slash,      // /
-    Star,       // *
-    Mod,        // %
+    LeftParen,    // (
+    RightParen,   // )
+    LeftBrace,    // {
+    RightBrace,   // }
+    LeftBracket,  // [
+    RightBracket, // ]
+    Comma,        // ,
+    Dot,          // .
+    Plus,         // +
+    Colon,        // :
+    Semicolon,    // ;
+    Slash,        // /
+    Star,         // *
+    Mod,          // %
+    Ampersand,    // &
 
     // one or two character tokens.
     Bang,         // !
@@ -26,36 +31,134 @@ pub enum TokenType {
     LessEqual,    // <=
     Minus,        // -
     FatArrow,     // ->
+    MatchArrow,   // =>
+    PlusEqual,    // +=
+    MinusEqual,   // -=
+    StarEqual,    // *=
+    SlashEqual,   // /=
+    ModEqual,     // %=
+    PlusPlus,     // ++
+    MinusMinus,   // --
+    DotDot,       // ..
+    DotDotEqual,  // ..=
+    ColonColon,   // ::
 
     // literals.
     Identifier,  // variables, function names, class names.
+    Label,       // loop labels, 'name.
     String,      // Strings.
     NumberInt,   // numbers : integers.
     NumberFloat, // numbers : floats.
 
     // keywords
-    True,   // true
-    False,  // false
-    Null,   // null
-    And,    // and
-    Or,     // or
-    Let,    // let
-    Fn,     // fn
-    Return, // return
-    If,     // if
-    Else,   // else
-    Loop,   // loop
-    Break,  // break
-    Print,  // print
-    Struct, // struct
-    Extern, // extern
+    True,     // true
+    False,    // false
+    Null,     // null
+    And,      // and
+    Or,       // or
+    Let,      // let
+    Fn,       // fn
+    Return,   // return
+    If,       // if
+    Else,     // else
+    Loop,     // loop
+    While,    // while
+    For,      // for
+    Break,    // break
+    Continue, // continue
+    Match,    // match
+    Print,    // print
+    Struct,   // struct
+    Extern,   // extern
+    Const,    // const
+    Static,   // static
+    Enum,     // enum
 
     // Types
     TypeInt,
     TypeFloat,
+    TypeString,
     TypeVoid,
 
-    Eof, // end of file.
+    Error, // invalid input, already reported by the lexer.
+    Eof,   // end of file.
+}
+
+impl TokenType {
+    /// Returns how the token is written in source, for error messages.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            TokenType::LeftParen => "`(`",
+            TokenType::RightParen => "`)`",
+            TokenType::LeftBrace => "`{`",
+            TokenType::RightBrace => "`}`",
+            TokenType::LeftBracket => "`[`",
+            TokenType::RightBracket => "`]`",
+            TokenType::Comma => "`,`",
+            TokenType::Dot => "`.`",
+            TokenType::Plus => "`+`",
+            TokenType::Colon => "`:`",
+            TokenType::Semicolon => "`;`",
+            TokenType::Slash => "`/`",
+            TokenType::Star => "`*`",
+            TokenType::Mod => "`%`",
+            TokenType::Ampersand => "`&`",
+            TokenType::Bang => "`!`",
+            TokenType::BangEqual => "`!=`",
+            TokenType::Equal => "`=`",
+            TokenType::EqualEqual => "`==`",
+            TokenType::Greater => "`>`",
+            TokenType::GreaterEqual => "`>=`",
+            TokenType::Less => "`<`",
+            TokenType::LessEqual => "`<=`",
+            TokenType::Minus => "`-`",
+            TokenType::FatArrow => "`->`",
+            TokenType::MatchArrow => "`=>`",
+            TokenType::PlusEqual => "`+=`",
+            TokenType::MinusEqual => "`-=`",
+            TokenType::StarEqual => "`*=`",
+            TokenType::SlashEqual => "`/=`",
+            TokenType::ModEqual => "`%=`",
+            TokenType::PlusPlus => "`++`",
+            TokenType::MinusMinus => "`--`",
+            TokenType::DotDot => "`..`",
+            TokenType::DotDotEqual => "`..=`",
+            TokenType::ColonColon => "`::`",
+            TokenType::Identifier => "identifier",
+            TokenType::Label => "label",
+            TokenType::String => "string literal",
+            TokenType::NumberInt => "integer literal",
+            TokenType::NumberFloat => "float literal",
+            TokenType::True => "`true`",
+            TokenType::False => "`false`",
+            TokenType::Null => "`null`",
+            TokenType::And => "`and`",
+            TokenType::Or => "`or`",
+            TokenType::Let => "`let`",
+            TokenType::Fn => "`fn`",
+            TokenType::Return => "`return`",
+            TokenType::If => "`if`",
+            TokenType::Else => "`else`",
+            TokenType::Loop => "`loop`",
+            TokenType::While => "`while`",
+            TokenType::For => "`for`",
+            TokenType::Break => "`break`",
+            TokenType::Continue => "`continue`",
+            TokenType::Match => "`match`",
+            TokenType::Print => "`print`",
+            TokenType::Struct => "`struct`",
+            TokenType::Extern => "`extern`",
+            TokenType::Const => "`const`",
+            TokenType::Static => "`static`",
+            TokenType::Enum => "`enum`",
+            TokenType::TypeInt => "`int`",
+            TokenType::TypeFloat => "`float`",
+            TokenType::TypeString => "`string`",
+            TokenType::TypeVoid => "`void`",
+            TokenType::Error => "invalid token",
+            TokenType::Eof => "end of file",
+        }
+    }
 }
 
 impl std::fmt::Display for TokenType {
@@ -98,19 +201,26 @@ pub struct Token {
     // parsed literal
     pub literal: LiteralValue,
 
-    // at which line number in the source.
-    pub line: usize,
+    // where the token is in the source.
+    pub span: Span,
+
+    // lines of '///' doc comments written right before the token.
+    pub docs: Vec<String>,
 }
 
 impl Token {
     pub fn is_a_basic_type(&self) -> bool {
         matches!(
             self.ttype,
-            TokenType::TypeInt | TokenType::TypeFloat | TokenType::TypeVoid
+            TokenType::TypeInt | TokenType::TypeFloat | TokenType::TypeString | TokenType::TypeVoid
         )
     }
     pub fn is_a_extended_type(&self) -> bool {
-        matches!(self.ttype, TokenType::TypeVoid)
+        // '[' starts an array type, '*' a pointer type.
+        matches!(
+            self.ttype,
+            TokenType::TypeVoid | TokenType::Identifier | TokenType::LeftBracket | TokenType::Star
+        )
     }
     pub fn is_a_type(&self) -> bool {
         self.is_a_basic_type() || self.is_a_extended_type()
@@ -124,14 +234,15 @@ impl std::fmt::Display for Token {
 }
 
 /// static array of all reserved keywords.
-static RESERVED_KEYWORDS_KEYS: [&str; 18] = [
-    "true", "false", "null", "and", "or", "let", "fn", "return", "if", "else", "loop", "break",
-    "print", "struct", "extern", "int", "float", "void",
+static RESERVED_KEYWORDS_KEYS: [&str; 26] = [
+    "true", "false", "null", "and", "or", "let", "fn", "return", "if", "else", "loop", "while",
+    "for", "break", "continue", "match", "print", "struct", "extern", "const", "static", "enum",
+    "int", "float", "string", "void",
 ];
 
 /// TokenTypes which are reserved keywords,
 /// THIS HAS TO BE IN SAME ORDER AS RESERVED_KEYWORDS_KEYS
-static RESERVED_KEYWORDS_TYPES: [TokenType; 18] = [
+static RESERVED_KEYWORDS_TYPES: [TokenType; 26] = [
     TokenType::True,
     TokenType::False,
     TokenType::Null,
@@ -143,12 +254,20 @@ static RESERVED_KEYWORDS_TYPES: [TokenType; 18] = [
     TokenType::If,
     TokenType::Else,
     TokenType::Loop,
+    TokenType::While,
+    TokenType::For,
     TokenType::Break,
+    TokenType::Continue,
+    TokenType::Match,
     TokenType::Print,
     TokenType::Struct,
     TokenType::Extern,
+    TokenType::Const,
+    TokenType::Static,
+    TokenType::Enum,
     TokenType::TypeInt,
     TokenType::TypeFloat,
+    TokenType::TypeString,
     TokenType::TypeVoid,
 ];
 
@@ -161,7 +280,7 @@ pub fn is_reserved(target: &str) -> bool {
 impl<'a> TryFrom<&'a str> for TokenType {
     type Error = anyhow::Error;
 
-    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+    fn try_from(value: &'a str) -> Result<Self, <Self as TryFrom<&'a str>>::Error> {
         if is_reserved(value) {
             Ok(RESERVED_KEYWORDS_TYPES[RESERVED_KEYWORDS_KEYS
                 .iter()
@@ -0,0 +1,228 @@
+use super::{DType, Expr, LValue, Stmt};
+
+/// Calls 'visit' with every expression in the given statements, including nested ones.
+/// Outer expressions are visited before the expressions inside them.
+pub fn visit_exprs<'a>(stmts: &'a [Stmt], visit: &mut impl FnMut(&'a Expr)) {
+    for stmt in stmts {
+        visit_stmt(stmt, visit);
+    }
+}
+
+fn visit_stmt<'a>(stmt: &'a Stmt, visit: &mut impl FnMut(&'a Expr)) {
+    match stmt {
+        Stmt::Block(block) => visit_exprs(&block.stmts, visit),
+        Stmt::Let(le) => visit_expr(&le.initialiser, visit),
+        Stmt::If(ifst) => {
+            visit_expr(&ifst.condition, visit);
+            visit_stmt(&ifst.if_branch, visit);
+            if let Some(else_branch) = &ifst.else_branch {
+                visit_stmt(else_branch, visit);
+            }
+        }
+        Stmt::Loop(lo) => visit_stmt(&lo.body, visit),
+        Stmt::While(wh) => {
+            visit_expr(&wh.condition, visit);
+            visit_stmt(&wh.body, visit);
+        }
+        Stmt::For(fo) => {
+            if let Some(initialiser) = &fo.initialiser {
+                visit_stmt(initialiser, visit);
+            }
+            for expr in fo.condition.iter().chain(fo.increment.iter()) {
+                visit_expr(expr, visit);
+            }
+            visit_stmt(&fo.body, visit);
+        }
+        Stmt::Match(ma) => {
+            visit_expr(&ma.value, visit);
+            for arm in &ma.arms {
+                visit_stmt(&arm.body, visit);
+            }
+        }
+        Stmt::Print(p) => visit_expr(&p.value, visit),
+        Stmt::Return(ret) => {
+            if let Some(value) = &ret.value {
+                visit_expr(value, visit);
+            }
+        }
+        Stmt::Expression(expr) => visit_expr(expr, visit),
+        Stmt::Break(_) | Stmt::Continue(_) | Stmt::Error(_) => {}
+    }
+}
+
+fn visit_expr<'a>(expr: &'a Expr, visit: &mut impl FnMut(&'a Expr)) {
+    visit(expr);
+    match expr {
+        Expr::Binary(bin) => {
+            visit_expr(&bin.left, visit);
+            visit_expr(&bin.right, visit);
+        }
+        Expr::Call(call) => {
+            visit_expr(&call.callee, visit);
+            for arg in &call.args {
+                visit_expr(arg, visit);
+            }
+        }
+        Expr::NativeCall(call) => {
+            for arg in &call.args {
+                visit_expr(arg, visit);
+            }
+        }
+        Expr::FieldAccess(fiac) => visit_expr(&fiac.parent, visit),
+        Expr::Unary(un) => visit_expr(&un.operand, visit),
+        Expr::Grouping(gr) => visit_expr(&gr.value, visit),
+        Expr::Assignment(ass) => {
+            visit_lvalue(&ass.target, visit);
+            visit_expr(&ass.value, visit);
+        }
+        Expr::StructInit(st_init) => {
+            for arg in &st_init.arguments {
+                visit_expr(&arg.field_expr, visit);
+            }
+        }
+        Expr::Index(idx) => {
+            visit_expr(&idx.object, visit);
+            visit_expr(&idx.index, visit);
+        }
+        Expr::ArrayInit(arr) => {
+            for element in &arr.elements {
+                visit_expr(element, visit);
+            }
+        }
+        Expr::AddrOf(addr) => visit_lvalue(&addr.target, visit),
+        Expr::Deref(deref) => visit_expr(&deref.pointer, visit),
+        Expr::Variable(_)
+        | Expr::Literal(_)
+        | Expr::VecNew(_)
+        | Expr::EnumVariant(_)
+        | Expr::Error(_) => {}
+    }
+}
+
+/// Visits expressions inside a place, the place itself isn't an expression.
+fn visit_lvalue<'a>(target: &'a LValue, visit: &mut impl FnMut(&'a Expr)) {
+    match target {
+        LValue::FieldAccess(fiac) => visit_expr(&fiac.parent, visit),
+        LValue::Index(idx) => {
+            visit_expr(&idx.object, visit);
+            visit_expr(&idx.index, visit);
+        }
+        LValue::Deref(deref) => visit_expr(&deref.pointer, visit),
+        LValue::Variable(_) => {}
+    }
+}
+
+/// Calls 'visit' with every type written in the given statements,
+/// annotations of let bindings, element types of vectors and result types of extern calls.
+pub fn visit_types_mut(stmts: &mut [Stmt], visit: &mut impl FnMut(&mut DType)) {
+    for stmt in stmts {
+        visit_stmt_types(stmt, visit);
+    }
+}
+
+fn visit_stmt_types(stmt: &mut Stmt, visit: &mut impl FnMut(&mut DType)) {
+    match stmt {
+        Stmt::Block(block) => visit_types_mut(&mut block.stmts, visit),
+        Stmt::Let(le) => {
+            if let Some(ty) = &mut le.ty {
+                visit(ty);
+            }
+            visit_expr_types(&mut le.initialiser, visit);
+        }
+        Stmt::If(ifst) => {
+            visit_expr_types(&mut ifst.condition, visit);
+            visit_stmt_types(&mut ifst.if_branch, visit);
+            if let Some(else_branch) = &mut ifst.else_branch {
+                visit_stmt_types(else_branch, visit);
+            }
+        }
+        Stmt::Loop(lo) => visit_stmt_types(&mut lo.body, visit),
+        Stmt::While(wh) => {
+            visit_expr_types(&mut wh.condition, visit);
+            visit_stmt_types(&mut wh.body, visit);
+        }
+        Stmt::For(fo) => {
+            if let Some(initialiser) = &mut fo.initialiser {
+                visit_stmt_types(initialiser, visit);
+            }
+            for expr in fo.condition.iter_mut().chain(fo.increment.iter_mut()) {
+                visit_expr_types(expr, visit);
+            }
+            visit_stmt_types(&mut fo.body, visit);
+        }
+        Stmt::Match(ma) => {
+            visit_expr_types(&mut ma.value, visit);
+            for arm in &mut ma.arms {
+                visit_stmt_types(&mut arm.body, visit);
+            }
+        }
+        Stmt::Print(p) => visit_expr_types(&mut p.value, visit),
+        Stmt::Return(ret) => {
+            if let Some(value) = &mut ret.value {
+                visit_expr_types(value, visit);
+            }
+        }
+        Stmt::Expression(expr) => visit_expr_types(expr, visit),
+        Stmt::Break(_) | Stmt::Continue(_) | Stmt::Error(_) => {}
+    }
+}
+
+fn visit_expr_types(expr: &mut Expr, visit: &mut impl FnMut(&mut DType)) {
+    match expr {
+        Expr::Binary(bin) => {
+            visit_expr_types(&mut bin.left, visit);
+            visit_expr_types(&mut bin.right, visit);
+        }
+        Expr::Call(call) => {
+            visit_expr_types(&mut call.callee, visit);
+            for arg in &mut call.args {
+                visit_expr_types(arg, visit);
+            }
+        }
+        Expr::NativeCall(call) => {
+            if let Some(ty) = &mut call.return_type {
+                visit(ty);
+            }
+            for arg in &mut call.args {
+                visit_expr_types(arg, visit);
+            }
+        }
+        Expr::FieldAccess(fiac) => visit_expr_types(&mut fiac.parent, visit),
+        Expr::Unary(un) => visit_expr_types(&mut un.operand, visit),
+        Expr::Grouping(gr) => visit_expr_types(&mut gr.value, visit),
+        Expr::Assignment(ass) => {
+            visit_lvalue_types(&mut ass.target, visit);
+            visit_expr_types(&mut ass.value, visit);
+        }
+        Expr::StructInit(st_init) => {
+            for arg in &mut st_init.arguments {
+                visit_expr_types(&mut arg.field_expr, visit);
+            }
+        }
+        Expr::Index(idx) => {
+            visit_expr_types(&mut idx.object, visit);
+            visit_expr_types(&mut idx.index, visit);
+        }
+        Expr::ArrayInit(arr) => {
+            for element in &mut arr.elements {
+                visit_expr_types(element, visit);
+            }
+        }
+        Expr::VecNew(vec) => visit(&mut vec.elem_type),
+        Expr::AddrOf(addr) => visit_lvalue_types(&mut addr.target, visit),
+        Expr::Deref(deref) => visit_expr_types(&mut deref.pointer, visit),
+        Expr::Variable(_) | Expr::Literal(_) | Expr::EnumVariant(_) | Expr::Error(_) => {}
+    }
+}
+
+fn visit_lvalue_types(target: &mut LValue, visit: &mut impl FnMut(&mut DType)) {
+    match target {
+        LValue::FieldAccess(fiac) => visit_expr_types(&mut fiac.parent, visit),
+        LValue::Index(idx) => {
+            visit_expr_types(&mut idx.object, visit);
+            visit_expr_types(&mut idx.index, visit);
+        }
+        LValue::Deref(deref) => visit_expr_types(&mut deref.pointer, visit),
+        LValue::Variable(_) => {}
+    }
+}
@@ -1,10 +1,23 @@
-use std::{path::Path, sync::Arc};
+use std::{fs, path::Path, sync::Arc};
 
 use std::process::{Command, Output};
 
 use anyhow::Context;
 use log::debug;
 
+/// C source of the runtime library, it backs vectors.
+pub const RUNTIME_SOURCE: &str = include_str!("runtime.c");
+
+/// Writes source of the runtime library to 'path', so that it can be compiled along with a program.
+pub fn write_runtime<P: AsRef<Path>>(path: P) -> anyhow::Result<()> {
+    fs::write(path.as_ref(), RUNTIME_SOURCE).with_context(|| {
+        format!(
+            "Failed to write runtime library to '{}'",
+            path.as_ref().display()
+        )
+    })
+}
+
 pub struct CCFlags {
     c_compiler: String,
     opt_level: i8,
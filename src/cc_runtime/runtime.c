@@ -0,0 +1,65 @@
+/*
+ * Runtime library of evelin, compiled and linked along with every program.
+ * Names start with '___eve_' so they don't clash with user functions.
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* Growable vector, elements are stored inline one after another. */
+typedef struct {
+    char *data;
+    long len;
+    long cap;
+    long elem_size;
+} EveVec;
+
+static void ___eve_fail(const char *msg) {
+    fprintf(stderr, "%s\n", msg);
+    exit(1);
+}
+
+/* Creates an empty vector of elements which are 'elem_size' bytes large. */
+EveVec *___eve_vec_new(long elem_size) {
+    EveVec *vec = calloc(1, sizeof(EveVec));
+    if (vec == NULL) {
+        ___eve_fail("out of memory");
+    }
+    vec->elem_size = elem_size;
+    return vec;
+}
+
+/* Appends an uninitialised element, returns its address for the caller to fill in. */
+void *___eve_vec_push(EveVec *vec) {
+    if (vec->len == vec->cap) {
+        long cap = vec->cap == 0 ? 4 : vec->cap * 2;
+        char *data = realloc(vec->data, cap * vec->elem_size);
+        if (data == NULL) {
+            ___eve_fail("out of memory");
+        }
+        vec->data = data;
+        vec->cap = cap;
+    }
+    return vec->data + vec->len++ * vec->elem_size;
+}
+
+/* Removes the last element, returns its address which stays valid until the next push. */
+void *___eve_vec_pop(EveVec *vec) {
+    if (vec->len == 0) {
+        ___eve_fail("cannot pop from an empty vector");
+    }
+    return vec->data + --vec->len * vec->elem_size;
+}
+
+int ___eve_vec_len(EveVec *vec) { return (int)vec->len; }
+
+/* Returns address of the element at 'index', exits if it is out of bounds. */
+void *___eve_vec_get(EveVec *vec, int index) {
+    if (index < 0 || index >= vec->len) {
+        fprintf(stderr, "index %d is out of bounds for vector of length %ld\n",
+                index, vec->len);
+        exit(1);
+    }
+    return vec->data + (long)index * vec->elem_size;
+}
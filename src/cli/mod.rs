@@ -18,6 +18,20 @@ pub enum DebugTypes {
     Trace,
 }
 
+/// How diagnostics are printed.
+#[derive(clap::ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// source snippets with underlined spans.
+    #[default]
+    Human,
+
+    /// a single line per diagnostic.
+    Short,
+
+    /// a json object per line, for tools.
+    Json,
+}
+
 #[derive(Parser, Debug)]
 #[command(arg_required_else_help = true)]
 #[command(version, about= "The Evelin Programming Language", long_about = None)]
@@ -47,6 +61,15 @@ pub struct EveCliOptions {
     /// External library directory passed to the linker as -L<path_1> -L<path_2>
     #[arg(short = 'L', long = "lib_path", value_delimiter = ' ', num_args = 1.. )]
     pub lib_path: Option<Vec<String>>,
+
+    /// Format of the reported errors and warnings
+    #[clap(value_enum, default_value_t = ErrorFormat::Human)]
+    #[arg(long = "error-format")]
+    pub error_format: ErrorFormat,
+
+    /// Check array indices at runtime, out of bounds accesses exit the program
+    #[arg(long = "bounds-check")]
+    pub bounds_check: bool,
 }
 
 pub fn init() -> anyhow::Result<EveCliOptions> {
@@ -0,0 +1,199 @@
+mod render;
+
+pub use render::Reporter;
+
+use crate::source_map::Span;
+
+/// How serious a diagnostic is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            }
+        )
+    }
+}
+
+/// A span of source code with an optional message attached to it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub span: Span,
+
+    // message printed next to the underline, can be empty.
+    pub message: String,
+
+    // primary labels point to the cause of the diagnostic,
+    // secondary labels add context to it.
+    pub primary: bool,
+}
+
+/// A replacement for a span of source code which might fix the diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub span: Span,
+    pub message: String,
+    pub replacement: String,
+}
+
+/// A message reported to the user, either an error or a warning.
+///
+/// Error codes:
+/// * `E0001` - illegal character
+/// * `E0002` - unterminated string
+/// * `E0003` - invalid escape sequence
+/// * `E0004` - number literal out of range
+/// * `E0005` - invalid number literal
+/// * `E0006` - unterminated block comment
+/// * `E0200` - missing main function
+/// * `E0201` - call to undefined function
+/// * `E0202` - field initialised more than once
+/// * `E0203` - missing field in struct initialiser
+/// * `E0204` - unknown field in struct initialiser
+/// * `E0205` - recursive struct
+/// * `E0300` - mismatched types
+/// * `E0301` - undefined variable
+/// * `E0302` - field access on a non struct value
+/// * `E0303` - undefined struct
+/// * `E0304` - struct has no such field
+/// * `E0305` - wrong number of arguments in function call
+/// * `E0306` - parameter declared more than once
+/// * `E0307` - break or continue outside of a loop
+/// * `E0308` - use of undeclared loop label
+/// * `E0309` - loop label already in use by an outer loop
+/// * `E0310` - match doesn't cover every value
+/// * `E0311` - match arm can never be taken
+/// * `E0312` - assignment to a constant
+/// * `E0313` - initialiser of a constant or static isn't a constant expression
+/// * `E0314` - constant or static declared more than once
+/// * `E0315` - indexing a value which isn't an array or a vector
+/// * `E0316` - constant array index out of bounds
+/// * `E0317` - call to a method which doesn't exist
+/// * `E0318` - address of a constant
+/// * `E0319` - dereferencing a value which isn't a typed pointer
+/// * `E0320` - enum or variant declared more than once
+/// * `E0321` - undefined enum or variant
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl Diagnostic {
+    pub fn new<M: Into<String>>(severity: Severity, message: M) -> Self {
+        Self {
+            severity,
+            code: None,
+            message: message.into(),
+            labels: vec![],
+            notes: vec![],
+            help: None,
+            suggestions: vec![],
+        }
+    }
+
+    /// Creates a new error diagnostic.
+    pub fn error<M: Into<String>>(message: M) -> Self {
+        Self::new(Severity::Error, message)
+    }
+
+    /// Creates a new warning diagnostic.
+    pub fn warning<M: Into<String>>(message: M) -> Self {
+        Self::new(Severity::Warning, message)
+    }
+
+    /// Sets error code.
+    pub fn with_code(mut self, code: &'static str) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// Adds a label pointing to the cause of the diagnostic.
+    pub fn primary<M: Into<String>>(mut self, span: Span, message: M) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: true,
+        });
+        self
+    }
+
+    /// Adds a label giving context to the diagnostic.
+    pub fn secondary<M: Into<String>>(mut self, span: Span, message: M) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+            primary: false,
+        });
+        self
+    }
+
+    /// Adds a note.
+    pub fn with_note<M: Into<String>>(mut self, note: M) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    /// Sets help message.
+    pub fn with_help<M: Into<String>>(mut self, help: M) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    /// Adds a suggested replacement for the given span.
+    pub fn with_suggestion<M: Into<String>, R: Into<String>>(
+        mut self,
+        span: Span,
+        message: M,
+        replacement: R,
+    ) -> Self {
+        self.suggestions.push(Suggestion {
+            span,
+            message: message.into(),
+            replacement: replacement.into(),
+        });
+        self
+    }
+
+    /// Returns span of the first primary label.
+    pub fn primary_span(&self) -> Option<Span> {
+        self.labels.iter().find(|l| l.primary).map(|l| l.span)
+    }
+
+    /// Converts an anyhow error into a diagnostic.
+    /// Errors which aren't diagnostics are pointed at the `fallback` span.
+    /// * `err` - the error.
+    /// * `fallback` - span used when the error doesn't point anywhere.
+    pub fn from_anyhow(err: &anyhow::Error, fallback: Span) -> Self {
+        match err.downcast_ref::<Diagnostic>() {
+            Some(diag) => Diagnostic {
+                // keep context added on top of the diagnostic.
+                message: format!("{:#}", err),
+                ..diag.clone()
+            },
+            None => Diagnostic::error(format!("{:#}", err)).primary(fallback, ""),
+        }
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
@@ -0,0 +1,319 @@
+use colored::{ColoredString, Colorize};
+use log::{error, warn};
+use serde_json::{Value, json};
+
+use crate::cli::ErrorFormat;
+use crate::source_map::{FileId, SourceMap, Span};
+
+use super::{Diagnostic, Label, Severity};
+
+/// Prints diagnostics to stderr.
+pub struct Reporter<'a> {
+    source_map: &'a SourceMap,
+    format: ErrorFormat,
+}
+
+impl<'a> Reporter<'a> {
+    pub fn new(source_map: &'a SourceMap, format: ErrorFormat) -> Self {
+        Self { source_map, format }
+    }
+
+    /// Reports a single diagnostic.
+    pub fn report(&self, diag: &Diagnostic) {
+        match diag.severity {
+            Severity::Error => error!("{}", diag.message),
+            Severity::Warning => warn!("{}", diag.message),
+        }
+        match self.format {
+            ErrorFormat::Human => eprintln!("{}\n", self.render(diag)),
+            ErrorFormat::Short | ErrorFormat::Json => eprintln!("{}", self.render(diag)),
+        }
+    }
+
+    /// Renders a diagnostic in the reporter's format.
+    pub fn render(&self, diag: &Diagnostic) -> String {
+        match self.format {
+            ErrorFormat::Human => render_human(diag, self.source_map),
+            ErrorFormat::Short => render_short(diag, self.source_map),
+            ErrorFormat::Json => render_json(diag, self.source_map).to_string(),
+        }
+    }
+
+    /// Reports all given diagnostics in order.
+    pub fn report_all(&self, diags: &[Diagnostic]) {
+        for diag in diags {
+            self.report(diag);
+        }
+    }
+}
+
+/// Renders a diagnostic along with the source lines it points to.
+/// ```text
+/// error[E0201]: Call to undefined function 'foo'
+///  --> main.eve:4:3
+///   |
+/// 4 |   foo(1);
+///   |   ^^^
+/// ```
+/// * `diag` - diagnostic to render.
+/// * `source_map` - used to find the source of labels.
+fn render_human(diag: &Diagnostic, source_map: &SourceMap) -> String {
+    let mut out = String::new();
+
+    let code = diag.code.map(|c| format!("[{}]", c)).unwrap_or_default();
+    out.push_str(&format!(
+        "{}: {}",
+        paint(diag.severity, &format!("{}{}", diag.severity, code)),
+        diag.message.bold()
+    ));
+
+    let width = diag
+        .labels
+        .iter()
+        .map(|l| l.span.line.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let pad = " ".repeat(width);
+
+    for (i, file_id) in label_files(diag).into_iter().enumerate() {
+        let mut labels: Vec<&Label> = diag
+            .labels
+            .iter()
+            .filter(|l| l.span.file_id == file_id)
+            .collect();
+        labels.sort_by_key(|l| (l.span.line, l.span.byte_start));
+
+        let first = labels.iter().find(|l| l.primary).unwrap_or(&labels[0]).span;
+        let arrow = if i == 0 { "-->" } else { ":::" };
+        out.push_str(&format!(
+            "\n{}{} {}",
+            pad,
+            arrow.blue().bold(),
+            source_map.location(&first)
+        ));
+
+        let Some(file) = source_map.get(file_id) else {
+            continue;
+        };
+
+        out.push_str(&format!("\n{} {}", pad, "|".blue().bold()));
+        let mut lines: Vec<usize> = labels.iter().map(|l| l.span.line).collect();
+        lines.dedup();
+        for line in lines {
+            let line_labels: Vec<&&Label> = labels.iter().filter(|l| l.span.line == line).collect();
+            let Some((line_start, text)) = line_text(&file.src, line_labels[0].span.byte_start)
+            else {
+                continue;
+            };
+
+            out.push_str(&format!(
+                "\n{} {} {}",
+                format!("{:>width$}", line, width = width).blue().bold(),
+                "|".blue().bold(),
+                text
+            ));
+
+            for label in line_labels {
+                out.push_str(&format!(
+                    "\n{} {} {}",
+                    pad,
+                    "|".blue().bold(),
+                    underline(diag.severity, label, line_start, text)
+                ));
+            }
+        }
+    }
+
+    let has_trailer = !diag.notes.is_empty() || diag.help.is_some() || !diag.suggestions.is_empty();
+    if !diag.labels.is_empty() && has_trailer {
+        out.push_str(&format!("\n{} {}", pad, "|".blue().bold()));
+    }
+
+    for note in &diag.notes {
+        out.push_str(&format!(
+            "\n{} {} {}",
+            pad,
+            "=".blue().bold(),
+            note_text("note", note)
+        ));
+    }
+
+    if let Some(help) = &diag.help {
+        out.push_str(&format!(
+            "\n{} {} {}",
+            pad,
+            "=".blue().bold(),
+            note_text("help", help)
+        ));
+    }
+
+    for suggestion in &diag.suggestions {
+        let help = format!("{}: `{}`", suggestion.message, suggestion.replacement);
+        out.push_str(&format!(
+            "\n{} {} {}",
+            pad,
+            "=".blue().bold(),
+            note_text("help", &help)
+        ));
+    }
+
+    out
+}
+
+/// Renders a diagnostic on a single line.
+/// ```text
+/// main.eve:4:3: error[E0201]: Call to undefined function 'foo'
+/// ```
+fn render_short(diag: &Diagnostic, source_map: &SourceMap) -> String {
+    let code = diag.code.map(|c| format!("[{}]", c)).unwrap_or_default();
+    let header = format!(
+        "{}: {}",
+        paint(diag.severity, &format!("{}{}", diag.severity, code)),
+        diag.message
+    );
+
+    match diag.primary_span() {
+        Some(span) => format!("{}: {}", source_map.location(&span), header),
+        None => header,
+    }
+}
+
+/// Renders a diagnostic as a json object, file and span are taken from the primary label.
+/// ```text
+/// {"file":"main.eve","span":{..},"severity":"error","code":"E0201","message":"..",..}
+/// ```
+fn render_json(diag: &Diagnostic, source_map: &SourceMap) -> Value {
+    let primary = diag.primary_span();
+    let labels: Vec<Value> = diag
+        .labels
+        .iter()
+        .map(|l| {
+            json!({
+                "file": json_file(&l.span, source_map),
+                "span": json_span(&l.span),
+                "message": l.message,
+                "primary": l.primary,
+            })
+        })
+        .collect();
+    let suggestions: Vec<Value> = diag
+        .suggestions
+        .iter()
+        .map(|s| {
+            json!({
+                "file": json_file(&s.span, source_map),
+                "span": json_span(&s.span),
+                "message": s.message,
+                "replacement": s.replacement,
+            })
+        })
+        .collect();
+
+    json!({
+        "file": primary.and_then(|s| json_file(&s, source_map)),
+        "span": primary.map(|s| json_span(&s)),
+        "severity": diag.severity.to_string(),
+        "code": diag.code,
+        "message": diag.message,
+        "labels": labels,
+        "notes": diag.notes,
+        "help": diag.help,
+        "suggestions": suggestions,
+    })
+}
+
+fn json_file(span: &Span, source_map: &SourceMap) -> Option<String> {
+    source_map
+        .path(span.file_id)
+        .map(|p| p.display().to_string())
+}
+
+fn json_span(span: &Span) -> Value {
+    json!({
+        "byte_start": span.byte_start,
+        "byte_end": span.byte_end,
+        "line": span.line,
+        "col": span.col,
+    })
+}
+
+/// Returns ids of all files labels point to,
+/// file of the primary label comes first.
+fn label_files(diag: &Diagnostic) -> Vec<FileId> {
+    let mut files: Vec<FileId> = vec![];
+    if let Some(span) = diag.primary_span() {
+        files.push(span.file_id);
+    }
+
+    for label in &diag.labels {
+        if !files.contains(&label.span.file_id) {
+            files.push(label.span.file_id);
+        }
+    }
+    files
+}
+
+/// Returns start position and text of the line containing `pos`.
+fn line_text(src: &str, pos: usize) -> Option<(usize, &str)> {
+    let pos = pos.min(src.len());
+    if !src.is_char_boundary(pos) {
+        return None;
+    }
+
+    let start = src[..pos].rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let end = src[pos..].find('\n').map(|i| pos + i).unwrap_or(src.len());
+    Some((start, src[start..end].trim_end_matches('\r')))
+}
+
+/// Builds the marker line under source text for a label.
+fn underline(severity: Severity, label: &Label, line_start: usize, text: &str) -> String {
+    let start = label
+        .span
+        .byte_start
+        .saturating_sub(line_start)
+        .min(text.len());
+    let end = label
+        .span
+        .byte_end
+        .saturating_sub(line_start)
+        .clamp(start, text.len());
+
+    // keep tabs so markers line up with the source text.
+    let prefix: String = text
+        .get(..start)
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let count = text
+        .get(start..end)
+        .unwrap_or_default()
+        .chars()
+        .count()
+        .max(1);
+
+    let marks = if label.primary {
+        paint(severity, &"^".repeat(count))
+    } else {
+        "-".repeat(count).blue().bold()
+    };
+
+    if label.message.is_empty() {
+        format!("{}{}", prefix, marks)
+    } else if label.primary {
+        format!("{}{} {}", prefix, marks, paint(severity, &label.message))
+    } else {
+        format!("{}{} {}", prefix, marks, label.message.blue().bold())
+    }
+}
+
+fn note_text(kind: &str, text: &str) -> String {
+    format!("{}: {}", kind.bold(), text)
+}
+
+fn paint(severity: Severity, text: &str) -> ColoredString {
+    match severity {
+        Severity::Error => text.red().bold(),
+        Severity::Warning => text.yellow().bold(),
+    }
+}
@@ -1,6 +1,8 @@
 use std::{cmp, collections::HashMap};
 
-use crate::ast::{DType, StructDecl};
+use crate::ast::{DType, Expr, GlobalDecl, LiteralValue, StructDecl};
+use crate::emitter::EmitterResult;
+use anyhow::bail;
 use log::debug;
 use qbe;
 
@@ -10,8 +12,13 @@ use super::QBEEmitter;
 pub(super) type StructMeta = HashMap<String, (qbe::Type<'static>, u64)>;
 
 impl QBEEmitter<'_> {
-    /// Emits a single function
-    pub(super) fn emit_data_def(&mut self, struct_decl: &StructDecl) {
+    /// Emits type definition for a struct,
+    /// structs used by its fields are emitted before it.
+    pub(super) fn emit_data_def(&mut self, struct_decl: &StructDecl) -> EmitterResult<()> {
+        if self.struct_meta.contains_key(&struct_decl.name) {
+            return Ok(());
+        }
+
         self.tmp_counter += 1;
         let mut typedef = qbe::TypeDef {
             name: struct_decl.name.clone(),
@@ -25,12 +32,14 @@ impl QBEEmitter<'_> {
 
         for field in &struct_decl.fields {
             let ty = match &field.field_type {
-                DType::Primitive(ttype) => qbe::Type::try_from(*ttype).unwrap(),
-                DType::Derived(_) => {
-                    panic!(
-                        "Aggregate type for struct fields is not supported yet, line {}",
-                        struct_decl.metadata.line
-                    )
+                DType::Primitive(ttype) => qbe::Type::try_from(*ttype)?,
+                ty => {
+                    if let Some(name) = ty.inline_struct()
+                        && let Some(decl) = self.struct_decls.iter().find(|x| x.name == name)
+                    {
+                        self.emit_data_def(decl)?;
+                    }
+                    self.qbe_type(ty, field.metadata.span)?
                 }
             };
             let field_align = Self::type_alignment(&ty);
@@ -55,6 +64,75 @@ impl QBEEmitter<'_> {
 
         self.type_defs.push(typedef.clone());
         self.module.add_type(typedef);
+        Ok(())
+    }
+
+    /// Emits data definition for a static, its initialiser is already evaluated by the type system.
+    pub(super) fn emit_static_def(&mut self, global: &GlobalDecl) -> EmitterResult<()> {
+        debug!("emitting static: '{}'", &global.name);
+        let Expr::Literal(lit) = &global.value else {
+            bail!(
+                "Initialiser of static '{}' is not evaluated, at {}",
+                global.name,
+                global.metadata.span
+            );
+        };
+
+        let item = match &lit.value {
+            // words are stored as their 32 bit pattern.
+            LiteralValue::NumberInt(v) => (qbe::Type::Word, qbe::DataItem::Const(*v as u32 as u64)),
+            LiteralValue::NumberFloat(v) => (qbe::Type::Double, qbe::DataItem::Const(v.to_bits())),
+            LiteralValue::String(v) => {
+                let glob_name = self.new_glob_name();
+                self.module.add_data(qbe::DataDef::new(
+                    qbe::Linkage::private(),
+                    glob_name.clone(),
+                    None,
+                    Self::string_data_items(v),
+                ));
+                (qbe::Type::Long, qbe::DataItem::Symbol(glob_name, None))
+            }
+            LiteralValue::Boolean(b) => (qbe::Type::Word, qbe::DataItem::Const(*b as u64)),
+            LiteralValue::Null => (qbe::Type::Word, qbe::DataItem::Const(0)),
+        };
+
+        let def = self.module.add_data(qbe::DataDef::new(
+            qbe::Linkage::private(),
+            format!("static.{}", global.name),
+            None,
+            vec![item.clone()],
+        ));
+        let address = qbe::Value::Global(def.name.clone());
+        self.globals.insert(global.name.clone(), (item.0, address));
+        Ok(())
+    }
+
+    /// Returns data items for a null terminated string.
+    /// Printable ascii is emitted as string items,
+    /// everything else as single bytes so that it doesn't depend on the assembler's escapes.
+    pub(super) fn string_data_items(value: &str) -> Vec<(qbe::Type<'static>, qbe::DataItem)> {
+        let mut items = vec![];
+        let mut run = String::new();
+        for byte in value.bytes() {
+            if (b' '..=b'~').contains(&byte) && byte != b'"' && byte != b'\\' {
+                run.push(byte as char);
+                continue;
+            }
+
+            if !run.is_empty() {
+                items.push((
+                    qbe::Type::Byte,
+                    qbe::DataItem::Str(std::mem::take(&mut run)),
+                ));
+            }
+            items.push((qbe::Type::Byte, qbe::DataItem::Const(byte as u64)));
+        }
+
+        if !run.is_empty() {
+            items.push((qbe::Type::Byte, qbe::DataItem::Str(run)));
+        }
+        items.push((qbe::Type::Byte, qbe::DataItem::Const(0)));
+        items
     }
 
     // Emits initialization data definition
@@ -99,5 +177,19 @@ impl QBEEmitter<'_> {
                 (qbe::Type::Byte, qbe::DataItem::Const(0)),
             ],
         ));
+
+        self.module.add_data(qbe::DataDef::new(
+            qbe::Linkage::private(),
+            "___FMT_BOUNDS",
+            None,
+            vec![
+                (
+                    qbe::Type::Byte,
+                    qbe::DataItem::Str("index %d is out of bounds for array of length %d".into()),
+                ),
+                (qbe::Type::Byte, qbe::DataItem::Const(10)),
+                (qbe::Type::Byte, qbe::DataItem::Const(0)),
+            ],
+        ));
     }
 }
@@ -0,0 +1,135 @@
+use crate::ast::{EnumDecl, EnumVariantExpr};
+use crate::emitter::EmitterResult;
+use crate::source_map::Span;
+use anyhow::Context;
+use log::trace;
+use qbe;
+
+use super::QBEEmitter;
+
+// Enums are words holding the value of their variant,
+// each enum gets a function which maps values back to names of variants for printing.
+impl QBEEmitter<'_> {
+    /// Emits the value of an enum variant.
+    pub(super) fn emit_enum_variant(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        var: &EnumVariantExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting enum variant = {:?}", var);
+        let value = self.variant_value(&var.enum_name, &var.variant, var.metadata.span)?;
+
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            qbe::Type::Word,
+            // words are written as their 32 bit pattern, like values of statics.
+            qbe::Instr::Copy(qbe::Value::Const(value as u32 as u64)),
+        );
+        Ok((qbe::Type::Word, tmp))
+    }
+
+    /// Returns the value of the variant 'enum_name::variant'.
+    pub(super) fn variant_value(
+        &self,
+        enum_name: &str,
+        variant: &str,
+        span: Span,
+    ) -> EmitterResult<i32> {
+        self.enum_decls
+            .iter()
+            .find(|x| x.name == enum_name)
+            .and_then(|decl| decl.variants.iter().find(|x| x.name == variant))
+            .map(|variant| variant.value)
+            .with_context(|| {
+                format!(
+                    "Use of undeclared variant '{}::{}', at {}",
+                    enum_name, variant, span
+                )
+            })
+    }
+
+    /// Emits a call to the function returning the name of the variant 'value' holds.
+    pub(super) fn emit_variant_name(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        enum_name: &str,
+        value: qbe::Value,
+    ) -> qbe::Value {
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Call(
+                Self::variant_name_fn(enum_name),
+                vec![(qbe::Type::Word, value)],
+                None,
+            ),
+        );
+        tmp
+    }
+
+    /// Emits the function mapping values of an enum to names of its variants,
+    /// values which don't belong to any variant are named '<invalid>'.
+    pub(super) fn emit_variant_name_fn(&mut self, decl: &EnumDecl) {
+        trace!("emitting variant names of enum '{}'", &decl.name);
+        let value = qbe::Value::Temporary("value".into());
+        let mut func = qbe::Function::new(
+            qbe::Linkage::private(),
+            Self::variant_name_fn(&decl.name),
+            vec![(qbe::Type::Word, value.clone())],
+            Some(qbe::Type::Long),
+        );
+        func.add_block("start");
+
+        for variant in &decl.variants {
+            let name = self.module.add_data(qbe::DataDef::new(
+                qbe::Linkage::private(),
+                format!("enum.{}.{}", decl.name, variant.name),
+                None,
+                Self::string_data_items(&variant.name),
+            ));
+            let name = qbe::Value::Global(name.name.clone());
+
+            let is_variant = self.new_tmp();
+            func.assign_instr(
+                is_variant.clone(),
+                qbe::Type::Word,
+                qbe::Instr::Cmp(
+                    qbe::Type::Word,
+                    qbe::Cmp::Eq,
+                    value.clone(),
+                    qbe::Value::Const(variant.value as u32 as u64),
+                ),
+            );
+
+            let found_label = format!("variant.{}", variant.name);
+            let next_label = format!("not.{}", variant.name);
+            func.add_instr(qbe::Instr::Jnz(
+                is_variant,
+                found_label.clone(),
+                next_label.clone(),
+            ));
+            func.add_block(found_label);
+            func.add_instr(qbe::Instr::Ret(Some(name)));
+            func.add_block(next_label);
+        }
+
+        let invalid = self.module.add_data(qbe::DataDef::new(
+            qbe::Linkage::private(),
+            format!("enum.{}", decl.name),
+            None,
+            Self::string_data_items("<invalid>"),
+        ));
+        func.add_instr(qbe::Instr::Ret(Some(qbe::Value::Global(
+            invalid.name.clone(),
+        ))));
+
+        self.module.add_function(func);
+    }
+
+    /// Name of the function returning names of variants of an enum.
+    fn variant_name_fn(enum_name: &str) -> String {
+        format!("enum_name.{}", enum_name)
+    }
+}
@@ -1,9 +1,11 @@
 use crate::ast::{
-    AssignmentExpr, BinExpr, BinOp, CallExpr, Expr, FieldAccessExpr, GroupExpr, LiteralExpr,
-    LiteralValue, NativeCallExpr, UnOp, UnaryExpr, VariableExpr,
+    ArrayInitExpr, AssignKind, AssignmentExpr, BinExpr, BinOp, CallExpr, DType, EveTypes, Expr,
+    FieldAccessExpr, GroupExpr, IndexExpr, LValue, LiteralExpr, LiteralValue, NativeCallExpr,
+    StructInitExpr, UnOp, UnaryExpr, VariableExpr,
 };
+use crate::bail_at;
 use crate::emitter::EmitterResult;
-use anyhow::{Context, bail};
+use anyhow::Context;
 use log::{error, trace};
 use qbe;
 
@@ -26,24 +28,168 @@ impl QBEEmitter<'_> {
             Expr::Unary(una) => self.emit_unary(func, una),
             Expr::Grouping(gro) => self.emit_grouping(func, gro),
             Expr::Literal(lit) => self.emit_literal(func, lit),
-            Expr::Variable(var) => self.emit_variable(var),
+            Expr::Variable(var) => self.emit_variable(func, var),
+            Expr::StructInit(st_init) => self.emit_struct_init(func, st_init),
+            Expr::Index(idx) => self.emit_index(func, idx),
+            Expr::ArrayInit(arr) => self.emit_array_init(func, arr),
+            Expr::VecNew(vec) => self.emit_vec_new(func, vec),
+            Expr::AddrOf(addr) => self.emit_addr_of(func, addr),
+            Expr::Deref(deref) => self.emit_deref(func, deref),
+            Expr::EnumVariant(var) => self.emit_enum_variant(func, var),
+            Expr::Error(err) => bail_at!(err.metadata.span, "Cannot emit an erroneous expression"),
         }
     }
 
-    /// Emits variable reassignment
+    /// Emits reassignment of a variable or a struct field
     fn emit_assignment(
         &mut self,
         func: &mut qbe::Function<'static>,
         ass: &AssignmentExpr,
     ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
-        let (ty, value) = self.emit_expr(func, &ass.value)?;
-        let result_value = self.get_var(&ass.name)?;
+        if let AssignKind::Compound(op) | AssignKind::Postfix(op) = ass.kind {
+            return self.emit_update(func, ass, op);
+        }
+
+        let (mut ty, mut value) = self.emit_expr(func, &ass.value)?;
+        // ints assigned to floats are converted, the assignment has the type of the place.
+        if matches!(
+            ass.metadata.node_type,
+            Some(DType::Primitive(EveTypes::Float))
+        ) && !matches!(ty, qbe::Type::Double)
+        {
+            value = self.emit_int_to_float(func, value);
+            ty = qbe::Type::Double;
+        }
+        match &ass.target {
+            LValue::Variable(var) => {
+                let (var_ty, var_value) = self.get_var(&var.name)?.clone();
+                if matches!(var_ty, qbe::Type::Aggregate(_)) {
+                    // structs and arrays are copied into the variable's own storage.
+                    self.emit_struct_copy(func, &var_ty, value.clone(), var_value);
+                } else if self.is_in_memory(&var.name, &var_ty, &var_value) {
+                    func.add_instr(qbe::Instr::Store(var_ty, var_value, value.clone()));
+                } else {
+                    func.assign_instr(
+                        var_value,
+                        Self::tmp_type(&ty),
+                        qbe::Instr::Copy(value.clone()),
+                    );
+                }
+            }
+            LValue::FieldAccess(fiac) => {
+                let (field_ty, field_ptr) = self.emit_field_ptr(func, fiac)?;
+                self.emit_store(func, &field_ty, field_ptr, value.clone());
+            }
+            LValue::Index(idx) => {
+                let (elem_ty, elem_ptr) = self.emit_index_ptr(func, idx)?;
+                self.emit_store(func, &elem_ty, elem_ptr, value.clone());
+            }
+            LValue::Deref(deref) => {
+                let Some(pointee) = &deref.metadata.node_type else {
+                    bail_at!(deref.metadata.span, "Dereference without a type");
+                };
+                let pointee_ty = self.qbe_type(pointee, deref.metadata.span)?;
+                let (_, ptr) = self.emit_expr(func, &deref.pointer)?;
+                self.emit_store(func, &pointee_ty, ptr, value.clone());
+            }
+        }
+        Ok((ty, value))
+    }
+
+    /// Emits 'target op= value', 'target++' and 'target--',
+    /// the address of the place is computed once, then it's loaded, updated and stored.
+    fn emit_update(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        ass: &AssignmentExpr,
+        op: BinOp,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting update = {:?}", ass);
+        let (ty, ptr) = match &ass.target {
+            LValue::Variable(var) => {
+                let (var_ty, var_value) = self.get_var(&var.name)?.clone();
+                if !self.is_in_memory(&var.name, &var_ty, &var_value) {
+                    // variables held in temporaries are updated in place.
+                    let mut old = var_value.clone();
+                    if matches!(ass.kind, AssignKind::Postfix(_)) {
+                        old = self.new_tmp();
+                        func.assign_instr(
+                            old.clone(),
+                            var_ty.clone(),
+                            qbe::Instr::Copy(var_value.clone()),
+                        );
+                    }
+                    let new = self.emit_update_value(func, &var_ty, old.clone(), op, &ass.value)?;
+                    func.assign_instr(var_value, var_ty.clone(), qbe::Instr::Copy(new.clone()));
+                    return Ok(Self::update_result(ass, var_ty, old, new));
+                }
+                (var_ty, var_value)
+            }
+            LValue::FieldAccess(fiac) => self.emit_field_ptr(func, fiac)?,
+            LValue::Index(idx) => self.emit_index_ptr(func, idx)?,
+            LValue::Deref(deref) => {
+                let Some(pointee) = &deref.metadata.node_type else {
+                    bail_at!(deref.metadata.span, "Dereference without a type");
+                };
+                let pointee_ty = self.qbe_type(pointee, deref.metadata.span)?;
+                let (_, ptr) = self.emit_expr(func, &deref.pointer)?;
+                (pointee_ty, ptr)
+            }
+        };
+
+        let old = self.new_tmp();
         func.assign_instr(
-            result_value.1.clone(),
+            old.clone(),
             ty.clone(),
-            qbe::Instr::Copy(value.clone()),
+            qbe::Instr::Load(ty.clone(), ptr.clone()),
         );
-        Ok((ty, value))
+        let new = self.emit_update_value(func, &ty, old.clone(), op, &ass.value)?;
+        func.add_instr(qbe::Instr::Store(ty.clone(), ptr, new.clone()));
+        Ok(Self::update_result(ass, ty, old, new))
+    }
+
+    /// Emits 'old op value' for a place of type 'ty'.
+    fn emit_update_value(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        ty: &qbe::Type<'static>,
+        old: qbe::Value,
+        op: BinOp,
+        value: &Expr,
+    ) -> EmitterResult<qbe::Value> {
+        let (value_ty, mut value) = self.emit_expr(func, value)?;
+        // ints are converted when updating floats, the opposite doesn't type check.
+        if matches!(ty, qbe::Type::Double) && !matches!(value_ty, qbe::Type::Double) {
+            value = self.emit_int_to_float(func, value);
+        }
+
+        let new = self.new_tmp();
+        func.assign_instr(
+            new.clone(),
+            ty.clone(),
+            match op {
+                BinOp::Add => qbe::Instr::Add(old, value),
+                BinOp::Sub => qbe::Instr::Sub(old, value),
+                BinOp::Mul => qbe::Instr::Mul(old, value),
+                BinOp::Div => qbe::Instr::Div(old, value),
+                BinOp::Mod => qbe::Instr::Rem(old, value),
+                _ => unreachable!("update with {}", op),
+            },
+        );
+        Ok(new)
+    }
+
+    /// Postfix updates evaluate to the old value, others to the new one.
+    fn update_result(
+        ass: &AssignmentExpr,
+        ty: qbe::Type<'static>,
+        old: qbe::Value,
+        new: qbe::Value,
+    ) -> (qbe::Type<'static>, qbe::Value) {
+        match ass.kind {
+            AssignKind::Postfix(_) => (ty, old),
+            _ => (ty, new),
+        }
     }
 
     /// Emit binary operation ast.
@@ -59,29 +205,11 @@ impl QBEEmitter<'_> {
 
         let ty = qbe::Type::try_from(&expr.metadata.node_type.clone().unwrap())?;
         if matches!(ty_left, qbe::Type::Double) && !matches!(ty_right, qbe::Type::Double) {
-            let promoted = self.new_tmp();
-            func.assign_instr(promoted.clone(), qbe::Type::Long, qbe::Instr::Extsw(right));
-
-            let new_right = self.new_tmp();
-            func.assign_instr(
-                new_right.clone(),
-                qbe::Type::Double,
-                qbe::Instr::Cast(promoted),
-            );
-            right = new_right;
+            right = self.emit_int_to_float(func, right);
         }
 
         if matches!(ty_right, qbe::Type::Double) && !matches!(ty_left, qbe::Type::Double) {
-            let promoted = self.new_tmp();
-            func.assign_instr(promoted.clone(), qbe::Type::Long, qbe::Instr::Extsw(left));
-
-            let new_left = self.new_tmp();
-            func.assign_instr(
-                new_left.clone(),
-                qbe::Type::Double,
-                qbe::Instr::Cast(promoted),
-            );
-            left = new_left;
+            left = self.emit_int_to_float(func, left);
         }
 
         func.assign_instr(
@@ -99,9 +227,13 @@ impl QBEEmitter<'_> {
                 BinOp::Or => qbe::Instr::Or(left, right),
                 BinOp::And => qbe::Instr::And(left, right),
 
-                // comparison
+                // comparison, pointers are compared as longs.
                 cmp => qbe::Instr::Cmp(
-                    ty.clone(),
+                    if matches!(ty_left, qbe::Type::Long) {
+                        qbe::Type::Long
+                    } else {
+                        ty.clone()
+                    },
                     match cmp {
                         BinOp::Less => qbe::Cmp::Slt,
                         BinOp::LessEqual => qbe::Cmp::Sle,
@@ -120,40 +252,59 @@ impl QBEEmitter<'_> {
         Ok((ty, tmp))
     }
 
-    /// Emit Eve function call
+    /// Emit Eve function call, arguments and result use the callee's declared types.
     fn emit_call(
         &mut self,
         func: &mut qbe::Function<'static>,
         call: &CallExpr,
     ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
         trace!("emitting call expr call = {:?}", call);
-        let ty = qbe::Type::Word;
-        let arg = if let Some(arg_expr) = &call.arg {
-            vec![self.emit_expr(func, arg_expr)?]
-        } else {
-            vec![]
-        };
-
-        let tmp = self.new_tmp();
-
-        if let Expr::Variable(var) = &call.callee {
-            func.assign_instr(
-                tmp.clone(),
-                ty.clone(),
-                qbe::Instr::Call(var.name.clone(), arg, None),
-            );
-        } else {
+        if let Expr::FieldAccess(fiac) = &call.callee {
+            return self.emit_method_call(func, call, fiac);
+        }
+        let Expr::Variable(var) = &call.callee else {
             error!(
-                "Expected function name got '{:?}' instead, line {}",
-                call.callee, call.metadata.line
+                "Expected function name got '{:?}' instead, at {}",
+                call.callee, call.metadata.span
             );
-            bail!(
-                "Expected function name got '{:?}' instead, line {}",
-                call.callee,
-                call.metadata.line
+            bail_at!(
+                call.metadata.span,
+                "Expected function name got '{:?}' instead",
+                call.callee
             );
+        };
+
+        let fn_decls = self.fn_decls;
+        let fn_decl = fn_decls
+            .iter()
+            .find(|x| x.name == var.name)
+            .with_context(|| {
+                format!(
+                    "Call to undeclared function '{}', at {}",
+                    var.name, call.metadata.span
+                )
+            })?;
+
+        let mut args = vec![];
+        for (arg, param) in call.args.iter().zip(&fn_decl.parameters) {
+            let (arg_ty, mut value) = self.emit_expr(func, arg)?;
+            let param_ty = self.qbe_type(&param.field_type, param.metadata.span)?;
+            if matches!(param_ty, qbe::Type::Double) && !matches!(arg_ty, qbe::Type::Double) {
+                value = self.emit_int_to_float(func, value);
+            }
+            args.push((param_ty, value));
         }
 
+        // struct results are pointers to a copy made by the caller.
+        let ty = self.qbe_type(&fn_decl.return_type, call.metadata.span)?;
+        let tmp = self.new_tmp();
+        Self::assign_aggregate(
+            func,
+            tmp.clone(),
+            ty.clone(),
+            qbe::Instr::Call(var.name.clone(), args, None),
+        );
+
         Ok((ty, tmp))
     }
 
@@ -165,22 +316,220 @@ impl QBEEmitter<'_> {
     ) -> Result<(qbe::Type<'static>, qbe::Value), anyhow::Error> {
         trace!("emitting field access = {:?}", &fiac);
 
-        let (parent_tmp, struct_ty) = match &fiac.parent {
-            Expr::Variable(var) => {
-                let (struct_ty, src) = self.get_var(&var.name)?.to_owned();
-                let struct_ty = match struct_ty {
-                    qbe::Type::Aggregate(ag) => ag,
-                    _ => bail!(
-                        "Field access is only supported for struct types, line {}",
-                        fiac.metadata.line
-                    ),
-                };
+        let (field_ty, field_ptr) = self.emit_field_ptr(func, fiac)?;
+        if matches!(field_ty, qbe::Type::Aggregate(_)) {
+            return Ok((field_ty, field_ptr));
+        }
+
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            field_ty.clone(),
+            qbe::Instr::Load(field_ty.clone(), field_ptr),
+        );
+
+        Ok((field_ty, tmp))
+    }
+
+    /// Emits reading an array element, arrays and structs are returned as pointers to them.
+    fn emit_index(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        idx: &IndexExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting index = {:?}", &idx);
+
+        let (elem_ty, elem_ptr) = self.emit_index_ptr(func, idx)?;
+        if matches!(elem_ty, qbe::Type::Aggregate(_)) {
+            return Ok((elem_ty, elem_ptr));
+        }
+
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            elem_ty.clone(),
+            qbe::Instr::Load(elem_ty.clone(), elem_ptr),
+        );
+
+        Ok((elem_ty, tmp))
+    }
 
-                (src, struct_ty)
+    /// Emits the address of an array or vector element, returns it with the element's type.
+    pub(super) fn emit_index_ptr(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        idx: &IndexExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        if let Some(DType::Vector(elem)) = &idx.object.node_type {
+            return self.emit_vec_elem_ptr(func, idx, elem);
+        }
+        let Some(DType::Array(elem, len)) = &idx.object.node_type else {
+            bail_at!(
+                idx.metadata.span,
+                "Indexing is only supported for array types"
+            );
+        };
+        let elem_ty = self.qbe_type(elem, idx.metadata.span)?;
+
+        // arrays are passed around as pointers, like structs.
+        let (_, array_ptr) = self.emit_expr(func, &idx.object)?;
+        let (_, index) = self.emit_expr(func, &idx.index)?;
+        if self.bounds_checks {
+            self.emit_bounds_check(func, index.clone(), *len);
+        }
+
+        let offset = self.new_tmp();
+        func.assign_instr(offset.clone(), qbe::Type::Long, qbe::Instr::Extsw(index));
+        let scaled = self.new_tmp();
+        func.assign_instr(
+            scaled.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Mul(offset, qbe::Value::Const(elem_ty.size())),
+        );
+        let elem_ptr = self.new_tmp();
+        func.assign_instr(
+            elem_ptr.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Add(array_ptr, scaled),
+        );
+
+        Ok((elem_ty, elem_ptr))
+    }
+
+    /// Exits the program with a message if 'index' isn't less than 'len'.
+    fn emit_bounds_check(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        index: qbe::Value,
+        len: usize,
+    ) {
+        self.tmp_counter += 1;
+        let ok_label = format!("bounds.{}.ok", self.tmp_counter);
+        let fail_label = format!("bounds.{}.fail", self.tmp_counter);
+
+        // negative indices are huge when compared unsigned.
+        let in_bounds = self.new_tmp();
+        func.assign_instr(
+            in_bounds.clone(),
+            qbe::Type::Word,
+            qbe::Instr::Cmp(
+                qbe::Type::Word,
+                qbe::Cmp::Ult,
+                index.clone(),
+                qbe::Value::Const(len as u64),
+            ),
+        );
+        func.add_instr(qbe::Instr::Jnz(
+            in_bounds,
+            ok_label.clone(),
+            fail_label.clone(),
+        ));
+
+        func.add_block(fail_label);
+        func.add_instr(qbe::Instr::Call(
+            "printf".into(),
+            vec![
+                (qbe::Type::Long, qbe::Value::Global("___FMT_BOUNDS".into())),
+                (qbe::Type::Word, index),
+                (qbe::Type::Word, qbe::Value::Const(len as u64)),
+            ],
+            Some(1),
+        ));
+        func.add_instr(qbe::Instr::Call(
+            "exit".into(),
+            vec![(qbe::Type::Word, qbe::Value::Const(1))],
+            None,
+        ));
+
+        // exit doesn't return, falling through to the ok block is never taken.
+        func.add_block(ok_label);
+    }
+
+    /// Emits an array literal on the stack, returns pointer to it.
+    fn emit_array_init(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        arr: &ArrayInitExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting array literal");
+        let Some(DType::Array(elem, len)) = &arr.metadata.node_type else {
+            bail_at!(arr.metadata.span, "Array literal without an array type");
+        };
+        let array_ty = self.array_type(elem, *len, arr.metadata.span)?;
+        let elem_ty = self.qbe_type(elem, arr.metadata.span)?;
+        let elem_size = elem_ty.size();
+
+        let tmp = self.new_tmp();
+        Self::emit_entry_alloc(func, tmp.clone(), array_ty.size());
+
+        if arr.count.is_none() {
+            for (i, element) in arr.elements.iter().enumerate() {
+                let (_, value) = self.emit_expr(func, element)?;
+                let elem_ptr = self.new_tmp();
+                func.assign_instr(
+                    elem_ptr.clone(),
+                    qbe::Type::Long,
+                    qbe::Instr::Add(tmp.clone(), qbe::Value::Const(i as u64 * elem_size)),
+                );
+                self.emit_store(func, &elem_ty, elem_ptr, value);
             }
-            _ => unreachable!(
-                "field access's parent is not a var, line {}",
-                fiac.metadata.line
+            return Ok((array_ty, tmp));
+        }
+
+        // [value; count], the value is evaluated once and stored in a loop.
+        let (_, value) = self.emit_expr(func, &arr.elements[0])?;
+        self.tmp_counter += 1;
+        let loop_label = format!("array.{}.fill", self.tmp_counter);
+        let end_label = format!("array.{}.end", self.tmp_counter);
+
+        let offset = self.new_tmp();
+        func.assign_instr(
+            offset.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Copy(qbe::Value::Const(0)),
+        );
+        func.add_block(&loop_label);
+        let elem_ptr = self.new_tmp();
+        func.assign_instr(
+            elem_ptr.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Add(tmp.clone(), offset.clone()),
+        );
+        self.emit_store(func, &elem_ty, elem_ptr, value);
+        func.assign_instr(
+            offset.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Add(offset.clone(), qbe::Value::Const(elem_size)),
+        );
+        let more = self.new_tmp();
+        func.assign_instr(
+            more.clone(),
+            qbe::Type::Word,
+            qbe::Instr::Cmp(
+                qbe::Type::Long,
+                qbe::Cmp::Ult,
+                offset,
+                qbe::Value::Const(array_ty.size()),
+            ),
+        );
+        func.add_instr(qbe::Instr::Jnz(more, loop_label, end_label.clone()));
+        func.add_block(end_label);
+
+        Ok((array_ty, tmp))
+    }
+
+    /// Emits the address of a struct field, returns it with the field's type.
+    pub(super) fn emit_field_ptr(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        fiac: &FieldAccessExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        // structs are passed around as pointers, nested ones included.
+        let (parent_tmp, struct_ty) = match self.emit_expr(func, &fiac.parent)? {
+            (qbe::Type::Aggregate(ag), src) => (src, ag),
+            _ => bail_at!(
+                fiac.metadata.span,
+                "Field access is only supported for struct types"
             ),
         };
 
@@ -189,8 +538,8 @@ impl QBEEmitter<'_> {
             .get(&struct_ty.name)
             .with_context(|| {
                 format!(
-                    "Use of undeclared struct var '{}', line {}",
-                    &struct_ty.name, fiac.metadata.line
+                    "Use of undeclared struct var '{}', at {}",
+                    &struct_ty.name, fiac.metadata.span
                 )
             })?
             .to_owned();
@@ -199,8 +548,8 @@ impl QBEEmitter<'_> {
             .get(&fiac.field)
             .with_context(|| {
                 format!(
-                    "Field '{}' does not exist on struct '{}', line {}",
-                    &fiac.field, &struct_ty.name, fiac.metadata.line
+                    "Field '{}' does not exist on struct '{}', at {}",
+                    &fiac.field, &struct_ty.name, fiac.metadata.span
                 )
             })?
             .to_owned();
@@ -212,16 +561,60 @@ impl QBEEmitter<'_> {
             qbe::Instr::Add(parent_tmp, qbe::Value::Const(offset)),
         );
 
-        // parent_tmp -> src, ty -> ty, offset -> offset
+        Ok((field_ty, field_ptr))
+    }
 
+    /// Emits struct initialiser, the struct is returned as a pointer to it.
+    fn emit_struct_init(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        st_init: &StructInitExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting struct init stmt");
+
+        let (meta, size) = self
+            .struct_meta
+            .get(&st_init.struct_name)
+            .with_context(|| {
+                format!(
+                    "Initialiser of undeclared struct '{}', at {}",
+                    &st_init.struct_name, st_init.metadata.span
+                )
+            })?
+            .to_owned();
+
+        let struct_ty = self.struct_type(&st_init.struct_name, st_init.metadata.span)?;
         let tmp = self.new_tmp();
-        func.assign_instr(
-            tmp.clone(),
-            field_ty.clone(),
-            qbe::Instr::Load(field_ty.clone(), field_ptr),
-        );
+        Self::emit_entry_alloc(func, tmp.clone(), size);
 
-        Ok((field_ty, tmp))
+        for arg in &st_init.arguments {
+            // get meta about arg
+            let (field_type, offset) = meta.get(&arg.field_name).with_context(|| {
+                format!(
+                    "Unknown field : '{}', at {}",
+                    arg.field_name, arg.metadata.span
+                )
+            })?;
+
+            let (_, expr_tmp) = self.emit_expr(func, &arg.field_expr)?;
+            let field_tmp = self.new_tmp();
+            func.assign_instr(
+                field_tmp.clone(),
+                qbe::Type::Long,
+                qbe::Instr::Add(tmp.clone(), qbe::Value::Const(*offset)),
+            );
+
+            match field_type {
+                qbe::Type::Aggregate(_) => {
+                    self.emit_struct_copy(func, field_type, expr_tmp, field_tmp);
+                }
+                _ => {
+                    func.add_instr(qbe::Instr::Store(field_type.clone(), field_tmp, expr_tmp));
+                }
+            }
+        }
+
+        Ok((struct_ty, tmp))
     }
 
     /// Emit eve native function call
@@ -231,30 +624,41 @@ impl QBEEmitter<'_> {
         call: &NativeCallExpr,
     ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
         trace!("emitting native call expr call = {:?}", call);
-        let ty = qbe::Type::Word;
+        let Some(result_ty) = &call.metadata.node_type else {
+            bail_at!(call.metadata.span, "Native call without a result type");
+        };
+        let ty = self.qbe_type(result_ty, call.metadata.span)?;
         let args = call
             .args
             .iter()
-            .map(|arg| self.emit_expr(func, arg))
-            .collect::<Result<Vec<_>, _>>()?;
+            .map(|arg| {
+                let (ty, value) = self.emit_expr(func, arg)?;
+                // C takes arrays as pointers to their first element.
+                if let Some(DType::Array(..)) = arg.node_type {
+                    return Ok((qbe::Type::Long, value));
+                }
+                Ok((ty, value))
+            })
+            .collect::<EmitterResult<Vec<_>>>()?;
 
         let tmp = self.new_tmp();
 
         if let Expr::Variable(var) = &call.callee {
-            func.assign_instr(
+            Self::assign_aggregate(
+                func,
                 tmp.clone(),
                 ty.clone(),
                 qbe::Instr::Call(var.name.clone(), args, None),
             );
         } else {
             error!(
-                "Expected function name got '{:?}' instead, line {}",
-                call.callee, call.metadata.line
+                "Expected function name got '{:?}' instead, at {}",
+                call.callee, call.metadata.span
             );
-            bail!(
-                "Expected function name got '{:?}' instead, line {}",
-                call.callee,
-                call.metadata.line
+            bail_at!(
+                call.metadata.span,
+                "Expected function name got '{:?}' instead",
+                call.callee
             );
         }
 
@@ -298,10 +702,18 @@ impl QBEEmitter<'_> {
     /// Emits variable expression
     fn emit_variable(
         &mut self,
+        func: &mut qbe::Function<'static>,
         expr: &VariableExpr,
     ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
         trace!("emitting variable expr = {:?}", expr);
         let (ty, tmp) = self.get_var(&expr.name)?.clone();
+
+        // statics and locals whose address is taken live in memory.
+        if self.is_in_memory(&expr.name, &ty, &tmp) {
+            let value = self.new_tmp();
+            func.assign_instr(value.clone(), ty.clone(), qbe::Instr::Load(ty.clone(), tmp));
+            return Ok((ty, value));
+        }
         Ok((ty, tmp))
     }
 
@@ -344,10 +756,7 @@ impl QBEEmitter<'_> {
                     qbe::Linkage::private(),
                     glob_name,
                     None,
-                    vec![
-                        (qbe::Type::Byte, qbe::DataItem::Str(v.into())),
-                        (qbe::Type::Byte, qbe::DataItem::Const(0)),
-                    ],
+                    Self::string_data_items(v),
                 ));
 
                 func.assign_instr(
@@ -372,7 +781,15 @@ impl QBEEmitter<'_> {
                 Ok((ty, tmp))
             }
 
-            LiteralValue::Null => unreachable!(),
+            LiteralValue::Null => {
+                let tmp = self.new_tmp();
+                func.assign_instr(
+                    tmp.clone(),
+                    qbe::Type::Long,
+                    qbe::Instr::Copy(qbe::Value::Const(0)),
+                );
+                Ok((qbe::Type::Long, tmp))
+            }
         }
     }
 }
@@ -1,50 +1,45 @@
 use std::collections::HashMap;
 
-use crate::ast::{DType, FnDecl, Stmt};
+use crate::ast::{FnDecl, Stmt};
 use crate::emitter::EmitterResult;
 use log::trace;
 use qbe;
 
 use super::QBEEmitter;
-use anyhow::Context;
 
 impl QBEEmitter<'_> {
     /// Emits a single function
     pub(super) fn emit_function(&mut self, func: &FnDecl) -> EmitterResult<()> {
         trace!("Emitting a new function: '{}'", &func.name);
         self.scopes.push(HashMap::new());
+        self.stack_vars = Self::addressed_vars(&func.body);
+        // structs are returned by value, qbe copies them out of the returned pointer.
+        let return_ty = self.qbe_type(&func.return_type, func.metadata.span)?;
         let mut func_block = qbe::Function::new(
             qbe::Linkage::public(),
             &func.name,
-            func.parameter
+            func.parameters
                 .iter()
                 .map(|x| {
-                    let ty = match &x.field_type {
-                        DType::Primitive(ttype) => qbe::Type::try_from(*ttype).unwrap(),
-                        DType::Derived(name) => {
-                            let type_def = self
-                                .type_defs
-                                .iter()
-                                .find(|x| &x.name == name)
-                                .cloned()
-                                .with_context(|| {
-                                    format!(
-                                        "Initialiser of undeclared struct '{}', line {}",
-                                        name, func.metadata.line
-                                    )
-                                })?;
-
-                            let boxed_type_def = Box::new(type_def);
-                            qbe::Type::Aggregate(Box::leak(boxed_type_def))
-                        }
-                    };
+                    let ty = self.qbe_type(&x.field_type, x.metadata.span)?;
                     let val = self.new_var(ty.clone(), x.field_name.clone())?;
                     Ok((ty, val))
                 })
                 .collect::<anyhow::Result<Vec<_>>>()?,
-            qbe::Type::try_from(func.return_type.clone()).ok(),
+            Some(return_ty),
         );
         func_block.add_block("start");
+        for param in &func.parameters {
+            let (ty, value) = self.get_var(&param.field_name)?.clone();
+            if self.is_in_memory(&param.field_name, &ty, &value) {
+                let slot = self.new_tmp();
+                self.emit_stack_slot(&mut func_block, slot.clone(), &ty, value);
+                self.scopes
+                    .last_mut()
+                    .expect("Expected last scope to be present")
+                    .insert(param.field_name.clone(), (ty, slot));
+            }
+        }
         self.emit_function_body(&mut func_block, &func.body)?;
 
         // add a ret instruction if there isnt one at the end of a function declaration.
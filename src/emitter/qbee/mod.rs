@@ -1,12 +1,15 @@
 mod data;
+mod enums;
 mod expr;
 mod func;
+mod pointer;
 mod stmt;
 mod utils;
+mod vector;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::ast::{FnDecl, StructDecl};
+use crate::ast::{EnumDecl, FnDecl, GlobalDecl, GlobalKind, StructDecl};
 use crate::emitter::EmitterResult;
 use data::StructMeta;
 use log::{debug, info};
@@ -14,6 +17,18 @@ use qbe;
 
 use super::Emitter;
 
+/// Labels which break and continue statements of a loop jump to.
+struct LoopScope {
+    /// Label given to the loop in source, if any.
+    label: Option<String>,
+
+    /// Block right after the loop.
+    end_label: String,
+
+    /// Block which starts the next iteration.
+    continue_label: String,
+}
+
 /// Public struct for the QBE IR Emitter.
 pub struct QBEEmitter<'a> {
     /// Counts total number of temporaries created.
@@ -25,11 +40,26 @@ pub struct QBEEmitter<'a> {
     /// Struct declarations
     struct_decls: &'a Vec<StructDecl>,
 
+    /// Const and static declarations
+    global_decls: &'a Vec<GlobalDecl>,
+
+    /// Enum declarations
+    enum_decls: &'a Vec<EnumDecl>,
+
+    /// Statics emitted as data, name -> (type, address).
+    globals: HashMap<String, (qbe::Type<'static>, qbe::Value)>,
+
     /// Scopes for variables.
     scopes: Vec<HashMap<String, (qbe::Type<'static>, qbe::Value)>>,
 
+    /// Variables of the current function whose address is taken, they live in stack slots.
+    stack_vars: HashSet<String>,
+
+    /// Whether array indexing is checked against the array's length at runtime.
+    bounds_checks: bool,
+
     /// Scopes for loops.
-    loop_scopes: Vec<usize>,
+    loop_scopes: Vec<LoopScope>,
 
     /// Type defs emitted for this module
     type_defs: Vec<qbe::TypeDef<'static>>,
@@ -42,15 +72,28 @@ pub struct QBEEmitter<'a> {
     module: qbe::Module<'a>,
 }
 
+/// Declarations the emitter is created from.
+type Decls<'a> = (
+    &'a Vec<FnDecl>,
+    &'a Vec<StructDecl>,
+    &'a Vec<GlobalDecl>,
+    &'a Vec<EnumDecl>,
+);
+
 /// Impl From for QBEEmitter.
-impl<'a> From<(&'a Vec<FnDecl>, &'a Vec<StructDecl>)> for QBEEmitter<'a> {
-    fn from(decls: (&'a Vec<FnDecl>, &'a Vec<StructDecl>)) -> Self {
+impl<'a> From<Decls<'a>> for QBEEmitter<'a> {
+    fn from(decls: Decls<'a>) -> Self {
         info!("creating new QBEEmitter instance");
         Self {
             tmp_counter: 0,
             fn_decls: decls.0,
             struct_decls: decls.1,
+            global_decls: decls.2,
+            enum_decls: decls.3,
+            globals: HashMap::new(),
             scopes: vec![],
+            stack_vars: HashSet::new(),
+            bounds_checks: false,
             loop_scopes: vec![],
             type_defs: vec![],
             struct_meta: HashMap::new(),
@@ -63,7 +106,7 @@ impl<'a> From<(&'a Vec<FnDecl>, &'a Vec<StructDecl>)> for QBEEmitter<'a> {
 impl Emitter for QBEEmitter<'_> {
     fn emit_ir(&mut self) -> EmitterResult<String> {
         debug!("start emitting qbe ir");
-        self.emit_data_defs();
+        self.emit_data_defs()?;
         self.emit_functions()?;
         Ok(self.module.to_string())
     }
@@ -71,16 +114,32 @@ impl Emitter for QBEEmitter<'_> {
 
 /// More impl for QBEEmitter.
 impl QBEEmitter<'_> {
-    /// Emits all parsed structs
-    fn emit_data_defs(&mut self) {
+    /// Enables runtime bounds checks for array indexing.
+    pub fn set_bounds_checks(mut self, enabled: bool) -> Self {
+        self.bounds_checks = enabled;
+        self
+    }
+
+    /// Emits all parsed structs and statics
+    fn emit_data_defs(&mut self) -> EmitterResult<()> {
         self.init_data_def();
         for struc in self.struct_decls {
-            self.emit_data_def(struc);
+            self.emit_data_def(struc)?;
         }
+        for global in self.global_decls {
+            // constants are already inlined by the type system.
+            if global.kind == GlobalKind::Static {
+                self.emit_static_def(global)?;
+            }
+        }
+        Ok(())
     }
-    /// Emits all parsed functions
+    /// Emits all parsed functions, along with functions naming variants of enums.
     fn emit_functions(&mut self) -> EmitterResult<()> {
         debug!("Emitting functions");
+        for decl in self.enum_decls {
+            self.emit_variant_name_fn(decl);
+        }
         for func in self.fn_decls {
             self.emit_function(func)?;
         }
@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+
+use crate::ast::{AddrOfExpr, DerefExpr, Expr, LValue, Stmt, visit_exprs};
+use crate::bail_at;
+use crate::emitter::EmitterResult;
+use log::trace;
+use qbe;
+
+use super::QBEEmitter;
+
+// Pointers are plain addresses, locals whose address is taken live in stack slots
+// so that writes through a pointer are seen by later reads of the variable.
+impl QBEEmitter<'_> {
+    /// Names of the variables whose address is taken in a function's body.
+    pub(super) fn addressed_vars(body: &[Stmt]) -> HashSet<String> {
+        let mut names = HashSet::new();
+        visit_exprs(body, &mut |expr| {
+            if let Expr::AddrOf(addr) = expr
+                && let LValue::Variable(var) = &addr.target
+            {
+                names.insert(var.name.clone());
+            }
+        });
+        names
+    }
+
+    /// Whether the variable 'name' holding 'value' lives in memory, statics and
+    /// locals whose address is taken do. Structs and arrays are pointers already.
+    pub(super) fn is_in_memory(&self, name: &str, ty: &qbe::Type, value: &qbe::Value) -> bool {
+        match value {
+            qbe::Value::Global(_) => true,
+            _ => self.stack_vars.contains(name) && !matches!(ty, qbe::Type::Aggregate(_)),
+        }
+    }
+
+    /// Allocates a stack slot whose address is held by 'slot' in the start block,
+    /// stores 'value' in it.
+    pub(super) fn emit_stack_slot(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        slot: qbe::Value,
+        ty: &qbe::Type<'static>,
+        value: qbe::Value,
+    ) {
+        Self::emit_entry_alloc(func, slot.clone(), ty.size());
+        func.add_instr(qbe::Instr::Store(ty.clone(), slot, value));
+    }
+
+    pub(super) fn emit_addr_of(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        addr: &AddrOfExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting address of = {:?}", addr);
+        let ptr = match &addr.target {
+            LValue::Variable(var) => {
+                let (ty, value) = self.get_var(&var.name)?.clone();
+                if !matches!(ty, qbe::Type::Aggregate(_))
+                    && !self.is_in_memory(&var.name, &ty, &value)
+                {
+                    bail_at!(
+                        addr.metadata.span,
+                        "Variable '{}' doesn't live in memory",
+                        var.name
+                    );
+                }
+                value
+            }
+            LValue::FieldAccess(fiac) => self.emit_field_ptr(func, fiac)?.1,
+            LValue::Index(idx) => self.emit_index_ptr(func, idx)?.1,
+            LValue::Deref(deref) => self.emit_expr(func, &deref.pointer)?.1,
+        };
+
+        Ok((qbe::Type::Long, ptr))
+    }
+
+    /// Emits reading the value a pointer points to, structs and arrays are returned as the pointer itself.
+    pub(super) fn emit_deref(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        deref: &DerefExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting dereference = {:?}", deref);
+        let Some(pointee) = &deref.metadata.node_type else {
+            bail_at!(deref.metadata.span, "Dereference without a type");
+        };
+        let ty = self.qbe_type(pointee, deref.metadata.span)?;
+        let (_, ptr) = self.emit_expr(func, &deref.pointer)?;
+        if matches!(ty, qbe::Type::Aggregate(_)) {
+            return Ok((ty, ptr));
+        }
+
+        let tmp = self.new_tmp();
+        func.assign_instr(tmp.clone(), ty.clone(), qbe::Instr::Load(ty.clone(), ptr));
+        Ok((ty, tmp))
+    }
+}
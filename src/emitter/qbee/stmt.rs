@@ -1,15 +1,16 @@
 use std::collections::HashMap;
 
 use crate::ast::{
-    BreakStmt, Expr, IfStmt, LetStmt, LoopStmt, PrintStmt, ReturnStmt, Stmt, StructInitStmt,
+    BreakStmt, ContinueStmt, DType, Expr, ForStmt, IfStmt, LetStmt, LoopStmt, MatchPattern,
+    MatchStmt, PrintStmt, ReturnStmt, Stmt, WhileStmt,
 };
-use crate::die;
 use crate::emitter::EmitterResult;
-use anyhow::{Context, bail};
+use crate::{bail_at, die};
+use anyhow::Context;
 use log::{error, trace};
 use qbe;
 
-use super::QBEEmitter;
+use super::{LoopScope, QBEEmitter};
 
 impl QBEEmitter<'_> {
     // Emits statement
@@ -22,13 +23,17 @@ impl QBEEmitter<'_> {
         match stmt {
             Stmt::Block(stmt) => self.emit_block(func, &stmt.stmts),
             Stmt::Let(stmt) => self.emit_let(func, stmt),
-            Stmt::StructInit(stmt) => self.emit_struct_init(func, stmt),
             Stmt::If(stmt) => self.emit_if_stmt(func, stmt),
             Stmt::Loop(stmt) => self.emit_loop_stmt(func, stmt),
+            Stmt::While(stmt) => self.emit_while_stmt(func, stmt),
+            Stmt::For(stmt) => self.emit_for_stmt(func, stmt),
+            Stmt::Match(stmt) => self.emit_match_stmt(func, stmt),
             Stmt::Break(stmt) => self.emit_break_stmt(func, stmt),
+            Stmt::Continue(stmt) => self.emit_continue_stmt(func, stmt),
             Stmt::Print(stmt) => self.emit_print_stmt(func, stmt),
             Stmt::Return(stmt) => self.emit_return_stmt(func, stmt),
             Stmt::Expression(expr) => self.emit_expr_stmt(func, expr),
+            Stmt::Error(err) => bail_at!(err.metadata.span, "Cannot emit an erroneous statement"),
         }
     }
 
@@ -49,81 +54,38 @@ impl QBEEmitter<'_> {
 
     // emits let declaration
     fn emit_let(&mut self, func: &mut qbe::Function<'static>, le: &LetStmt) -> EmitterResult<()> {
-        let (ty, value) = self.emit_expr(func, &le.initialiser)?;
+        let (mut ty, mut value) = self.emit_expr(func, &le.initialiser)?;
+        if let Some(annotated) = &le.ty {
+            let annotated = self.qbe_type(annotated, le.metadata.span)?;
+            if matches!(annotated, qbe::Type::Double) && !matches!(ty, qbe::Type::Double) {
+                value = self.emit_int_to_float(func, value);
+            }
+            ty = annotated;
+        }
+
         let result_value = self.new_var(ty.clone(), le.name.clone())?;
-        func.assign_instr(result_value, ty, qbe::Instr::Copy(value));
+        // structs and arrays are values, the binding gets its own copy of an existing one.
+        if matches!(ty, qbe::Type::Aggregate(_)) && !Self::creates_value(&le.initialiser) {
+            Self::emit_entry_alloc(func, result_value.clone(), ty.size());
+            self.emit_struct_copy(func, &ty, value, result_value);
+            return Ok(());
+        }
+        if self.is_in_memory(&le.name, &ty, &result_value) {
+            self.emit_stack_slot(func, result_value, &ty, value);
+            return Ok(());
+        }
+        func.assign_instr(result_value, Self::tmp_type(&ty), qbe::Instr::Copy(value));
         Ok(())
     }
 
-    // emits struct initialization
-    fn emit_struct_init(
-        &mut self,
-        func: &mut qbe::Function<'static>,
-        st_init: &StructInitStmt,
-    ) -> EmitterResult<()> {
-        trace!("emitting struct init stmt");
-
-        let (meta, size) = self
-            .struct_meta
-            .get(&st_init.struct_name)
-            .with_context(|| {
-                format!(
-                    "Initialiser of undeclared struct '{}', line {}",
-                    &st_init.struct_name, st_init.metadata.line
-                )
-            })?
-            .to_owned();
-
-        let type_def = self
-            .type_defs
-            .iter()
-            .find(|x| x.name == st_init.struct_name)
-            .cloned()
-            .with_context(|| {
-                format!(
-                    "Initialiser of undeclared struct '{}', line {}",
-                    st_init.struct_name, st_init.metadata.line
-                )
-            })?;
-
-        let boxed_type_def = Box::new(type_def);
-        let tmp = self.new_var(
-            qbe::Type::Aggregate(Box::leak(boxed_type_def)),
-            st_init.name.to_owned(),
-        )?;
-        func.assign_instr(tmp.clone(), qbe::Type::Long, qbe::Instr::Alloc8(size));
-
-        for arg in &st_init.arguments {
-            // get meta about arg
-            let (field_type, offset) = meta.get(&arg.field_name).with_context(|| {
-                format!(
-                    "Unknown field : '{}', line {}",
-                    arg.field_name, arg.metadata.line
-                )
-            })?;
-
-            let (_, expr_tmp) = self.emit_expr(func, &arg.field_expr)?;
-            match field_type {
-                qbe::Type::Aggregate(_) => {
-                    bail!(
-                        "Aggregate types inside structs are not supported yet, line {}",
-                        arg.metadata.line
-                    );
-                }
-                _ => {
-                    let field_tmp = self.new_tmp();
-                    func.assign_instr(
-                        field_tmp.clone(),
-                        qbe::Type::Long,
-                        qbe::Instr::Add(tmp.clone(), qbe::Value::Const(*offset)),
-                    );
-
-                    func.add_instr(qbe::Instr::Store(field_type.clone(), field_tmp, expr_tmp));
-                }
-            }
+    /// Returns whether evaluating 'expr' creates a new struct or array,
+    /// rather than referring to an existing one.
+    pub(super) fn creates_value(expr: &Expr) -> bool {
+        match expr {
+            Expr::Grouping(gr) => Self::creates_value(&gr.value),
+            Expr::StructInit(_) | Expr::ArrayInit(_) | Expr::Call(_) | Expr::NativeCall(_) => true,
+            _ => false,
         }
-
-        Ok(())
     }
 
     /// Emits if statement
@@ -176,9 +138,13 @@ impl QBEEmitter<'_> {
         loop_stmt: &LoopStmt,
     ) -> EmitterResult<()> {
         self.tmp_counter += 1;
-        self.loop_scopes.push(self.tmp_counter);
         let loop_start_label = format!("loop.{}.start", self.tmp_counter);
         let loop_end_label = format!("loop.{}.end", self.tmp_counter);
+        self.loop_scopes.push(LoopScope {
+            label: loop_stmt.label.clone(),
+            end_label: loop_end_label.clone(),
+            continue_label: loop_start_label.clone(),
+        });
 
         func.add_block(&loop_start_label);
         self.emit_stmt(func, &loop_stmt.body)?;
@@ -193,21 +159,207 @@ impl QBEEmitter<'_> {
         Ok(())
     }
 
+    /// emits while statement
+    fn emit_while_stmt(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        while_stmt: &WhileStmt,
+    ) -> EmitterResult<()> {
+        self.tmp_counter += 1;
+        let loop_cond_label = format!("loop.{}.cond", self.tmp_counter);
+        let loop_body_label = format!("loop.{}.body", self.tmp_counter);
+        let loop_end_label = format!("loop.{}.end", self.tmp_counter);
+        self.loop_scopes.push(LoopScope {
+            label: while_stmt.label.clone(),
+            end_label: loop_end_label.clone(),
+            continue_label: loop_cond_label.clone(),
+        });
+
+        // header: checked before every iteration.
+        func.add_block(&loop_cond_label);
+        let (_, cond_result) = self.emit_expr(func, &while_stmt.condition)?;
+        func.add_instr(qbe::Instr::Jnz(
+            cond_result,
+            loop_body_label.clone(),
+            loop_end_label.clone(),
+        ));
+
+        func.add_block(loop_body_label);
+        self.emit_stmt(func, &while_stmt.body)?;
+
+        if !func.blocks.last().is_some_and(|b| b.jumps()) {
+            func.add_instr(qbe::Instr::Jmp(loop_cond_label));
+        }
+
+        func.add_block(loop_end_label);
+        self.loop_scopes.pop();
+
+        Ok(())
+    }
+
+    /// emits for statement
+    fn emit_for_stmt(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        for_stmt: &ForStmt,
+    ) -> EmitterResult<()> {
+        // variables declared in the initialiser only live as long as the loop.
+        self.scopes.push(HashMap::new());
+        if let Some(initialiser) = &for_stmt.initialiser {
+            self.emit_stmt(func, initialiser)?;
+        }
+
+        self.tmp_counter += 1;
+        let loop_cond_label = format!("loop.{}.cond", self.tmp_counter);
+        let loop_body_label = format!("loop.{}.body", self.tmp_counter);
+        let loop_latch_label = format!("loop.{}.latch", self.tmp_counter);
+        let loop_end_label = format!("loop.{}.end", self.tmp_counter);
+        self.loop_scopes.push(LoopScope {
+            label: for_stmt.label.clone(),
+            end_label: loop_end_label.clone(),
+            continue_label: loop_latch_label.clone(),
+        });
+
+        // header: checked before every iteration, a missing condition loops forever.
+        func.add_block(&loop_cond_label);
+        if let Some(condition) = &for_stmt.condition {
+            let (_, cond_result) = self.emit_expr(func, condition)?;
+            func.add_instr(qbe::Instr::Jnz(
+                cond_result,
+                loop_body_label.clone(),
+                loop_end_label.clone(),
+            ));
+        }
+
+        func.add_block(loop_body_label);
+        self.emit_stmt(func, &for_stmt.body)?;
+
+        // latch: runs the increment, continue statements jump here.
+        func.add_block(loop_latch_label);
+        if let Some(increment) = &for_stmt.increment {
+            self.emit_expr(func, increment)?;
+        }
+        func.add_instr(qbe::Instr::Jmp(loop_cond_label));
+
+        func.add_block(loop_end_label);
+        self.loop_scopes.pop();
+        self.scopes.pop();
+
+        Ok(())
+    }
+
+    /// emits match statement as a chain of tests, one per arm in order.
+    fn emit_match_stmt(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        match_stmt: &MatchStmt,
+    ) -> EmitterResult<()> {
+        trace!("emitting match stmt");
+        let (ty, value) = self.emit_expr(func, &match_stmt.value)?;
+        self.tmp_counter += 1;
+        let match_id = self.tmp_counter;
+        let end_label = format!("match.{}.end", match_id);
+
+        for (i, arm) in match_stmt.arms.iter().enumerate() {
+            let arm_label = format!("match.{}.arm.{}", match_id, i);
+            let next_label = if i + 1 < match_stmt.arms.len() {
+                format!("match.{}.test.{}", match_id, i + 1)
+            } else {
+                end_label.clone()
+            };
+
+            func.add_block(format!("match.{}.test.{}", match_id, i));
+            let cond = match arm.pattern {
+                MatchPattern::Int(v) => {
+                    Some(self.emit_compare(func, &ty, qbe::Cmp::Eq, value.clone(), v))
+                }
+                MatchPattern::Variant(ref enum_name, ref variant) => {
+                    let v = self.variant_value(enum_name, variant, arm.metadata.span)?;
+                    Some(self.emit_compare(func, &ty, qbe::Cmp::Eq, value.clone(), v))
+                }
+                MatchPattern::Range(low, high) => {
+                    let above = self.emit_compare(func, &ty, qbe::Cmp::Sge, value.clone(), low);
+                    let below = self.emit_compare(func, &ty, qbe::Cmp::Sle, value.clone(), high);
+                    let cond = self.new_tmp();
+                    func.assign_instr(cond.clone(), ty.clone(), qbe::Instr::And(above, below));
+                    Some(cond)
+                }
+                MatchPattern::Wildcard => None,
+            };
+
+            match cond {
+                Some(cond) => func.add_instr(qbe::Instr::Jnz(cond, arm_label.clone(), next_label)),
+                None => func.add_instr(qbe::Instr::Jmp(arm_label.clone())),
+            }
+
+            func.add_block(arm_label);
+            self.emit_stmt(func, &arm.body)?;
+            if !func.blocks.last().is_some_and(|b| b.jumps()) {
+                func.add_instr(qbe::Instr::Jmp(end_label.clone()));
+            }
+        }
+
+        func.add_block(end_label);
+        Ok(())
+    }
+
+    /// Compares a value with an integer constant, returns the temporary holding the result.
+    fn emit_compare(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        ty: &qbe::Type<'static>,
+        cmp: qbe::Cmp,
+        value: qbe::Value,
+        constant: i32,
+    ) -> qbe::Value {
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            ty.clone(),
+            qbe::Instr::Cmp(ty.clone(), cmp, value, qbe::Value::Const(constant as u64)),
+        );
+        tmp
+    }
+
     /// emits loop break statement
     fn emit_break_stmt(
         &mut self,
         func: &mut qbe::Function<'static>,
-        _break_stmt: &BreakStmt,
+        break_stmt: &BreakStmt,
+    ) -> EmitterResult<()> {
+        let loop_scope = self.loop_scope(&break_stmt.label, "break")?;
+        func.add_instr(qbe::Instr::Jmp(loop_scope.end_label.clone()));
+        Ok(())
+    }
+
+    /// emits loop continue statement
+    fn emit_continue_stmt(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        continue_stmt: &ContinueStmt,
     ) -> EmitterResult<()> {
-        let loop_id = self
-            .loop_scopes
-            .last()
-            .context("break used inside a non loop scope")?;
-        let loop_end_label = format!("loop.{}.end", loop_id);
-        func.add_instr(qbe::Instr::Jmp(loop_end_label));
+        let loop_scope = self.loop_scope(&continue_stmt.label, "continue")?;
+        func.add_instr(qbe::Instr::Jmp(loop_scope.continue_label.clone()));
         Ok(())
     }
 
+    /// Finds the loop a break or continue jumps out of,
+    /// the innermost loop if no label is given.
+    fn loop_scope(&self, label: &Option<String>, keyword: &str) -> EmitterResult<&LoopScope> {
+        match label {
+            Some(label) => self
+                .loop_scopes
+                .iter()
+                .rev()
+                .find(|x| x.label.as_ref() == Some(label))
+                .with_context(|| format!("{} used with undeclared label '{}'", keyword, label)),
+            None => self
+                .loop_scopes
+                .last()
+                .with_context(|| format!("{} used inside a non loop scope", keyword)),
+        }
+    }
+
     /// emits print statement based upon expression type.
     fn emit_print_stmt(
         &mut self,
@@ -215,7 +367,13 @@ impl QBEEmitter<'_> {
         print_stmt: &PrintStmt,
     ) -> EmitterResult<()> {
         trace!("emitting print stmt expr = {:?}", print_stmt.value);
-        let (ty, value) = self.emit_expr(func, &print_stmt.value)?;
+        let (mut ty, mut value) = self.emit_expr(func, &print_stmt.value)?;
+
+        // enums are printed as the name of their variant.
+        if let Some(DType::Enum(name)) = &print_stmt.value.node_type {
+            value = self.emit_variant_name(func, name, value);
+            ty = qbe::Type::Long;
+        }
 
         let fmt = match ty {
             qbe::Type::Word => "___FMT_WORD",
@@ -249,7 +407,12 @@ impl QBEEmitter<'_> {
         trace!("emitting new return stmt expr = {:?}", &return_stmt.value);
         let mut inst = qbe::Instr::Ret(None);
         if let Some(expr) = &return_stmt.value {
-            let (_, value) = self.emit_expr(func, expr)?;
+            let (ty, mut value) = self.emit_expr(func, expr)?;
+            // ints returned from functions returning floats are converted.
+            if matches!(func.return_ty, Some(qbe::Type::Double)) && !matches!(ty, qbe::Type::Double)
+            {
+                value = self.emit_int_to_float(func, value);
+            }
             inst = qbe::Instr::Ret(Some(value));
         }
         func.add_instr(inst);
@@ -1,10 +1,11 @@
-use anyhow::{anyhow, bail};
+use anyhow::{Context, anyhow, bail};
 use log::{error, trace};
 
 use crate::{
     ast::{DType, EveTypes, Token, TokenType},
     die,
     emitter::EmitterResult,
+    source_map::Span,
 };
 
 use super::QBEEmitter;
@@ -17,7 +18,7 @@ impl QBEEmitter<'_> {
         name: String,
     ) -> EmitterResult<qbe::Value> {
         trace!("creating a new variable: {}", &name);
-        if self.get_var(&name).is_ok() {
+        if self.scopes.iter().any(|x| x.contains_key(&name)) {
             bail!("Re-declaration of variable : {}", name);
         }
 
@@ -32,7 +33,7 @@ impl QBEEmitter<'_> {
     }
 
     /// Retrieves an existing bind
-    /// Searches in reverse order of scopes.
+    /// Searches in reverse order of scopes, falls back to statics.
     pub(super) fn get_var(
         &mut self,
         name: &String,
@@ -42,6 +43,7 @@ impl QBEEmitter<'_> {
             .rev()
             .filter_map(|x| x.get(name))
             .next()
+            .or_else(|| self.globals.get(name))
             .ok_or_else(|| anyhow!("undefined variable: {}", name))
     }
 
@@ -59,6 +61,134 @@ impl QBEEmitter<'_> {
         format!("glob.{}", self.tmp_counter)
     }
 
+    /// Returns the aggregate type of an already emitted struct.
+    pub(super) fn struct_type(&self, name: &str, span: Span) -> EmitterResult<qbe::Type<'static>> {
+        let type_def = self
+            .type_defs
+            .iter()
+            .find(|x| x.name == name)
+            .cloned()
+            .with_context(|| format!("Use of undeclared struct '{}', at {}", name, span))?;
+
+        Ok(qbe::Type::Aggregate(Box::leak(Box::new(type_def))))
+    }
+
+    /// Returns qbe type used for values of the given type.
+    pub(super) fn qbe_type(&mut self, ty: &DType, span: Span) -> EmitterResult<qbe::Type<'static>> {
+        match ty {
+            DType::Derived(name) => self.struct_type(name, span),
+            DType::Array(elem, len) => self.array_type(elem, *len, span),
+            ty => qbe::Type::try_from(ty),
+        }
+    }
+
+    /// Returns the aggregate type of an array, its type definition is emitted on first use.
+    pub(super) fn array_type(
+        &mut self,
+        elem: &DType,
+        len: usize,
+        span: Span,
+    ) -> EmitterResult<qbe::Type<'static>> {
+        let elem_ty = self.qbe_type(elem, span)?;
+        let elem_name = match &elem_ty {
+            qbe::Type::Aggregate(td) => td.name.clone(),
+            ty => ty.to_string(),
+        };
+        let name = format!("array.{}.{}", elem_name, len);
+
+        if !self.type_defs.iter().any(|x| x.name == name) {
+            let typedef = qbe::TypeDef {
+                name: name.clone(),
+                align: Some(Self::type_alignment(&elem_ty)),
+                items: vec![(elem_ty, len)],
+            };
+            self.type_defs.push(typedef.clone());
+            self.module.add_type(typedef);
+        }
+
+        self.struct_type(&name, span)
+    }
+
+    /// Converts an Int value to a Float.
+    pub(super) fn emit_int_to_float(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        value: qbe::Value,
+    ) -> qbe::Value {
+        let tmp = self.new_tmp();
+        func.assign_instr(tmp.clone(), qbe::Type::Double, qbe::Instr::Swtof(value));
+        tmp
+    }
+
+    /// Copies a struct value from 'src' to 'dest', both being pointers to it.
+    pub(super) fn emit_struct_copy(
+        &self,
+        func: &mut qbe::Function<'static>,
+        ty: &qbe::Type<'static>,
+        src: qbe::Value,
+        dest: qbe::Value,
+    ) {
+        func.add_instr(qbe::Instr::Blit(src, dest, ty.size()));
+    }
+
+    /// Allocates a stack slot of 'size' bytes in the start block of the function,
+    /// so that a slot allocated inside a loop is reused by every iteration.
+    pub(super) fn emit_entry_alloc(func: &mut qbe::Function<'static>, slot: qbe::Value, size: u64) {
+        func.blocks
+            .first_mut()
+            .expect("Start block must be present")
+            .items
+            .insert(
+                0,
+                qbe::BlockItem::Statement(qbe::Statement::Assign(
+                    slot,
+                    qbe::Type::Long,
+                    qbe::Instr::Alloc8(size),
+                )),
+            );
+    }
+
+    /// Stores 'value' at 'ptr', structs and arrays are copied.
+    pub(super) fn emit_store(
+        &self,
+        func: &mut qbe::Function<'static>,
+        ty: &qbe::Type<'static>,
+        ptr: qbe::Value,
+        value: qbe::Value,
+    ) {
+        if matches!(ty, qbe::Type::Aggregate(_)) {
+            self.emit_struct_copy(func, ty, value, ptr);
+        } else {
+            func.add_instr(qbe::Instr::Store(ty.clone(), ptr, value));
+        }
+    }
+
+    /// Same as 'assign_instr' but keeps aggregate types as they are,
+    /// qbe needs them for calls which return structs.
+    pub(super) fn assign_aggregate(
+        func: &mut qbe::Function<'static>,
+        temp: qbe::Value,
+        ty: qbe::Type<'static>,
+        instr: qbe::Instr<'static>,
+    ) {
+        func.blocks
+            .last_mut()
+            .expect("Last block must be present")
+            .items
+            .push(qbe::BlockItem::Statement(qbe::Statement::Assign(
+                temp, ty, instr,
+            )));
+    }
+
+    /// Type of the temporaries holding a value of 'ty',
+    /// structs are held as pointers to them.
+    pub(super) fn tmp_type(ty: &qbe::Type<'static>) -> qbe::Type<'static> {
+        match ty {
+            qbe::Type::Aggregate(_) => qbe::Type::Long,
+            ty => ty.clone(),
+        }
+    }
+
     /// Get maximum alignment of a type
     pub(super) fn type_alignment(ty: &qbe::Type) -> u64 {
         match ty {
@@ -92,6 +222,10 @@ impl TryFrom<DType> for qbe::Type<'_> {
             DType::Primitive(EveTypes::String) => Ok(qbe::Type::Long),
             DType::Primitive(EveTypes::Void) => Ok(qbe::Type::Word),
             DType::Derived(_) => Err(anyhow!("qbe::Type::TryFrom<EveTypes> recieved type = Void")),
+            DType::Array(..) => Err(anyhow!("qbe::Type::TryFrom<DType> recieved an array type")),
+            // vectors are pointers to their header in the runtime library.
+            DType::Vector(_) | DType::Pointer(_) => Ok(qbe::Type::Long),
+            DType::Enum(_) => Ok(qbe::Type::Word),
         }
     }
 }
@@ -106,6 +240,10 @@ impl TryFrom<&DType> for qbe::Type<'_> {
             DType::Primitive(EveTypes::String) => Ok(qbe::Type::Long),
             DType::Primitive(EveTypes::Void) => Ok(qbe::Type::Word),
             DType::Derived(_) => Err(anyhow!("qbe::Type::TryFrom<EveTypes> recieved type = Void")),
+            DType::Array(..) => Err(anyhow!("qbe::Type::TryFrom<DType> recieved an array type")),
+            // vectors are pointers to their header in the runtime library.
+            DType::Vector(_) | DType::Pointer(_) => Ok(qbe::Type::Long),
+            DType::Enum(_) => Ok(qbe::Type::Word),
         }
     }
 }
@@ -130,6 +268,7 @@ impl TryFrom<Token> for qbe::Type<'_> {
         match value.ttype {
             TokenType::TypeInt => Ok(qbe::Type::Word),
             TokenType::TypeFloat => Ok(qbe::Type::Double),
+            TokenType::TypeString => Ok(qbe::Type::Long),
             TokenType::TypeVoid => Err(anyhow!("qbe::Type::TryFrom recieved type = TypeVoid")),
             v => {
                 die!("qbe::Value::from failed, recieved token type: {}", v);
@@ -0,0 +1,153 @@
+use crate::ast::{CallExpr, DType, FieldAccessExpr, IndexExpr, VecNewExpr};
+use crate::bail_at;
+use crate::emitter::EmitterResult;
+use log::trace;
+use qbe;
+
+use super::QBEEmitter;
+
+// Vectors are pointers to a header managed by the runtime library,
+// which hands out addresses of elements for the emitted code to load from and store to.
+impl QBEEmitter<'_> {
+    /// Emits creation of an empty vector.
+    pub(super) fn emit_vec_new(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        vec: &VecNewExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting vector = {:?}", vec);
+        let elem_ty = self.qbe_type(&vec.elem_type, vec.metadata.span)?;
+
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Call(
+                "___eve_vec_new".into(),
+                vec![(qbe::Type::Long, qbe::Value::Const(elem_ty.size()))],
+                None,
+            ),
+        );
+
+        Ok((qbe::Type::Long, tmp))
+    }
+
+    /// Emits a call to one of the methods of a vector.
+    pub(super) fn emit_method_call(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        call: &CallExpr,
+        fiac: &FieldAccessExpr,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        trace!("emitting method call = {:?}", call);
+        let Some(DType::Vector(elem)) = &fiac.parent.node_type else {
+            bail_at!(call.metadata.span, "Methods are only supported for vectors");
+        };
+        let elem_ty = self.qbe_type(elem, call.metadata.span)?;
+        let (_, vec) = self.emit_expr(func, &fiac.parent)?;
+
+        match fiac.field.as_str() {
+            "push" => {
+                let (arg_ty, mut value) = self.emit_expr(func, &call.args[0])?;
+                if matches!(elem_ty, qbe::Type::Double) && !matches!(arg_ty, qbe::Type::Double) {
+                    value = self.emit_int_to_float(func, value);
+                }
+                // pushing may move the elements, so structs and arrays which may live
+                // in the vector, like 'v.push(v[0])', are copied out first.
+                if matches!(elem_ty, qbe::Type::Aggregate(_)) && !Self::creates_value(&call.args[0])
+                {
+                    let copy = self.new_tmp();
+                    Self::emit_entry_alloc(func, copy.clone(), elem_ty.size());
+                    self.emit_struct_copy(func, &elem_ty, value, copy.clone());
+                    value = copy;
+                }
+                let slot = self.emit_elem_call(func, "___eve_vec_push", vec);
+                self.emit_store(func, &elem_ty, slot, value);
+                Ok((qbe::Type::Word, qbe::Value::Const(0)))
+            }
+            "pop" => {
+                let slot = self.emit_elem_call(func, "___eve_vec_pop", vec);
+                Ok((elem_ty.clone(), self.emit_popped(func, &elem_ty, slot)))
+            }
+            "len" => {
+                let tmp = self.new_tmp();
+                func.assign_instr(
+                    tmp.clone(),
+                    qbe::Type::Word,
+                    qbe::Instr::Call("___eve_vec_len".into(), vec![(qbe::Type::Long, vec)], None),
+                );
+                Ok((qbe::Type::Word, tmp))
+            }
+            method => bail_at!(
+                call.metadata.span,
+                "No method '{}' on type '{}'",
+                method,
+                DType::Vector(elem.clone())
+            ),
+        }
+    }
+
+    /// Emits the address of a vector element, the runtime library checks it against the length.
+    pub(super) fn emit_vec_elem_ptr(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        idx: &IndexExpr,
+        elem: &DType,
+    ) -> EmitterResult<(qbe::Type<'static>, qbe::Value)> {
+        let elem_ty = self.qbe_type(elem, idx.metadata.span)?;
+        let (_, vec) = self.emit_expr(func, &idx.object)?;
+        let (_, index) = self.emit_expr(func, &idx.index)?;
+
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Call(
+                "___eve_vec_get".into(),
+                vec![(qbe::Type::Long, vec), (qbe::Type::Word, index)],
+                None,
+            ),
+        );
+
+        Ok((elem_ty, tmp))
+    }
+
+    /// Calls a runtime function which takes a vector and returns the address of an element.
+    fn emit_elem_call(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        name: &str,
+        vec: qbe::Value,
+    ) -> qbe::Value {
+        let tmp = self.new_tmp();
+        func.assign_instr(
+            tmp.clone(),
+            qbe::Type::Long,
+            qbe::Instr::Call(name.into(), vec![(qbe::Type::Long, vec)], None),
+        );
+        tmp
+    }
+
+    /// Loads a popped element from 'slot', structs and arrays are copied out of the vector
+    /// since the slot is reused by the next push.
+    /// Each pop copies into its own stack slot, which is reused when the pop runs in a loop.
+    fn emit_popped(
+        &mut self,
+        func: &mut qbe::Function<'static>,
+        elem_ty: &qbe::Type<'static>,
+        slot: qbe::Value,
+    ) -> qbe::Value {
+        let tmp = self.new_tmp();
+        if matches!(elem_ty, qbe::Type::Aggregate(_)) {
+            Self::emit_entry_alloc(func, tmp.clone(), elem_ty.size());
+            self.emit_struct_copy(func, elem_ty, slot, tmp.clone());
+        } else {
+            func.assign_instr(
+                tmp.clone(),
+                elem_ty.clone(),
+                qbe::Instr::Load(elem_ty.clone(), slot),
+            );
+        }
+        tmp
+    }
+}
@@ -1,77 +1,144 @@
-use anyhow::bail;
-use log::{debug, error, trace};
+use log::{debug, trace};
 
 pub use crate::ast::{LiteralValue, Token, TokenType};
-use crate::die;
+use crate::diagnostic::Diagnostic;
+use crate::err_at;
+use crate::source_map::{FileId, Span};
 use crate::utils::{is_alpha, is_alphanumeric, is_numeric};
 
 pub struct Lexer<'a> {
     // Input source as String.
     in_src: &'a String,
 
-    // input source string as characters.
-    in_chars: Vec<char>,
-
-    // input string length.
-    in_length: usize,
-
-    // start position of current scanning token.
+    // byte offset of the start of current scanning token.
     start: usize,
 
-    // current cursor poisiton
+    // byte offset of the current cursor poisiton, always at a char boundary.
     current: usize,
 
     // line number
     line: usize,
 
+    // position where the current line starts.
+    line_start: usize,
+
+    // line and column at which the current scanning token starts.
+    start_line: usize,
+    start_col: usize,
+
+    // id of the file being scanned.
+    file_id: FileId,
+
     // scanned tokens
     tokens: Vec<Token>,
+
+    // errors found while scanning.
+    diagnostics: Vec<Diagnostic>,
+
+    // doc comments waiting to be attached to the next token.
+    docs: Vec<String>,
 }
 
 impl<'a> From<&'a String> for Lexer<'a> {
     fn from(in_src: &'a String) -> Self {
+        Self::from((in_src, 0))
+    }
+}
+
+impl<'a> From<(&'a String, FileId)> for Lexer<'a> {
+    fn from(value: (&'a String, FileId)) -> Self {
+        let (in_src, file_id) = value;
         Self {
             in_src,
-            in_chars: in_src.clone().chars().collect(),
-            in_length: in_src.len(),
             start: 0,
             current: 0,
             line: 1,
+            line_start: 0,
+            start_line: 1,
+            start_col: 1,
+            file_id,
             tokens: vec![],
+            diagnostics: vec![],
+            docs: vec![],
         }
     }
 }
 
 impl Lexer<'_> {
-    /// Starts lexer
-    pub fn start(&mut self) -> anyhow::Result<()> {
+    /// Starts lexer, scans the whole input even if errors are found.
+    /// Invalid input is added as error tokens, returns all errors found.
+    pub fn start(&mut self) -> Result<(), Vec<Diagnostic>> {
         debug!("start scanning tokens.");
         while !self.is_at_end() {
-            self.start = self.current;
-            self.scan_token()?;
+            self.start_token();
+            self.scan_token();
         }
+        self.start_token();
         self.add_basic_token(TokenType::Eof);
+
+        if !self.diagnostics.is_empty() {
+            return Err(std::mem::take(&mut self.diagnostics));
+        }
         Ok(())
     }
 
     /// Scans indiviual tokens.
-    fn scan_token(&mut self) -> anyhow::Result<()> {
+    fn scan_token(&mut self) {
         let current_char = self.advance();
         match current_char {
             '(' => self.add_basic_token(TokenType::LeftParen),
             ')' => self.add_basic_token(TokenType::RightParen),
             '{' => self.add_basic_token(TokenType::LeftBrace),
             '}' => self.add_basic_token(TokenType::RightBrace),
+            '[' => self.add_basic_token(TokenType::LeftBracket),
+            ']' => self.add_basic_token(TokenType::RightBracket),
             ',' => self.add_basic_token(TokenType::Comma),
-            '.' => self.add_basic_token(TokenType::Dot),
-            '+' => self.add_basic_token(TokenType::Plus),
-            ':' => self.add_basic_token(TokenType::Colon),
+            '.' => {
+                if self.match_char('.') {
+                    if self.match_char('=') {
+                        self.add_basic_token(TokenType::DotDotEqual);
+                    } else {
+                        self.add_basic_token(TokenType::DotDot);
+                    }
+                } else {
+                    self.add_basic_token(TokenType::Dot);
+                }
+            }
+            '+' => {
+                if self.match_char('=') {
+                    self.add_basic_token(TokenType::PlusEqual);
+                } else if self.match_char('+') {
+                    self.add_basic_token(TokenType::PlusPlus);
+                } else {
+                    self.add_basic_token(TokenType::Plus);
+                }
+            }
+            ':' => {
+                if self.match_char(':') {
+                    self.add_basic_token(TokenType::ColonColon);
+                } else {
+                    self.add_basic_token(TokenType::Colon);
+                }
+            }
+            '&' => self.add_basic_token(TokenType::Ampersand),
             ';' => self.add_basic_token(TokenType::Semicolon),
-            '*' => self.add_basic_token(TokenType::Star),
-            '%' => self.add_basic_token(TokenType::Mod),
+            '*' => {
+                if self.match_char('=') {
+                    self.add_basic_token(TokenType::StarEqual);
+                } else {
+                    self.add_basic_token(TokenType::Star);
+                }
+            }
+            '%' => {
+                if self.match_char('=') {
+                    self.add_basic_token(TokenType::ModEqual);
+                } else {
+                    self.add_basic_token(TokenType::Mod);
+                }
+            }
 
             '\n' => {
-                self.line += 1;
+                self.new_line();
             }
 
             ' ' | '\t' | '\r' => {}
@@ -79,10 +146,23 @@ impl Lexer<'_> {
             '/' => {
                 // a line starting with '//'
                 if self.match_char('/') {
+                    // '///' starts a doc comment, but '////' doesn't.
+                    let is_doc = self.look_ahead() == '/' && self.look_ahead_twice() != '/';
+
                     // consuming everything until end.
                     while !self.is_at_end() && self.look_ahead() != '\n' {
                         self.advance();
                     }
+
+                    if is_doc {
+                        let text = &self.in_src[self.start + 3..self.current];
+                        let text = text.strip_prefix(' ').unwrap_or(text).trim_end();
+                        self.docs.push(text.to_string());
+                    }
+                } else if self.match_char('*') {
+                    self.scan_block_comment();
+                } else if self.match_char('=') {
+                    self.add_basic_token(TokenType::SlashEqual);
                 } else {
                     self.add_basic_token(TokenType::Slash);
                 }
@@ -99,6 +179,8 @@ impl Lexer<'_> {
             '=' => {
                 if self.match_char('=') {
                     self.add_basic_token(TokenType::EqualEqual);
+                } else if self.match_char('>') {
+                    self.add_basic_token(TokenType::MatchArrow);
                 } else {
                     self.add_basic_token(TokenType::Equal);
                 }
@@ -123,13 +205,32 @@ impl Lexer<'_> {
             '-' => {
                 if self.match_char('>') {
                     self.add_basic_token(TokenType::FatArrow);
+                } else if self.match_char('=') {
+                    self.add_basic_token(TokenType::MinusEqual);
+                } else if self.match_char('-') {
+                    self.add_basic_token(TokenType::MinusMinus);
+                } else if is_numeric(self.look_ahead()) && !self.after_operand() {
+                    // negative literals are one token, so that their range is checked after negation.
+                    let first_digit = self.advance();
+                    self.scan_number(first_digit, true);
                 } else {
                     self.add_basic_token(TokenType::Minus);
                 }
             }
 
             '"' => {
-                self.scan_string();
+                self.scan_string(false);
+            }
+
+            // loop labels, 'name
+            '\'' if is_alpha(self.look_ahead()) => {
+                self.scan_label();
+            }
+
+            // raw strings, r"..."
+            'r' if self.look_ahead() == '"' => {
+                self.advance();
+                self.scan_string(true);
             }
 
             // All other we need to either parse
@@ -139,22 +240,19 @@ impl Lexer<'_> {
             _ => {
                 if is_numeric(current_char) {
                     // 1. parses numbers.
-                    self.scan_number();
+                    self.scan_number(current_char, false);
                 } else if is_alpha(current_char) {
                     // 2. parses identifier.
                     self.scan_identifier();
                 } else {
                     // 3. everything else is illegal.
-                    bail!(
-                        "Illegal character found at line {} : '{}'",
-                        self.line,
-                        current_char
+                    self.add_error_token(
+                        err_at!(self.span(), "Illegal character found : '{}'", current_char)
+                            .with_code("E0001"),
                     );
                 }
             }
         }
-
-        Ok(())
     }
 
     /// Scans identifiers, reserved or user defined
@@ -175,73 +273,473 @@ impl Lexer<'_> {
         }
     }
 
-    /// Scans a number.
-    fn scan_number(&mut self) {
+    /// Scans a loop label, the opening quote is already consumed.
+    fn scan_label(&mut self) {
+        trace!("scanning label");
+        while is_alphanumeric(self.look_ahead()) {
+            self.advance();
+        }
+        let lexeme = self.in_src[self.start..self.current].to_string();
+        let name = lexeme[1..].to_string();
+        self.add_token(TokenType::Label, lexeme, LiteralValue::String(name));
+    }
+
+    /// Returns whether the last token ends an operand, a '-' after it is a binary minus.
+    fn after_operand(&self) -> bool {
+        self.tokens.last().is_some_and(|token| {
+            matches!(
+                token.ttype,
+                TokenType::Identifier
+                    | TokenType::String
+                    | TokenType::NumberInt
+                    | TokenType::NumberFloat
+                    | TokenType::True
+                    | TokenType::False
+                    | TokenType::Null
+                    | TokenType::RightParen
+                    | TokenType::RightBracket
+                    | TokenType::PlusPlus
+                    | TokenType::MinusMinus
+                    | TokenType::Error
+            )
+        })
+    }
+
+    /// Scans a number, 'negative' is set when it follows a '-' which was already consumed.
+    /// Supports '0x', '0o' and '0b' prefixes, '_' separators, exponents
+    /// and the 'i', 'u' and 'f' suffixes.
+    fn scan_number(&mut self, first_digit: char, negative: bool) {
         trace!("scanning number");
+        let radix = match (first_digit, self.look_ahead()) {
+            ('0', 'x') => 16,
+            ('0', 'o') => 8,
+            ('0', 'b') => 2,
+            _ => 10,
+        };
+
         let mut is_float = false;
-        while is_numeric(self.look_ahead()) {
+        let body_start;
+        if radix == 10 {
+            body_start = self.current - 1;
+            self.consume_digits(10);
+
+            if self.look_ahead() == '.' && is_numeric(self.look_ahead_twice()) {
+                trace!("number is a float.");
+                is_float = true;
+
+                // consume the '.'
+                self.advance();
+                self.consume_digits(10);
+            }
+
+            let exponent_digit = match self.look_ahead_twice() {
+                '+' | '-' => self.in_src[self.current..].chars().nth(2).unwrap_or('\0'),
+                c => c,
+            };
+            if matches!(self.look_ahead(), 'e' | 'E') && is_numeric(exponent_digit) {
+                trace!("number has an exponent.");
+                is_float = true;
+
+                // consume 'e' and the sign.
+                self.advance();
+                if matches!(self.look_ahead(), '+' | '-') {
+                    self.advance();
+                }
+                self.consume_digits(10);
+            }
+        } else {
+            // consume the base prefix.
             self.advance();
+            body_start = self.current;
+            self.consume_digits(radix);
         }
 
-        if self.look_ahead() == '.' && is_numeric(self.look_ahead_twice()) {
-            trace!("number is a float.");
-            is_float = true;
+        let body = self.in_src[body_start..self.current].replace('_', "");
+        let suffix_start = self.current;
+        while is_alphanumeric(self.look_ahead()) {
+            self.advance();
+        }
+        let suffix = &self.in_src[suffix_start..self.current];
+        let lexeme = self.in_src[self.start..self.current].to_string();
+
+        let suffix = suffix.to_string();
+        match self.number_literal(radix, &body, is_float, &suffix, negative) {
+            Some(literal @ LiteralValue::NumberFloat(_)) => {
+                self.add_token(TokenType::NumberFloat, lexeme, literal)
+            }
+            Some(literal) => self.add_token(TokenType::NumberInt, lexeme, literal),
+            None => {
+                // keep a valid token around, so that parsing doesn't report errors for it.
+                let (ttype, literal) = if is_float || suffix == "f" {
+                    (TokenType::NumberFloat, LiteralValue::NumberFloat(0.0))
+                } else {
+                    (TokenType::NumberInt, LiteralValue::NumberInt(0))
+                };
+                self.add_token(ttype, lexeme, literal);
+            }
+        }
+    }
 
-            // consume the '.'
+    /// Consumes digits and '_' separators, consumes all decimal digits for
+    /// smaller radixes, they are validated later.
+    fn consume_digits(&mut self, radix: u32) {
+        let radix = radix.max(10);
+        while self.look_ahead().is_digit(radix) || self.look_ahead() == '_' {
             self.advance();
+        }
+    }
 
-            // consume rest of the characters.
-            while is_numeric(self.look_ahead()) {
-                self.advance();
+    /// Converts a scanned number into a literal value.
+    /// Invalid numbers are reported and return None.
+    /// * `radix` - base of the number.
+    /// * `body` - digits of the number without prefix, suffix and separators.
+    /// * `is_float` - whether the number had a fraction or an exponent.
+    /// * `suffix` - type suffix, can be empty.
+    /// * `negative` - whether the number is negated, the range is checked after negation.
+    fn number_literal(
+        &mut self,
+        radix: u32,
+        body: &str,
+        is_float: bool,
+        suffix: &str,
+        negative: bool,
+    ) -> Option<LiteralValue> {
+        let radix_name = match radix {
+            16 => "hexadecimal",
+            8 => "octal",
+            2 => "binary",
+            _ => "decimal",
+        };
+
+        if body.is_empty() {
+            return self.report_number(
+                err_at!(self.span(), "Missing digits after the integer base prefix")
+                    .with_code("E0005"),
+            );
+        }
+
+        // only smaller radixes can have invalid digits, floats are always decimal.
+        if let Some(digit) = body.chars().find(|c| !is_float && !c.is_digit(radix)) {
+            return self.report_number(
+                err_at!(
+                    self.span(),
+                    "Invalid digit '{}' in {} literal",
+                    digit,
+                    radix_name
+                )
+                .with_code("E0005"),
+            );
+        }
+
+        let valid_suffix = match suffix {
+            "" => true,
+            "f" => radix == 10,
+            "i" | "u" => !is_float,
+            _ => false,
+        };
+        if !valid_suffix {
+            return self.report_number(
+                err_at!(
+                    self.span(),
+                    "Invalid suffix '{}' for {} literal",
+                    suffix,
+                    if is_float { "float" } else { radix_name }
+                )
+                .with_code("E0005")
+                .with_help(
+                    "valid suffixes are 'i' for int, 'u' for unsigned int and 'f' for float",
+                ),
+            );
+        }
+
+        if is_float || suffix == "f" {
+            let mut value = body.parse::<f64>().unwrap_or(f64::INFINITY);
+            if value.is_infinite() {
+                return self.report_number(
+                    err_at!(self.span(), "Float literal is out of range")
+                        .with_code("E0004")
+                        .with_note(format!("the maximum value of float is {:e}", f64::MAX)),
+                );
+            }
+            if negative {
+                value = -value;
             }
+            return Some(LiteralValue::NumberFloat(value));
         }
 
-        // getting the literal.
-        let lexeme = self.in_src[self.start..self.current].to_string();
+        // unsigned literals are stored with the same bits in an int.
+        if suffix == "u" && negative {
+            return self.report_number(
+                err_at!(self.span(), "Integer literal is out of range")
+                    .with_code("E0004")
+                    .with_note("unsigned ints can't be negative"),
+            );
+        }
+        if suffix == "u" {
+            return match u32::from_str_radix(body, radix) {
+                Ok(value) => Some(LiteralValue::NumberInt(value as i32)),
+                Err(_) => self.report_number(
+                    err_at!(self.span(), "Integer literal is out of range")
+                        .with_code("E0004")
+                        .with_note(format!("the maximum value of unsigned int is {}", u32::MAX)),
+                ),
+            };
+        }
 
-        if is_float {
-            let literal = LiteralValue::NumberFloat(lexeme.parse::<f64>().unwrap());
-            self.add_token(TokenType::NumberFloat, lexeme, literal);
+        let digits = if negative {
+            format!("-{}", body)
         } else {
-            let literal = LiteralValue::NumberInt(lexeme.parse::<i32>().unwrap());
-            self.add_token(TokenType::NumberInt, lexeme, literal);
+            body.to_string()
+        };
+        match i32::from_str_radix(&digits, radix) {
+            Ok(value) => Some(LiteralValue::NumberInt(value)),
+            Err(_) if negative => self.report_number(
+                err_at!(self.span(), "Integer literal is out of range")
+                    .with_code("E0004")
+                    .with_note(format!("the minimum value of int is {}", i32::MIN)),
+            ),
+            Err(_) => {
+                let mut diag = err_at!(self.span(), "Integer literal is out of range")
+                    .with_code("E0004")
+                    .with_note(format!("the maximum value of int is {}", i32::MAX));
+                if u32::from_str_radix(body, radix).is_ok() {
+                    diag = diag.with_help("use the 'u' suffix for an unsigned int");
+                }
+                self.report_number(diag)
+            }
         }
     }
 
-    /// Scans a string.
-    fn scan_string(&mut self) {
-        trace!("scanning string");
+    /// Records an invalid number literal, always returns None.
+    fn report_number(&mut self, diag: Diagnostic) -> Option<LiteralValue> {
+        self.diagnostics.push(diag);
+        None
+    }
+
+    /// Scans a block comment, call after consuming the '/*'.
+    /// Block comments can be nested.
+    fn scan_block_comment(&mut self) {
+        trace!("scanning block comment");
+        let mut depth = 1;
+        while depth > 0 {
+            if self.is_at_end() {
+                let span = Span {
+                    byte_end: self.start + 2,
+                    ..self.span()
+                };
+                self.diagnostics.push(
+                    Diagnostic::error("Unterminated block comment")
+                        .with_code("E0006")
+                        .primary(span, "comment starts here")
+                        .with_help("add a closing '*/'"),
+                );
+                return;
+            }
+
+            match self.advance() {
+                '/' if self.match_char('*') => depth += 1,
+                '*' if self.match_char('/') => depth -= 1,
+                '\n' => self.new_line(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Scans a string, processes escape sequences unless it's a raw string.
+    /// * `raw` - whether the string is a raw string.
+    fn scan_string(&mut self, raw: bool) {
+        trace!("scanning string, raw = {}", raw);
+        let mut value = String::new();
+
         // consume until a single-double quote or the stream ends.
         while !self.is_at_end() && self.look_ahead() != '"' {
-            if self.look_ahead() == '\n' {
-                self.line += 1;
+            match self.advance() {
+                '\n' => {
+                    self.new_line();
+                    value.push('\n');
+                }
+                '\\' if !raw => {
+                    if let Some(c) = self.scan_escape() {
+                        value.push(c);
+                    }
+                }
+                c => value.push(c),
             }
-
-            self.advance();
         }
 
         if self.is_at_end() {
-            die!("Non terminated string.");
+            let span = Span {
+                byte_end: self.current.min(self.start + if raw { 2 } else { 1 }),
+                ..self.span()
+            };
+            self.add_error_token(
+                Diagnostic::error("Unterminated string")
+                    .with_code("E0002")
+                    .primary(span, "string starts here")
+                    .with_help("add a closing '\"'"),
+            );
+            return;
         }
 
         // consume "
         self.advance();
 
-        // add token.
-        let literal = self.in_src[self.start + 1..self.current - 1]
-            .to_string()
-            .replace("\n", "\\n");
         self.add_token(
             TokenType::String,
-            literal.clone(),
-            LiteralValue::String(literal),
+            self.in_src[self.start..self.current].to_string(),
+            LiteralValue::String(value),
         )
     }
 
+    /// Scans an escape sequence, call after consuming the '\\'.
+    /// Returns the escaped character, invalid escapes are reported and return None.
+    fn scan_escape(&mut self) -> Option<char> {
+        let escape_start = self.current - 1;
+
+        let escaped = match self.look_ahead() {
+            'n' => Some('\n'),
+            't' => Some('\t'),
+            'r' => Some('\r'),
+            '0' => Some('\0'),
+            '\\' => Some('\\'),
+            '"' => Some('"'),
+            'x' => {
+                self.advance();
+                return self.scan_hex_escape(escape_start);
+            }
+            'u' => {
+                self.advance();
+                return self.scan_unicode_escape(escape_start);
+            }
+            _ => None,
+        };
+
+        // leave the closing quote or end of input to the string scanner.
+        if self.is_at_end() {
+            self.report_escape(escape_start, "Incomplete escape sequence", None);
+            return None;
+        }
+
+        let c = self.advance();
+        if escaped.is_none() {
+            self.report_escape(
+                escape_start,
+                &format!("Unknown character escape '\\{}'", c.escape_default()),
+                Some("valid escapes are \\n \\t \\r \\0 \\\\ \\\" \\xNN \\u{NNNN}"),
+            );
+        }
+
+        if c == '\n' {
+            self.new_line();
+        }
+        escaped
+    }
+
+    /// Scans a '\\xNN' escape, call after consuming the 'x'.
+    fn scan_hex_escape(&mut self, escape_start: usize) -> Option<char> {
+        let mut digits = String::new();
+        while digits.len() < 2 && self.look_ahead().is_ascii_hexdigit() {
+            digits.push(self.advance());
+        }
+
+        if digits.len() != 2 {
+            self.report_escape(
+                escape_start,
+                "Expected two hex digits in '\\x' escape",
+                None,
+            );
+            return None;
+        }
+
+        let value = u8::from_str_radix(&digits, 16).ok()?;
+        if !value.is_ascii() {
+            self.report_escape(
+                escape_start,
+                "Out of range hex escape, must be at most '\\x7F'",
+                Some("use '\\u{...}' for non ascii characters"),
+            );
+            return None;
+        }
+        Some(value as char)
+    }
+
+    /// Scans a '\\u{NNNN}' escape, call after consuming the 'u'.
+    fn scan_unicode_escape(&mut self, escape_start: usize) -> Option<char> {
+        if !self.match_char('{') {
+            self.report_escape(
+                escape_start,
+                "Expected '{' in '\\u' escape",
+                Some("unicode escapes are written as '\\u{NNNN}'"),
+            );
+            return None;
+        }
+
+        let mut digits = String::new();
+        while self.look_ahead().is_ascii_hexdigit() {
+            digits.push(self.advance());
+        }
+
+        if !self.match_char('}') {
+            self.report_escape(
+                escape_start,
+                "Unterminated unicode escape",
+                Some("unicode escapes are written as '\\u{NNNN}'"),
+            );
+            return None;
+        }
+
+        if digits.is_empty() || digits.len() > 6 {
+            self.report_escape(
+                escape_start,
+                "Unicode escape must have between 1 and 6 hex digits",
+                None,
+            );
+            return None;
+        }
+
+        let value = u32::from_str_radix(&digits, 16).ok()?;
+        let c = char::from_u32(value);
+        if c.is_none() {
+            self.report_escape(
+                escape_start,
+                &format!("Invalid unicode character escape '\\u{{{}}}'", digits),
+                None,
+            );
+        }
+        c
+    }
+
+    /// Reports an invalid escape sequence starting at `escape_start`.
+    fn report_escape(&mut self, escape_start: usize, message: &str, help: Option<&str>) {
+        let span = Span {
+            file_id: self.file_id,
+            byte_start: escape_start,
+            byte_end: self.current,
+            line: self.line,
+            col: self.in_src[self.line_start..escape_start].chars().count() + 1,
+        };
+
+        let mut diag = Diagnostic::error(message)
+            .with_code("E0003")
+            .primary(span, "invalid escape");
+        if let Some(help) = help {
+            diag = diag.with_help(help);
+        }
+        self.diagnostics.push(diag);
+    }
+
+    /// Marks the current position as start of a new token.
+    fn start_token(&mut self) {
+        self.start = self.current;
+        self.start_line = self.line;
+        // columns are counted in chars, not bytes.
+        self.start_col = self.in_src[self.line_start..self.current].chars().count() + 1;
+    }
+
     /// Consumes and returns next character in the input stream.
     fn advance(&mut self) -> char {
-        let current_char = self.in_chars[self.current];
-        self.current += 1;
+        let current_char = self.look_ahead();
+        self.current += current_char.len_utf8();
         trace!("current char : {}", current_char);
         current_char
     }
@@ -260,49 +758,62 @@ impl Lexer<'_> {
         let token = Token {
             ttype,
             lexeme,
-            line: self.line,
+            span: self.span(),
             literal,
+            docs: std::mem::take(&mut self.docs),
         };
         trace!("Added token = {:?}", token);
         self.tokens.push(token);
     }
 
+    /// Records an error and adds the scanned text as an error token,
+    /// so that parsing can continue after it.
+    fn add_error_token(&mut self, diag: Diagnostic) {
+        self.diagnostics.push(diag);
+        self.add_basic_token(TokenType::Error);
+    }
+
+    /// Returns span of the current scanning token.
+    fn span(&self) -> Span {
+        Span {
+            file_id: self.file_id,
+            byte_start: self.start,
+            byte_end: self.current,
+            line: self.start_line,
+            col: self.start_col,
+        }
+    }
+
+    /// Marks start of a new line, call after consuming a '\n'.
+    fn new_line(&mut self) {
+        self.line += 1;
+        self.line_start = self.current;
+    }
+
     /// Checks whether stream of tokens ended.
     fn is_at_end(&self) -> bool {
-        self.current >= self.in_length
+        self.current >= self.in_src.len()
     }
 
     /// matches current char with expected, consumes if same.
     /// also returns whether it matches or not.
     fn match_char(&mut self, expected: char) -> bool {
-        if self.is_at_end() {
-            return false;
-        }
-
-        if self.in_chars[self.current] != expected {
+        if self.is_at_end() || self.look_ahead() != expected {
             return false;
         }
 
-        self.current += 1;
+        self.current += expected.len_utf8();
         true
     }
 
     // like advance but doesn't consume the token.
     fn look_ahead(&self) -> char {
-        if self.is_at_end() {
-            return '\0';
-        }
-
-        self.in_chars[self.current]
+        self.in_src[self.current..].chars().next().unwrap_or('\0')
     }
 
     // like look_ahead but looks two positions forward.
     fn look_ahead_twice(&self) -> char {
-        if self.current + 1 >= self.in_length {
-            return '\0';
-        }
-
-        self.in_chars[self.current + 1]
+        self.in_src[self.current..].chars().nth(1).unwrap_or('\0')
     }
 
     // "getter" method for tokens
@@ -2,9 +2,11 @@ pub mod ast;
 pub mod backend;
 pub mod cc_runtime;
 pub mod cli;
+pub mod diagnostic;
 pub mod emitter;
 pub mod lexer;
 pub mod parser;
 pub mod passes;
+pub mod source_map;
 pub mod type_sys;
 pub mod utils;
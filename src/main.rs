@@ -2,10 +2,12 @@ mod ast;
 mod backend;
 mod cc_runtime;
 mod cli;
+mod diagnostic;
 mod emitter;
 mod lexer;
 mod parser;
 mod passes;
+mod source_map;
 mod type_sys;
 mod utils;
 
@@ -13,33 +15,51 @@ use anyhow::{Context, bail};
 use backend::Backend;
 use backend::qbe_backend::QbeBackend;
 use colored::Colorize;
+use diagnostic::{Diagnostic, Reporter};
 use emitter::Emitter;
 use emitter::qbee::QBEEmitter;
-use evelin::utils::{ErrorType, MessageType, report_message};
 use log::{debug, info};
 use parser::Parser;
+use source_map::SourceMap;
 use std::fs;
 use std::time::Instant;
 
-pub fn init() -> anyhow::Result<()> {
+pub fn init(opts: cli::EveCliOptions) -> anyhow::Result<()> {
     let initial_time = Instant::now();
 
-    let opts = cli::init()?;
-
     let mut fn_decls = vec![];
     let mut struct_decls = vec![];
+    let mut global_decls = vec![];
+    let mut enum_decls = vec![];
+    let mut source_map = SourceMap::new();
 
     for f in &opts.file {
         let in_src = fs::read_to_string(f).context("Failed to read input file")?;
+        let file_id = source_map.add_file(f, in_src);
+        let in_src = &source_map.get(file_id).unwrap().src;
+
+        let reporter = Reporter::new(&source_map, opts.error_format);
 
-        let mut lexer = lexer::Lexer::from(&in_src);
-        lexer.start()?;
+        // parse even if lexing fails, to report as many errors as possible.
+        let mut lexer = lexer::Lexer::from((in_src, file_id));
+        let lexer_errors = lexer.start().err().unwrap_or_default();
+        reporter.report_all(&lexer_errors);
         debug!("{:?}", &lexer.tokens());
 
         let mut parser = Parser::from(lexer.tokens());
         parser.parse();
         debug!("{:?}", &parser.struct_decls);
         debug!("{:?}", &parser.fn_decls);
+        debug!("{:?}", &parser.global_decls);
+        debug!("{:?}", &parser.enum_decls);
+        reporter.report_all(&parser.diagnostics);
+        if !lexer_errors.is_empty() {
+            bail!(
+                "Failed to compile due to {} lexical error(s) and {} parsing error(s)",
+                lexer_errors.len(),
+                parser.diagnostics.len()
+            );
+        }
         if parser.errors_count != 0 {
             bail!(
                 "Failed to compile due to {} parsing error(s)",
@@ -49,17 +69,24 @@ pub fn init() -> anyhow::Result<()> {
 
         fn_decls.append(&mut parser.fn_decls);
         struct_decls.append(&mut parser.struct_decls);
+        global_decls.append(&mut parser.global_decls);
+        enum_decls.append(&mut parser.enum_decls);
     }
 
     debug!("collective = \n {:?}", struct_decls);
     debug!("collective = \n {:?}", fn_decls);
 
+    // enums can be used by every module, so their names are resolved once all modules are parsed.
+    passes::resolve_enum_types(&enum_decls, &mut fn_decls, &mut struct_decls);
+
+    let reporter = Reporter::new(&source_map, opts.error_format);
     let (mut fn_decls, mut struct_decls) = match passes::run_passes(fn_decls, struct_decls) {
-        Ok((fn_, st)) => (fn_, st),
+        Ok((fn_, st, warnings)) => {
+            reporter.report_all(&warnings);
+            (fn_, st)
+        }
         Err(errs) => {
-            for e in &errs {
-                report_message(e.to_string(), MessageType::Error(ErrorType::None));
-            }
+            reporter.report_all(&errs);
             bail!("Failed to compile due to {} error(s)", &errs.len());
         }
     };
@@ -67,19 +94,28 @@ pub fn init() -> anyhow::Result<()> {
     debug!("After passes = \n {:?}", struct_decls);
     debug!("After passes = \n {:?}", fn_decls);
 
-    let type_sys = type_sys::TypeSystem::new(&mut fn_decls, &mut struct_decls);
-    let (type_error_count, fn_decls) = type_sys.check();
-    if type_error_count != 0 {
+    let type_sys = type_sys::TypeSystem::new(
+        &mut fn_decls,
+        &mut struct_decls,
+        &mut global_decls,
+        &enum_decls,
+    );
+    let (type_errors, fn_decls) = type_sys.check();
+    reporter.report_all(&type_errors);
+    if !type_errors.is_empty() {
         bail!(
             "Failed to compile due to {} type error(s)",
-            type_error_count
+            type_errors.len()
         );
     }
 
     debug!("After typesys= \n {:?}", struct_decls);
     debug!("After typesys= \n {:?}", fn_decls);
+    debug!("After typesys= \n {:?}", global_decls);
 
-    let mut qbe_generator = QBEEmitter::from((&fn_decls, &struct_decls));
+    let mut qbe_generator =
+        QBEEmitter::from((&fn_decls, &struct_decls, &global_decls, &enum_decls))
+            .set_bounds_checks(opts.bounds_check);
     let ir = qbe_generator.emit_ir()?;
     debug!("IR: \n{}", ir);
 
@@ -91,18 +127,24 @@ pub fn init() -> anyhow::Result<()> {
     abs_outfile.set_extension("s");
     fs::write(&abs_outfile, obj_code).context("Failed to write qbe output to a file")?;
 
+    // runtime library is compiled from source along with the program.
+    let runtime_file = abs_outfile.with_extension("runtime.c");
+    cc_runtime::write_runtime(&runtime_file)?;
+
     // build executable using platform's c compiler
     let out = cc_runtime::Build::default()
         .set_c_compiler(opts.cc)
         .file(&abs_outfile)
+        .file(&runtime_file)
         .set_outfile(&opts.out)
         .set_lib_paths(opts.lib_path.unwrap_or(vec![]))
         .set_lib_names(opts.lib_name.unwrap_or(vec![]))
         .set_opt(3)
         .compile()?;
 
-    // delete temporary file.
+    // delete temporary files.
     fs::remove_file(&abs_outfile)?;
+    fs::remove_file(&runtime_file)?;
 
     let elapsed_time = initial_time.elapsed();
     if !out.stderr.is_empty() {
@@ -120,13 +162,24 @@ pub fn init() -> anyhow::Result<()> {
 }
 use std::process::ExitCode;
 fn main() -> ExitCode {
-    match init() {
+    let opts = match cli::init() {
+        Ok(opts) => opts,
+        Err(err) => {
+            Reporter::new(&SourceMap::new(), cli::ErrorFormat::Human)
+                .report(&Diagnostic::error(format!("{:#}", err)));
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let error_format = opts.error_format;
+    match init(opts) {
         Ok(()) => {
             info!("Execution finished successfully.");
             ExitCode::SUCCESS
         }
         Err(err) => {
-            report_message(format!("{:#}", err), MessageType::Error(ErrorType::None));
+            Reporter::new(&SourceMap::new(), error_format)
+                .report(&Diagnostic::error(format!("{:#}", err)));
             ExitCode::FAILURE
         }
     }
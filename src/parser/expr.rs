@@ -1,12 +1,16 @@
-use super::{MAX_NATIVE_FUNCTION_ARITY, Parser, ParserResult};
+use super::{MAX_FUNCTION_ARITY, MAX_NATIVE_FUNCTION_ARITY, Parser, ParserResult, VEC_TYPE_NAME};
 
-use anyhow::{Context, bail};
+use anyhow::Context;
 use log::trace;
 
 use crate::ast::{
-    AssignmentExpr, BinExpr, BinOp, CallExpr, Expr, FieldAccessExpr, GroupExpr, LiteralExpr,
-    LiteralValue, Metadata, NativeCallExpr, TokenType, UnOp, UnaryExpr, VariableExpr,
+    AddrOfExpr, ArrayInitExpr, AssignKind, AssignmentExpr, BinExpr, BinOp, CallExpr, DerefExpr,
+    EnumVariantExpr, ErrorExpr, Expr, FieldAccessExpr, GroupExpr, IndexExpr, LValue, LiteralExpr,
+    LiteralValue, Metadata, NativeCallExpr, StInitField, StructInitExpr, TokenType, UnOp,
+    UnaryExpr, VariableExpr, VecNewExpr,
 };
+use crate::bail_at;
+use crate::diagnostic::Diagnostic;
 
 impl Parser<'_> {
     /// Parses top-level expressions.
@@ -19,39 +23,93 @@ impl Parser<'_> {
 
         if self.match_token(&[TokenType::Equal]) {
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
 
-            let value = self
-                .assignment()
-                .context(format!("Invalid assignment, line {}", self.current().line))?;
-
-            match expr {
-                Expr::Variable(expr) => {
-                    let name = expr.name;
-                    return Ok(Expr::Assignment(Box::new(AssignmentExpr {
-                        name,
-                        value,
-                        metadata,
-                    })));
-                }
-                _ => bail!(
-                    "Cannot assign to non-variable type, line {}",
-                    self.current().line
-                ),
+            let value = self.assignment().context("Invalid assignment")?;
+            let target = self.lvalue(expr, &metadata)?;
+            return Ok(Expr::Assignment(Box::new(AssignmentExpr {
+                target,
+                kind: AssignKind::Plain,
+                value,
+                metadata,
+            })));
+        }
+
+        if self.match_token(&[
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+            TokenType::ModEqual,
+        ]) {
+            let metadata = Metadata {
+                span: self.previous().span,
+                node_type: None,
+            };
+            let op = BinOp::from(&self.previous().ttype);
+            let value = self.assignment().context("Invalid assignment")?;
+            return self.compound_assignment(expr, AssignKind::Compound(op), value, metadata);
+        }
+
+        if self.match_token(&[TokenType::PlusPlus, TokenType::MinusMinus]) {
+            let metadata = Metadata {
+                span: self.previous().span,
+                node_type: None,
             };
+            let op = BinOp::from(&self.previous().ttype);
+            let one = Expr::Literal(LiteralExpr {
+                value: LiteralValue::NumberInt(1),
+                metadata: metadata.clone(),
+            });
+            return self.compound_assignment(expr, AssignKind::Postfix(op), one, metadata);
         }
 
         Ok(expr)
     }
 
+    /// Builds assignments which update the place with 'op',
+    /// the emitter evaluates the place only once.
+    fn compound_assignment(
+        &self,
+        target: Expr,
+        kind: AssignKind,
+        value: Expr,
+        metadata: Metadata,
+    ) -> ParserResult<Expr> {
+        let target = self.lvalue(target, &metadata)?;
+        Ok(Expr::Assignment(Box::new(AssignmentExpr {
+            target,
+            kind,
+            value,
+            metadata,
+        })))
+    }
+
+    /// Converts the left side of an assignment into the place being assigned to.
+    fn lvalue(&self, target: Expr, metadata: &Metadata) -> ParserResult<LValue> {
+        match LValue::try_from(target) {
+            Ok(lvalue) => Ok(lvalue),
+            Err(expr) => Err(Diagnostic::error("Invalid left-hand side of assignment")
+                .primary(metadata.span, "cannot assign to this expression")
+                .secondary(
+                    expr.span,
+                    format!(
+                        "'{}' is not a variable, a field, an array element or a dereferenced pointer",
+                        expr
+                    ),
+                )
+                .into()),
+        }
+    }
+
     /// parses logical or expressions
     fn or(&mut self) -> ParserResult<Expr> {
         let mut left = self.and()?;
         while self.match_token(&[TokenType::Or]) {
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
             let op = BinOp::from(&self.previous().ttype);
@@ -73,7 +131,7 @@ impl Parser<'_> {
         let mut left = self.equality()?;
         while self.match_token(&[TokenType::And]) {
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
             let op = BinOp::from(&self.previous().ttype);
@@ -95,7 +153,7 @@ impl Parser<'_> {
         let mut left = self.comparsion()?;
         while self.match_token(&[TokenType::EqualEqual, TokenType::BangEqual]) {
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
             let op = BinOp::from(&self.previous().ttype);
@@ -123,7 +181,7 @@ impl Parser<'_> {
         ]) {
             let op = BinOp::from(&self.previous().ttype);
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
             let right = self.term()?;
@@ -145,7 +203,7 @@ impl Parser<'_> {
 
         while self.match_token(&[TokenType::Minus, TokenType::Plus]) {
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
             let op = BinOp::from(&self.previous().ttype);
@@ -168,7 +226,7 @@ impl Parser<'_> {
 
         while self.match_token(&[TokenType::Slash, TokenType::Star, TokenType::Mod]) {
             let metadata = Metadata {
-                line: self.previous().line,
+                span: self.previous().span,
                 node_type: None,
             };
             let op = BinOp::from(&self.previous().ttype);
@@ -192,7 +250,7 @@ impl Parser<'_> {
             let op = UnOp::from(&self.previous().ttype);
             if let Ok(operand) = self.unary() {
                 let metadata = Metadata {
-                    line: operand.line,
+                    span: operand.span,
                     node_type: None,
                 };
                 let un = UnaryExpr {
@@ -204,6 +262,32 @@ impl Parser<'_> {
             }
         }
 
+        if self.match_token(&[TokenType::Star]) {
+            let metadata = Metadata {
+                span: self.previous().span,
+                node_type: None,
+            };
+            let pointer = self.unary()?;
+            return Ok(Expr::Deref(Box::new(DerefExpr { pointer, metadata })));
+        }
+
+        if self.match_token(&[TokenType::Ampersand]) {
+            let metadata = Metadata {
+                span: self.previous().span,
+                node_type: None,
+            };
+            let target = self.unary()?;
+            let target = LValue::try_from(target).map_err(|expr| {
+                Diagnostic::error("Cannot take the address of this expression")
+                    .primary(metadata.span, "")
+                    .secondary(
+                        expr.span,
+                        format!("'{}' is not a variable, a field, an array element or a dereferenced pointer", expr),
+                    )
+            })?;
+            return Ok(Expr::AddrOf(Box::new(AddrOfExpr { target, metadata })));
+        }
+
         if self.match_token(&[TokenType::Extern]) {
             return self.native_call();
         }
@@ -227,19 +311,21 @@ impl Parser<'_> {
     /// Parses trailing native function calls and function arguments.
     fn native_finish_call(&mut self, callee: Expr) -> ParserResult<Expr> {
         let metadata = Metadata {
-            line: callee.line,
+            span: callee.span,
             node_type: None,
         };
         let mut local_call = Box::new(NativeCallExpr {
             callee,
             args: vec![],
+            return_type: None,
             metadata,
         });
 
         if !self.match_current(&TokenType::RightParen) {
             loop {
                 if local_call.args.len() >= MAX_NATIVE_FUNCTION_ARITY {
-                    bail!(
+                    bail_at!(
+                        self.current().span,
                         "parsing function exceeded the MAX_NATIVE_FUNCTION_ARITY limit of {}",
                         MAX_NATIVE_FUNCTION_ARITY
                     );
@@ -253,10 +339,11 @@ impl Parser<'_> {
             }
         }
 
-        self.consume(
-            TokenType::RightParen,
-            "Expected ')' after function arguments.",
-        )?;
+        self.consume_one_of(&[TokenType::Comma, TokenType::RightParen])?;
+
+        if self.match_token(&[TokenType::FatArrow]) {
+            local_call.return_type = Some(self.parse_type("Expected result type after '->'")?);
+        }
 
         Ok(Expr::NativeCall(local_call))
     }
@@ -264,39 +351,56 @@ impl Parser<'_> {
     /// Parses function calling expressions.
     fn call(&mut self) -> ParserResult<Expr> {
         trace!("Parsing call");
-        let callee = self.primary()?;
+        let mut callee = self.primary()?;
 
         trace!("Parser::call callee_name = {:?}", &callee);
-        if self.match_token(&[TokenType::LeftParen]) {
-            return self.finish_call(callee);
-        } else if self.match_token(&[TokenType::Dot]) {
-            return self.finish_access(callee);
+        loop {
+            if self.match_token(&[TokenType::LeftParen]) {
+                callee = self.finish_call(callee)?;
+            } else if self.match_token(&[TokenType::Dot]) {
+                callee = self.finish_access(callee)?;
+            } else if self.match_token(&[TokenType::LeftBracket]) {
+                callee = self.finish_index(callee)?;
+            } else {
+                break;
+            }
         }
 
         Ok(callee)
     }
 
-    /// Parses trailing function calls and function argument.
+    /// Parses trailing function calls and function arguments.
     fn finish_call(&mut self, callee: Expr) -> ParserResult<Expr> {
         let metadata = Metadata {
-            line: callee.line,
+            span: callee.span,
             node_type: None,
         };
         let mut local_call = Box::new(CallExpr {
             callee,
-            arg: None,
+            args: vec![],
             metadata,
         });
 
         if !self.match_current(&TokenType::RightParen) {
-            trace!("parsing function argument");
-            local_call.arg = Some(self.expr()?);
+            trace!("parsing function arguments");
+            loop {
+                if local_call.args.len() >= MAX_FUNCTION_ARITY {
+                    bail_at!(
+                        self.current().span,
+                        "parsing function exceeded the MAX_FUNCTION_ARITY limit of {}",
+                        MAX_FUNCTION_ARITY
+                    );
+                }
+
+                local_call.args.push(self.expr()?);
+
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
         }
 
-        self.consume(
-                TokenType::RightParen,
-                "Expected ')' after function argument.\n\nNote: Multiple function arguments are only supported for extern function calls, otherwise use structs."
-            )?;
+        self.consume_one_of(&[TokenType::Comma, TokenType::RightParen])?;
 
         Ok(Expr::Call(local_call))
     }
@@ -305,7 +409,7 @@ impl Parser<'_> {
     fn finish_access(&mut self, callee: Expr) -> ParserResult<Expr> {
         let field = self.consume(TokenType::Identifier, "Expected field name")?;
         let metadata = Metadata {
-            line: callee.line,
+            span: callee.span,
             node_type: None,
         };
         Ok(Expr::FieldAccess(Box::new(FieldAccessExpr {
@@ -315,11 +419,117 @@ impl Parser<'_> {
         })))
     }
 
+    /// parses trailing array indexing.
+    fn finish_index(&mut self, object: Expr) -> ParserResult<Expr> {
+        let metadata = Metadata {
+            span: self.previous().span,
+            node_type: None,
+        };
+        let index = self.expr()?;
+        self.consume(TokenType::RightBracket, "Expected ']' after array index")?;
+        Ok(Expr::Index(Box::new(IndexExpr {
+            object,
+            index,
+            metadata,
+        })))
+    }
+
+    /// Parses creation of an empty vector, Vec<T>(), returns None without consuming anything
+    /// if 'Vec' isn't followed by a type in angle brackets, like in the comparison 'Vec < b'.
+    fn vec_new(&mut self, metadata: Metadata) -> ParserResult<Option<Expr>> {
+        trace!("Parsing vector creation");
+        let start = self.current;
+        // 'Vec' and '<'
+        self.advance();
+        self.advance();
+        let Ok(elem_type) = self.vec_elem_type() else {
+            self.current = start;
+            return Ok(None);
+        };
+        self.consume(TokenType::LeftParen, "Expected '(' after vector type")?;
+        self.consume(TokenType::RightParen, "Expected ')' after '('")?;
+        Ok(Some(Expr::VecNew(Box::new(VecNewExpr {
+            elem_type,
+            metadata,
+        }))))
+    }
+
+    /// Parses array literals, [a, b, ...] or [value; count]
+    fn array_init(&mut self, metadata: Metadata) -> ParserResult<Expr> {
+        trace!("Parsing array literal");
+        let mut elements = vec![];
+        let mut count = None;
+        while !self.match_token(&[TokenType::RightBracket]) && !self.is_at_end() {
+            elements.push(self.expr()?);
+
+            if elements.len() == 1 && self.match_token(&[TokenType::Semicolon]) {
+                count = Some(self.array_len()?);
+                self.consume(TokenType::RightBracket, "Expected ']' after array length")?;
+                break;
+            }
+
+            if !self.match_current(&TokenType::RightBracket) {
+                self.consume_one_of(&[TokenType::Comma, TokenType::RightBracket])?;
+            }
+        }
+
+        Ok(Expr::ArrayInit(Box::new(ArrayInitExpr {
+            elements,
+            count,
+            metadata,
+        })))
+    }
+
+    /// Parses struct initialiser expressions, Name { field: value, ... }
+    fn struct_init(&mut self) -> ParserResult<Expr> {
+        trace!("Parsing struct initialiser");
+        let metadata = Metadata {
+            span: self.current().span,
+            node_type: None,
+        };
+        let struct_name = self.advance().lexeme.clone();
+        self.consume(TokenType::LeftBrace, "Expected '{' after struct name")?;
+
+        let mut arguments = vec![];
+        while !self.match_token(&[TokenType::RightBrace]) && !self.is_at_end() {
+            let field_metadata = Metadata {
+                span: self.current().span,
+                node_type: None,
+            };
+            let field_name = self
+                .consume(
+                    TokenType::Identifier,
+                    "Expected field name inside struct initialiser",
+                )?
+                .lexeme
+                .clone();
+            self.consume(
+                TokenType::Colon,
+                "Expected ':' after field name in struct initialiser",
+            )?;
+            let arg = self.expr()?;
+            arguments.push(StInitField {
+                field_name,
+                field_expr: arg,
+                metadata: field_metadata,
+            });
+            if !self.match_current(&TokenType::RightBrace) {
+                self.consume_one_of(&[TokenType::Comma, TokenType::RightBrace])?;
+            }
+        }
+
+        Ok(Expr::StructInit(Box::new(StructInitExpr {
+            struct_name,
+            arguments,
+            metadata,
+        })))
+    }
+
     /// Parses literal expressions.
     fn primary(&mut self) -> ParserResult<Expr> {
         trace!("Parser::primary current_token = {}", self.current());
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.current().span,
             node_type: None,
         };
         if self.match_token(&[TokenType::False]) {
@@ -331,6 +541,15 @@ impl Parser<'_> {
             return Ok(literal);
         }
 
+        if self.match_token(&[TokenType::Null]) {
+            let literal = Expr::Literal(LiteralExpr {
+                value: LiteralValue::Null,
+                metadata,
+            });
+
+            return Ok(literal);
+        }
+
         if self.match_token(&[TokenType::True]) {
             let literal = Expr::Literal(LiteralExpr {
                 value: LiteralValue::Boolean(true),
@@ -340,6 +559,12 @@ impl Parser<'_> {
             return Ok(literal);
         }
 
+        // error tokens are already reported by the lexer,
+        // keep a placeholder to keep parsing the rest of the input.
+        if self.match_token(&[TokenType::Error]) {
+            return Ok(Expr::Error(ErrorExpr { metadata }));
+        }
+
         // literal values get parsed in the lexer section, we can just clone it here.
         if self.match_token(&[
             TokenType::String,
@@ -355,18 +580,53 @@ impl Parser<'_> {
         }
 
         // grouping
-        if self.match_token(&[TokenType::LeftParen]) {
-            if let Ok(expr) = self.expr() {
-                self.consume(
-                    TokenType::RightParen,
-                    format!("Expected ')' got {} instead", self.current().ttype).as_str(),
-                )?;
-                let literal = Expr::Grouping(Box::new(GroupExpr {
-                    value: expr,
-                    metadata,
-                }));
-                return Ok(literal);
-            }
+        if self.match_token(&[TokenType::LeftParen])
+            && let Ok(expr) = self.expr()
+        {
+            self.consume(
+                TokenType::RightParen,
+                format!("Expected ')' got {} instead", self.current().ttype).as_str(),
+            )?;
+            let literal = Expr::Grouping(Box::new(GroupExpr {
+                value: expr,
+                metadata,
+            }));
+            return Ok(literal);
+        }
+
+        // array literal
+        if self.match_token(&[TokenType::LeftBracket]) {
+            return self.array_init(metadata);
+        }
+
+        // empty vector, Vec<T>()
+        if self.match_current(&TokenType::Identifier)
+            && self.current().lexeme == VEC_TYPE_NAME
+            && self.peek().ttype == TokenType::Less
+            && let Some(vec) = self.vec_new(metadata.clone())?
+        {
+            return Ok(vec);
+        }
+
+        // enum variant, Name::Variant
+        if self.match_current(&TokenType::Identifier) && self.peek().ttype == TokenType::ColonColon
+        {
+            let enum_name = self.advance().lexeme.clone();
+            self.advance();
+            let variant = self
+                .consume(TokenType::Identifier, "Expected variant name after '::'")?
+                .lexeme
+                .clone();
+            return Ok(Expr::EnumVariant(Box::new(EnumVariantExpr {
+                enum_name,
+                variant,
+                metadata,
+            })));
+        }
+
+        // struct initialiser
+        if self.match_current(&TokenType::Identifier) && self.peek().ttype == TokenType::LeftBrace {
+            return self.struct_init();
         }
 
         // identifier
@@ -378,10 +638,10 @@ impl Parser<'_> {
             return Ok(var);
         }
 
-        bail!(
-            "Expected literal, expression, or identifier received '{}' instead, line {}",
-            self.current().lexeme,
-            self.current().line
+        bail_at!(
+            self.current().span,
+            "expected expression, found {}",
+            self.found()
         );
     }
 }
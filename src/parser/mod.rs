@@ -1,4 +1,3 @@
-use anyhow::anyhow;
 use log::info;
 
 mod expr;
@@ -7,10 +6,14 @@ mod top_level;
 mod utils;
 
 pub const MAX_NATIVE_FUNCTION_ARITY: usize = 256;
+pub const MAX_FUNCTION_ARITY: usize = 256;
+/// Name of the built-in vector type, 'Vec<T>'.
+pub const VEC_TYPE_NAME: &str = "Vec";
 
 pub type ParserResult<T> = anyhow::Result<T>;
 
-use crate::ast::{FnDecl, StructDecl, Token, TokenType};
+use crate::ast::{EnumDecl, FnDecl, GlobalDecl, GlobalKind, StructDecl, Token, TokenType};
+use crate::diagnostic::Diagnostic;
 
 pub struct Parser<'a> {
     /// Vec of tokens to parse.
@@ -22,11 +25,20 @@ pub struct Parser<'a> {
     /// flag to be set if any error occurs during parsing.
     pub errors_count: usize,
 
+    /// diagnostics of all errors occured during parsing.
+    pub diagnostics: Vec<Diagnostic>,
+
     /// vec of all parsed struct declarations.
     pub struct_decls: Vec<StructDecl>,
 
     /// vec of all parsed function declarations.
     pub fn_decls: Vec<FnDecl>,
+
+    /// vec of all parsed const and static declarations.
+    pub global_decls: Vec<GlobalDecl>,
+
+    /// vec of all parsed enum declarations.
+    pub enum_decls: Vec<EnumDecl>,
 }
 
 impl<'a> From<&'a Vec<Token>> for Parser<'a> {
@@ -36,8 +48,11 @@ impl<'a> From<&'a Vec<Token>> for Parser<'a> {
             tokens,
             current: 0,
             errors_count: 0,
+            diagnostics: vec![],
             struct_decls: vec![],
             fn_decls: vec![],
+            global_decls: vec![],
+            enum_decls: vec![],
         }
     }
 }
@@ -51,7 +66,8 @@ impl Parser<'_> {
         }
     }
 
-    /// Internal parsing function, calls struct_decl or fn_decl as needed, reports parser error.
+    /// Internal parsing function, calls struct_decl, fn_decl, global_decl or enum_decl as needed,
+    /// reports parser error.
     fn parse_internal(&mut self) {
         if self.match_token(&[TokenType::Struct]) {
             match self.struct_decl() {
@@ -69,8 +85,36 @@ impl Parser<'_> {
                     self.synchronize_toplevel();
                 }
             };
+        } else if self.match_token(&[TokenType::Const, TokenType::Static]) {
+            let kind = match self.previous().ttype {
+                TokenType::Const => GlobalKind::Const,
+                _ => GlobalKind::Static,
+            };
+            match self.global_decl(kind) {
+                Ok(decl) => self.global_decls.push(decl),
+                Err(e) => {
+                    self.report_parser_error(e, false);
+                    self.synchronize_toplevel();
+                }
+            };
+        } else if self.match_token(&[TokenType::Enum]) {
+            match self.enum_decl() {
+                Ok(decl) => self.enum_decls.push(decl),
+                Err(e) => {
+                    self.report_parser_error(e, false);
+                    self.synchronize_toplevel();
+                }
+            };
         } else {
-            self.report_parser_error(anyhow!("Expected struct or function declaration"), false);
+            let err = self.expected_one_of(&[
+                TokenType::Fn,
+                TokenType::Struct,
+                TokenType::Const,
+                TokenType::Static,
+                TokenType::Enum,
+            ]);
+            self.report_parser_error(err, false);
+            self.synchronize_toplevel();
         }
     }
 }
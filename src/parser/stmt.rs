@@ -1,22 +1,37 @@
 use log::trace;
 
 use crate::ast::{
-    BlockStmt, BreakStmt, IfStmt, LetStmt, LoopStmt, Metadata, PrintStmt, ReturnStmt, StInitField,
-    Stmt, StructInitStmt, TokenType,
+    BlockStmt, BreakStmt, ContinueStmt, ErrorStmt, ForStmt, IfStmt, LetStmt, LiteralValue,
+    LoopStmt, MatchArm, MatchPattern, MatchStmt, Metadata, PrintStmt, ReturnStmt, Stmt, TokenType,
+    WhileStmt,
 };
+use crate::diagnostic::Diagnostic;
+use crate::{bail_at, err_at};
 
 use super::{Parser, ParserResult};
 
 impl Parser<'_> {
     pub(super) fn stmt(&mut self) -> ParserResult<Stmt> {
+        if self.match_current(&TokenType::Label) && self.peek().ttype == TokenType::Colon {
+            return self.labeled_loop();
+        }
+
         if self.match_token(&[TokenType::LeftBrace]) {
             return self.block();
         } else if self.match_token(&[TokenType::Let]) {
             return self.let_decl();
         } else if self.match_token(&[TokenType::Loop]) {
-            return self.loop_stmt();
+            return self.loop_stmt(None);
+        } else if self.match_token(&[TokenType::While]) {
+            return self.while_stmt(None);
+        } else if self.match_token(&[TokenType::For]) {
+            return self.for_stmt(None);
+        } else if self.match_token(&[TokenType::Match]) {
+            return self.match_stmt();
         } else if self.match_token(&[TokenType::Break]) {
             return self.break_stmt();
+        } else if self.match_token(&[TokenType::Continue]) {
+            return self.continue_stmt();
         } else if self.match_token(&[TokenType::Print]) {
             return self.print_stmt();
         } else if self.match_token(&[TokenType::Return]) {
@@ -31,14 +46,41 @@ impl Parser<'_> {
     pub(super) fn block(&mut self) -> ParserResult<Stmt> {
         trace!("parsing block stmts.");
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
         let mut block_stmts = vec![];
-        while !self.match_token(&[TokenType::RightBrace]) && !self.is_at_end() {
+        while !self.match_token(&[TokenType::RightBrace]) {
+            // functions, structs, globals and enums can't be nested, so the block was never closed.
+            if matches!(
+                self.current().ttype,
+                TokenType::Eof
+                    | TokenType::Fn
+                    | TokenType::Struct
+                    | TokenType::Const
+                    | TokenType::Static
+                    | TokenType::Enum
+            ) {
+                let err = Diagnostic::error(format!("expected `}}`, found {}", self.found()))
+                    .primary(self.current().span, "expected `}`")
+                    .secondary(metadata.span, "unclosed block starts here");
+                self.report_parser_error(err.into(), false);
+                break;
+            }
+
+            let error_metadata = Metadata {
+                span: self.current().span,
+                node_type: None,
+            };
             match self.stmt() {
                 Ok(stmt) => block_stmts.push(stmt),
-                Err(err) => self.report_parser_error(err, true),
+                Err(err) => {
+                    // keep a placeholder and carry on with the next statement.
+                    self.report_parser_error(err, true);
+                    block_stmts.push(Stmt::Error(ErrorStmt {
+                        metadata: error_metadata,
+                    }));
+                }
             }
         }
 
@@ -51,7 +93,7 @@ impl Parser<'_> {
     fn let_decl(&mut self) -> ParserResult<Stmt> {
         trace!("Parsing let declaration statement");
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
 
@@ -63,88 +105,262 @@ impl Parser<'_> {
             .lexeme
             .clone();
 
+        let ty = if self.match_token(&[TokenType::Colon]) {
+            Some(self.parse_type("Expected type after ':'")?)
+        } else {
+            None
+        };
+
         self.consume(TokenType::Equal, "Expected '=' after identifier name")?;
 
-        if self.match_current(&TokenType::Identifier) && self.peek().ttype == TokenType::LeftBrace {
-            let struct_name = self.advance().lexeme.clone();
-            self.consume(TokenType::LeftBrace, "Expected '{' after struct name")?;
-            let mut arguments = vec![];
-
-            while !self.match_token(&[TokenType::RightBrace]) && !self.is_at_end() {
-                let field_name = self
-                    .consume(
-                        TokenType::Identifier,
-                        "Expected field name inside struct initialiser",
-                    )?
-                    .lexeme
-                    .clone();
-                self.consume(
-                    TokenType::Colon,
-                    "Expected ':' after field name in struct initialiser",
-                )?;
-                let arg = self.expr()?;
-                arguments.push(StInitField {
-                    field_name,
-                    field_expr: arg,
-                    metadata: metadata.clone(),
-                });
-                if !self.match_current(&TokenType::RightBrace) {
-                    self.consume(
-                        TokenType::Comma,
-                        "Expected ',' after field value in struct declaration",
-                    )?;
-                }
-            }
+        let initialiser = self.expr()?;
+        self.consume(TokenType::Semicolon, "Expected ';' after let statement")?;
+        Ok(Stmt::Let(LetStmt {
+            name,
+            ty,
+            initialiser,
+            metadata,
+        }))
+    }
+
+    /// Parses a loop prefixed with a label, 'name: loop { }
+    fn labeled_loop(&mut self) -> ParserResult<Stmt> {
+        trace!("parsing labeled loop");
+        let label = Some(self.advance().literal.to_string());
+        self.consume(TokenType::Colon, "Expected ':' after loop label")?;
 
-            self.consume(
-                TokenType::Semicolon,
-                "Expected ';' after struct declaration",
-            )?;
-            Ok(Stmt::StructInit(StructInitStmt {
-                name,
-                struct_name,
-                arguments,
-                metadata,
-            }))
+        if self.match_token(&[TokenType::Loop]) {
+            self.loop_stmt(label)
+        } else if self.match_token(&[TokenType::While]) {
+            self.while_stmt(label)
+        } else if self.match_token(&[TokenType::For]) {
+            self.for_stmt(label)
         } else {
-            let initialiser = self.expr()?;
-            self.consume(TokenType::Semicolon, "Expected ';' after let statement")?;
-            Ok(Stmt::Let(LetStmt {
-                name,
-                initialiser,
-                metadata,
-            }))
+            Err(self.expected_one_of(&[TokenType::Loop, TokenType::While, TokenType::For]))
+        }
+    }
+
+    /// Parses the optional label after break and continue.
+    fn jump_label(&mut self) -> Option<String> {
+        if self.match_token(&[TokenType::Label]) {
+            return Some(self.previous().literal.to_string());
         }
+        None
     }
 
-    fn loop_stmt(&mut self) -> ParserResult<Stmt> {
+    fn loop_stmt(&mut self, label: Option<String>) -> ParserResult<Stmt> {
         trace!("parsing loop stmt");
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
 
         self.consume(TokenType::LeftBrace, "Expected '{' after 'loop'")?;
         let body = self.block()?;
-        Ok(Stmt::Loop(Box::new(LoopStmt { body, metadata })))
+        Ok(Stmt::Loop(Box::new(LoopStmt {
+            label,
+            body,
+            metadata,
+        })))
+    }
+
+    fn while_stmt(&mut self, label: Option<String>) -> ParserResult<Stmt> {
+        trace!("parsing while stmt");
+        let metadata = Metadata {
+            span: self.previous().span,
+            node_type: None,
+        };
+
+        self.consume(TokenType::LeftParen, "Expected '(' after 'while'")?;
+        let condition = self.expr()?;
+        self.consume(TokenType::RightParen, "Expected ')' after while condition")?;
+        self.consume(TokenType::LeftBrace, "Expected '{' after while condition")?;
+        let body = self.block()?;
+        Ok(Stmt::While(Box::new(WhileStmt {
+            label,
+            condition,
+            body,
+            metadata,
+        })))
+    }
+
+    fn for_stmt(&mut self, label: Option<String>) -> ParserResult<Stmt> {
+        trace!("parsing for stmt");
+        let metadata = Metadata {
+            span: self.previous().span,
+            node_type: None,
+        };
+
+        self.consume(TokenType::LeftParen, "Expected '(' after 'for'")?;
+
+        // both let and expression statements consume the ';' themselves.
+        let initialiser = if self.match_token(&[TokenType::Semicolon]) {
+            None
+        } else if self.match_token(&[TokenType::Let]) {
+            Some(self.let_decl()?)
+        } else {
+            Some(self.expression_stmt()?)
+        };
+
+        let mut condition = None;
+        if !self.match_current(&TokenType::Semicolon) {
+            condition = Some(self.expr()?);
+        }
+        self.consume(TokenType::Semicolon, "Expected ';' after for condition")?;
+
+        let mut increment = None;
+        if !self.match_current(&TokenType::RightParen) {
+            increment = Some(self.expr()?);
+        }
+        self.consume(TokenType::RightParen, "Expected ')' after for clauses")?;
+
+        self.consume(TokenType::LeftBrace, "Expected '{' after for clauses")?;
+        let body = self.block()?;
+        Ok(Stmt::For(Box::new(ForStmt {
+            label,
+            initialiser,
+            condition,
+            increment,
+            body,
+            metadata,
+        })))
+    }
+
+    fn match_stmt(&mut self) -> ParserResult<Stmt> {
+        trace!("parsing match stmt");
+        let metadata = Metadata {
+            span: self.previous().span,
+            node_type: None,
+        };
+
+        self.consume(TokenType::LeftParen, "Expected '(' after 'match'")?;
+        let value = self.expr()?;
+        self.consume(TokenType::RightParen, "Expected ')' after match value")?;
+        self.consume(TokenType::LeftBrace, "Expected '{' after match value")?;
+
+        let mut arms = vec![];
+        while !self.match_token(&[TokenType::RightBrace]) {
+            if self.is_at_end() {
+                return Err(self.expected_one_of(&[TokenType::RightBrace]));
+            }
+            arms.push(self.match_arm()?);
+        }
+
+        Ok(Stmt::Match(Box::new(MatchStmt {
+            value,
+            arms,
+            metadata,
+        })))
+    }
+
+    /// Parses a single match arm, pattern => stmt
+    fn match_arm(&mut self) -> ParserResult<MatchArm> {
+        let metadata = Metadata {
+            span: self.current().span,
+            node_type: None,
+        };
+
+        let pattern = self.match_pattern()?;
+        self.consume(TokenType::MatchArrow, "Expected '=>' after match pattern")?;
+        let body = self.stmt()?;
+
+        Ok(MatchArm {
+            pattern,
+            body,
+            metadata,
+        })
+    }
+
+    /// Parses a match pattern, an integer, a range of integers, an enum variant or '_'.
+    fn match_pattern(&mut self) -> ParserResult<MatchPattern> {
+        if self.match_current(&TokenType::Identifier) && self.current().lexeme == "_" {
+            self.advance();
+            return Ok(MatchPattern::Wildcard);
+        }
+
+        // enum variant, Name::Variant
+        if self.match_current(&TokenType::Identifier) && self.peek().ttype == TokenType::ColonColon
+        {
+            let enum_name = self.advance().lexeme.clone();
+            self.advance();
+            let variant = self
+                .consume(TokenType::Identifier, "Expected variant name after '::'")?
+                .lexeme
+                .clone();
+            return Ok(MatchPattern::Variant(enum_name, variant));
+        }
+
+        let span = self.current().span;
+        let low = self.pattern_int()?;
+        if self.match_token(&[TokenType::DotDot, TokenType::DotDotEqual]) {
+            let inclusive = self.previous().ttype == TokenType::DotDotEqual;
+            let high = self.pattern_int()?;
+            let high = if inclusive {
+                Some(high)
+            } else {
+                high.checked_sub(1)
+            };
+
+            return match high {
+                Some(high) if low <= high => Ok(MatchPattern::Range(low, high)),
+                _ => {
+                    // the arm is still well formed, report without losing the rest of the match.
+                    let err = err_at!(span, "Range pattern doesn't match any value");
+                    self.report_parser_error(err.into(), false);
+                    Ok(MatchPattern::Int(low))
+                }
+            };
+        }
+
+        Ok(MatchPattern::Int(low))
+    }
+
+    /// Parses a possibly negative integer literal inside a pattern.
+    fn pattern_int(&mut self) -> ParserResult<i32> {
+        let negative = self.match_token(&[TokenType::Minus]);
+        if !self.match_token(&[TokenType::NumberInt]) {
+            return Err(self.expected_one_of(&[TokenType::NumberInt]));
+        }
+
+        match self.previous().literal {
+            LiteralValue::NumberInt(v) if negative => Ok(v.wrapping_neg()),
+            LiteralValue::NumberInt(v) => Ok(v),
+            _ => bail_at!(self.previous().span, "Expected integer literal in pattern"),
+        }
     }
 
     fn break_stmt(&mut self) -> ParserResult<Stmt> {
-        trace!("parsing loop stmt");
+        trace!("parsing break stmt");
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
 
+        let label = self.jump_label();
         self.consume(TokenType::Semicolon, "Expected ';' after break statement")?;
-        Ok(Stmt::Break(BreakStmt { metadata }))
+        Ok(Stmt::Break(BreakStmt { label, metadata }))
+    }
+
+    fn continue_stmt(&mut self) -> ParserResult<Stmt> {
+        trace!("parsing continue stmt");
+        let metadata = Metadata {
+            span: self.previous().span,
+            node_type: None,
+        };
+
+        let label = self.jump_label();
+        self.consume(
+            TokenType::Semicolon,
+            "Expected ';' after continue statement",
+        )?;
+        Ok(Stmt::Continue(ContinueStmt { label, metadata }))
     }
 
     fn if_stmt(&mut self) -> ParserResult<Stmt> {
         trace!("Parsing if stmt");
 
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
 
@@ -171,7 +387,7 @@ impl Parser<'_> {
     fn print_stmt(&mut self) -> ParserResult<Stmt> {
         trace!("Parsing print stmt");
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
 
@@ -183,7 +399,7 @@ impl Parser<'_> {
     fn return_stmt(&mut self) -> ParserResult<Stmt> {
         trace!("Parsing return stmt");
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.previous().span,
             node_type: None,
         };
         let mut stmt = ReturnStmt {
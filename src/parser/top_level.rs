@@ -1,13 +1,19 @@
 use anyhow::bail;
 
-use crate::ast::{DType, EveTypes, FnDecl, FnStDeclField, Metadata, Stmt, StructDecl, TokenType};
+use crate::ast::{
+    DType, EnumDecl, EnumVariant, EveTypes, Expr, FnDecl, FnStDeclField, GlobalDecl, GlobalKind,
+    LiteralExpr, LiteralValue, Metadata, Stmt, StructDecl, TokenType,
+};
+use crate::{bail_at, err_at};
 
-use super::{Parser, ParserResult};
+use super::{MAX_FUNCTION_ARITY, Parser, ParserResult, VEC_TYPE_NAME};
 
 impl Parser<'_> {
     pub(super) fn fn_decl(&mut self) -> ParserResult<FnDecl> {
+        // docs are attached to the 'fn' keyword.
+        let docs = self.previous().docs.clone();
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.current().span,
             node_type: None,
         };
 
@@ -17,45 +23,37 @@ impl Parser<'_> {
             .clone();
 
         self.consume(TokenType::LeftParen, "Expected '(' after function name")?;
-        let mut parameter = None;
-        if self.match_current(&TokenType::Identifier) {
-            let field_name = self.advance().lexeme.clone();
-            self.consume(TokenType::Colon, "Expected ':' after function parameter")?;
+        let mut parameters = vec![];
+        if !self.match_current(&TokenType::RightParen) {
+            loop {
+                if parameters.len() >= MAX_FUNCTION_ARITY {
+                    bail_at!(
+                        self.current().span,
+                        "function declaration exceeded the MAX_FUNCTION_ARITY limit of {}",
+                        MAX_FUNCTION_ARITY
+                    );
+                }
 
-            let field_type = if self.current().is_a_basic_type() {
-                DType::Primitive(EveTypes::try_from(self.advance())?)
-            } else {
-                let d = self.consume(TokenType::Identifier, "Expected parameter type")?;
-                DType::Derived(d.lexeme.clone())
-            };
+                parameters.push(self.fn_parameter()?);
 
-            parameter = Some(FnStDeclField {
-                field_name,
-                field_type,
-                metadata: metadata.clone(),
-            });
+                if !self.match_token(&[TokenType::Comma]) {
+                    break;
+                }
+            }
         }
 
-        self.consume(
-            TokenType::RightParen,
-            "Expected ')' after function parameter",
-        )?;
+        self.consume_one_of(&[TokenType::Comma, TokenType::RightParen])?;
 
         self.consume(
             TokenType::FatArrow,
-            "Expected '->' after function parameter",
+            "Expected '->' after function parameters",
         )?;
 
         if !self.current().is_a_type() {
-            bail!("Expected function return type");
+            bail_at!(self.current().span, "Expected function return type");
         }
 
-        let return_type = if self.current().is_a_basic_type() {
-            DType::Primitive(EveTypes::try_from(self.advance())?)
-        } else {
-            let d = self.consume(TokenType::Identifier, "Expected parameter type")?;
-            DType::Derived(d.lexeme.clone())
-        };
+        let return_type = self.parse_type("Expected function return type")?;
 
         self.consume(
             TokenType::LeftBrace,
@@ -69,16 +67,42 @@ impl Parser<'_> {
 
         Ok(FnDecl {
             name,
-            parameter,
+            parameters,
             body,
             return_type,
             metadata,
+            docs,
+        })
+    }
+
+    /// Parses a single function parameter.
+    fn fn_parameter(&mut self) -> ParserResult<FnStDeclField> {
+        let field_metadata = Metadata {
+            span: self.current().span,
+            node_type: None,
+        };
+        let docs = self.current().docs.clone();
+        let field_name = self
+            .consume(TokenType::Identifier, "Expected parameter name")?
+            .lexeme
+            .clone();
+        self.consume(TokenType::Colon, "Expected ':' after function parameter")?;
+
+        let field_type = self.parse_type("Expected parameter type")?;
+
+        Ok(FnStDeclField {
+            field_name,
+            field_type,
+            metadata: field_metadata,
+            docs,
         })
     }
 
     pub(super) fn struct_decl(&mut self) -> ParserResult<StructDecl> {
+        // docs are attached to the 'struct' keyword.
+        let docs = self.previous().docs.clone();
         let metadata = Metadata {
-            line: self.current().line,
+            span: self.current().span,
             node_type: None,
         };
         let name = self
@@ -90,6 +114,11 @@ impl Parser<'_> {
 
         let mut fields = vec![];
         while !self.match_token(&[TokenType::RightBrace]) && !self.is_at_end() {
+            let field_metadata = Metadata {
+                span: self.current().span,
+                node_type: None,
+            };
+            let field_docs = self.current().docs.clone();
             let field_name = self
                 .consume(TokenType::Identifier, "Expected field name")?
                 .lexeme
@@ -97,20 +126,17 @@ impl Parser<'_> {
 
             self.consume(TokenType::Colon, "Expected ':' after field name")?;
 
-            let field_type = if self.current().is_a_basic_type() {
-                DType::Primitive(EveTypes::try_from(self.advance())?)
-            } else {
-                DType::Derived(self.advance().lexeme.clone())
-            };
+            let field_type = self.parse_type("Expected field type")?;
 
             fields.push(FnStDeclField {
                 field_name,
                 field_type,
-                metadata: metadata.clone(),
+                metadata: field_metadata,
+                docs: field_docs,
             });
 
             if !self.match_current(&TokenType::RightBrace) {
-                self.consume(TokenType::Comma, "Expected ',' after field name")?;
+                self.consume_one_of(&[TokenType::Comma, TokenType::RightBrace])?;
             }
         }
 
@@ -118,6 +144,208 @@ impl Parser<'_> {
             name,
             fields,
             metadata,
+            docs,
+        })
+    }
+
+    /// Parses an enum declaration, variants without a value follow the previous one.
+    pub(super) fn enum_decl(&mut self) -> ParserResult<EnumDecl> {
+        // docs are attached to the 'enum' keyword.
+        let docs = self.previous().docs.clone();
+        let metadata = Metadata {
+            span: self.current().span,
+            node_type: None,
+        };
+        let name = self
+            .consume(TokenType::Identifier, "Expected enum name")?
+            .lexeme
+            .clone();
+
+        self.consume(TokenType::LeftBrace, "Expected '{' after enum name")?;
+
+        let mut variants: Vec<EnumVariant> = vec![];
+        while !self.match_token(&[TokenType::RightBrace]) && !self.is_at_end() {
+            let variant_metadata = Metadata {
+                span: self.current().span,
+                node_type: None,
+            };
+            let variant_name = self
+                .consume(TokenType::Identifier, "Expected variant name")?
+                .lexeme
+                .clone();
+
+            let value = if self.match_token(&[TokenType::Equal]) {
+                self.variant_value()?
+            } else {
+                match variants.last() {
+                    Some(prev) => prev.value.checked_add(1).ok_or_else(|| {
+                        err_at!(
+                            variant_metadata.span,
+                            "Value of variant '{}' overflows an int",
+                            variant_name
+                        )
+                    })?,
+                    None => 0,
+                }
+            };
+
+            variants.push(EnumVariant {
+                name: variant_name,
+                value,
+                metadata: variant_metadata,
+            });
+
+            if !self.match_current(&TokenType::RightBrace) {
+                self.consume_one_of(&[TokenType::Comma, TokenType::RightBrace])?;
+            }
+        }
+
+        if variants.is_empty() {
+            bail_at!(
+                metadata.span,
+                "Enum '{}' must have at least one variant",
+                name
+            );
+        }
+
+        Ok(EnumDecl {
+            name,
+            variants,
+            metadata,
+            docs,
+        })
+    }
+
+    /// Parses the value given to an enum variant, an optionally negated integer literal.
+    fn variant_value(&mut self) -> ParserResult<i32> {
+        let negative = self.match_token(&[TokenType::Minus]);
+        let value = self.consume(TokenType::NumberInt, "Expected integer value of variant")?;
+        match value.literal {
+            LiteralValue::NumberInt(v) if negative => Ok(-v),
+            LiteralValue::NumberInt(v) => Ok(v),
+            _ => bail_at!(value.span, "Expected integer value of variant"),
+        }
+    }
+
+    /// Parses a 'const' or 'static' declaration, the keyword is already consumed.
+    pub(super) fn global_decl(&mut self, kind: GlobalKind) -> ParserResult<GlobalDecl> {
+        // docs are attached to the 'const' or 'static' keyword.
+        let docs = self.previous().docs.clone();
+        let metadata = Metadata {
+            span: self.current().span,
+            node_type: None,
+        };
+        let name = self
+            .consume(TokenType::Identifier, &format!("Expected {} name", kind))?
+            .lexeme
+            .clone();
+
+        self.consume(
+            TokenType::Colon,
+            &format!("Expected ':' after {} name", kind),
+        )?;
+        let ty = self.parse_type(&format!("Expected {} type", kind))?;
+
+        self.consume(
+            TokenType::Equal,
+            &format!("Expected '=' after {} type", kind),
+        )?;
+        let value = self.expr()?;
+
+        self.consume(
+            TokenType::Semicolon,
+            &format!("Expected ';' after {} declaration", kind),
+        )?;
+
+        Ok(GlobalDecl {
+            kind,
+            name,
+            ty,
+            value,
+            metadata,
+            docs,
         })
     }
+
+    /// Parses a type, either a primitive type, an array '[T; N]' or name of a struct.
+    pub(super) fn parse_type(&mut self, message: &str) -> ParserResult<DType> {
+        if self.current().is_a_basic_type() {
+            return Ok(DType::Primitive(EveTypes::try_from(self.advance())?));
+        }
+
+        if self.match_token(&[TokenType::Star]) {
+            let pointee = self.parse_type("Expected pointer type after '*'")?;
+            return Ok(DType::Pointer(Box::new(pointee)));
+        }
+
+        if self.match_token(&[TokenType::LeftBracket]) {
+            let elem = self.parse_type("Expected array element type")?;
+            self.consume(
+                TokenType::Semicolon,
+                "Expected ';' after array element type",
+            )?;
+            let len = self.array_len()?;
+            self.consume(TokenType::RightBracket, "Expected ']' after array length")?;
+            return Ok(DType::Array(Box::new(elem), len));
+        }
+
+        let name = self.consume(TokenType::Identifier, message)?.lexeme.clone();
+        if name == VEC_TYPE_NAME && self.match_token(&[TokenType::Less]) {
+            return Ok(DType::Vector(Box::new(self.vec_elem_type()?)));
+        }
+        Ok(DType::Derived(name))
+    }
+
+    /// Parses element type of a vector along with the closing '>', the opening '<' is already consumed.
+    pub(super) fn vec_elem_type(&mut self) -> ParserResult<DType> {
+        let elem = self.parse_type("Expected vector element type")?;
+        self.consume(TokenType::Greater, "Expected '>' after vector element type")?;
+        Ok(elem)
+    }
+
+    /// Parses length of an array, a positive integer literal or the name of a constant
+    /// with such a value, lengths are part of types so the constant has to be declared
+    /// before it in the same file.
+    pub(super) fn array_len(&mut self) -> ParserResult<usize> {
+        if self.match_token(&[TokenType::Identifier]) {
+            let name = self.previous();
+            let decl = self
+                .global_decls
+                .iter()
+                .find(|x| x.kind == GlobalKind::Const && x.name == name.lexeme)
+                .ok_or_else(|| {
+                    err_at!(
+                        name.span,
+                        "'{}' is not a constant declared before this in the same file",
+                        name.lexeme
+                    )
+                    .with_code("E0313")
+                })?;
+
+            return match &decl.value {
+                Expr::Literal(LiteralExpr {
+                    value: LiteralValue::NumberInt(n),
+                    ..
+                }) if *n > 0 => Ok(*n as usize),
+                Expr::Literal(LiteralExpr {
+                    value: LiteralValue::NumberInt(_),
+                    ..
+                }) => bail_at!(name.span, "Array length must be greater than 0"),
+                _ => Err(err_at!(
+                    name.span,
+                    "Array length '{}' must be a constant with an integer literal value",
+                    name.lexeme
+                )
+                .with_code("E0313")
+                .secondary(decl.value.span, "not an integer literal")
+                .into()),
+            };
+        }
+
+        let len = self.consume(TokenType::NumberInt, "Expected array length")?;
+        match len.literal {
+            LiteralValue::NumberInt(n) if n > 0 => Ok(n as usize),
+            _ => bail_at!(len.span, "Array length must be greater than 0"),
+        }
+    }
 }
@@ -1,12 +1,12 @@
 use super::Parser;
 
-use anyhow::bail;
 use log::{error, trace};
 
 use crate::{
     ast::{Token, TokenType},
+    bail_at,
+    diagnostic::Diagnostic,
     die,
-    utils::{ErrorType, MessageType, report_message},
 };
 
 impl Parser<'_> {
@@ -22,7 +22,41 @@ impl Parser<'_> {
             return Ok(self.advance());
         }
 
-        bail!("{}, line {}", message.to_owned(), self.current().line);
+        bail_at!(self.current().span, "{}, found {}", message, self.found());
+    }
+
+    /// Consumes current token if it matches any of the given token types,
+    /// otherwise errors with an "expected one of ..." message.
+    /// * `expected` - types of token to match with.
+    pub(super) fn consume_one_of(&mut self, expected: &[TokenType]) -> anyhow::Result<&Token> {
+        if expected.iter().any(|ttype| self.match_current(ttype)) {
+            return Ok(self.advance());
+        }
+
+        Err(self.expected_one_of(expected))
+    }
+
+    /// Creates an error for when the current token isn't any of the expected ones.
+    /// * `expected` - types of token which would have been accepted.
+    pub(super) fn expected_one_of(&self, expected: &[TokenType]) -> anyhow::Error {
+        let expected = expected.iter().map(|x| x.describe()).collect::<Vec<_>>();
+        let expected = match expected.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("one of {} or {}", rest.join(", "), last),
+            None => "something else".to_string(),
+        };
+
+        Diagnostic::error(format!("expected {}, found {}", expected, self.found()))
+            .primary(self.current().span, format!("expected {}", expected))
+            .into()
+    }
+
+    /// Describes the current token for error messages.
+    pub(super) fn found(&self) -> String {
+        match self.current().ttype {
+            TokenType::Eof => "end of file".to_string(),
+            _ => format!("`{}`", self.current().lexeme),
+        }
     }
 
     /// Checks whether the current token is
@@ -83,16 +117,18 @@ impl Parser<'_> {
         self.current().ttype == TokenType::Eof
     }
 
-    /// Increments parser's error count, reports the error message to user,
+    /// Increments parser's error count, records the error,
     /// then synchronizes to next statement.
+    /// Errors which don't point to a location are reported at the current token,
+    /// errors at an error token are not recorded since the lexer already reported them.
     /// * `message` - error.
     /// * `sync` - whether to synchronize or not
     pub fn report_parser_error(&mut self, err: anyhow::Error, sync: bool) {
         self.errors_count += 1;
-        report_message(
-            format!("at line {}: {:#}.", self.current().line, err),
-            MessageType::Error(ErrorType::ParsingError),
-        );
+        if self.current().ttype != TokenType::Error {
+            self.diagnostics
+                .push(Diagnostic::from_anyhow(&err, self.current().span));
+        }
 
         if sync {
             self.synchronize();
@@ -108,28 +144,48 @@ impl Parser<'_> {
         &self.tokens[self.current + 1]
     }
 
-    /// Synchronizes: consumes all tokens untill next meaningful statement.
+    /// Synchronizes: consumes all tokens untill next meaningful statement,
+    /// stops before the '}' which closes the current block.
     fn synchronize(&mut self) {
         trace!("trying to synchronize");
-        self.advance();
 
         while !self.is_at_end() {
-            if self.previous().ttype == TokenType::Semicolon {
-                trace!("found semicolon, ending synchronize");
-                return;
-            }
-
             match self.current().ttype {
-                TokenType::Struct
+                TokenType::Semicolon => {
+                    trace!("found semicolon, ending synchronize");
+                    self.advance();
+                    return;
+                }
+                // a labeled loop.
+                TokenType::Label if self.peek().ttype == TokenType::Colon => {
+                    trace!("Found loop label ending synchronize");
+                    return;
+                }
+                TokenType::RightBrace
+                | TokenType::Struct
                 | TokenType::Fn
+                | TokenType::Const
+                | TokenType::Static
+                | TokenType::Enum
                 | TokenType::Let
                 | TokenType::Return
                 | TokenType::If
+                | TokenType::Loop
+                | TokenType::While
+                | TokenType::For
+                | TokenType::Break
+                | TokenType::Continue
+                | TokenType::Match
                 | TokenType::Print
                 | TokenType::Extern => {
                     trace!("Found new statement beginner token ending synchronize");
                     return;
                 }
+                TokenType::LeftBrace => {
+                    trace!("skipping nested block");
+                    self.skip_block();
+                    continue;
+                }
                 _ => trace!("didnt match any new statement beginner token."),
             };
 
@@ -137,15 +193,35 @@ impl Parser<'_> {
         }
     }
 
-    /// Synchronizes at top level: consumes all tokens untill next fn or struct decl
+    /// Consumes a '{' along with everything up to its matching '}'.
+    fn skip_block(&mut self) {
+        let mut depth = 0;
+        while !self.is_at_end() {
+            match self.advance().ttype {
+                TokenType::LeftBrace => depth += 1,
+                TokenType::RightBrace => depth -= 1,
+                _ => {}
+            }
+
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    /// Synchronizes at top level: consumes all tokens untill next fn, struct, const or static decl
     pub(super) fn synchronize_toplevel(&mut self) {
         trace!("trying to synchronize at top level");
         self.advance();
 
         while !self.is_at_end() {
             match self.current().ttype {
-                TokenType::Struct | TokenType::Fn => {
-                    trace!("Found new fn or struct decl token, ending top level synchronize");
+                TokenType::Struct
+                | TokenType::Fn
+                | TokenType::Const
+                | TokenType::Static
+                | TokenType::Enum => {
+                    trace!("Found new top level decl token, ending top level synchronize");
                     return;
                 }
                 _ => trace!("didnt match any new fn or struct token."),
@@ -1,8 +1,8 @@
-use anyhow::bail;
-
 use crate::ast::{CallExpr, Expr, FnDecl, Stmt, StructDecl};
+use crate::diagnostic::Diagnostic;
+use crate::utils::closest_match;
 
-use super::{EvePass, EvePassImmutable, PassResult};
+use super::{EvePass, EvePassImmutable, PassResult, PassResultGeneric};
 
 /// This pass checks for existence of all the non extern called function.
 pub struct AllFnExistence {
@@ -21,10 +21,10 @@ impl EvePassImmutable for AllFnExistence {
         let mut errs = vec![];
         for fns in &self.fn_decls {
             for stmt in &fns.body {
-                if let Stmt::Expression(Expr::Call(call)) = stmt {
-                    if let Err(err) = self.check_fn_existence(call) {
-                        errs.push(err);
-                    }
+                if let Stmt::Expression(Expr::Call(call)) = stmt
+                    && let Err(mut err) = self.check_fn_existence(call)
+                {
+                    errs.append(&mut err);
                 }
             }
         }
@@ -38,19 +38,30 @@ impl EvePassImmutable for AllFnExistence {
 }
 
 impl AllFnExistence {
-    fn check_fn_existence(&self, call: &CallExpr) -> Result<(), anyhow::Error> {
+    fn check_fn_existence(&self, call: &CallExpr) -> PassResultGeneric<()> {
         match &call.callee {
             Expr::Variable(var) => {
                 if self.fn_decls.iter().any(|decl| decl.name == var.name) {
-                    Ok(())
-                } else {
-                    bail!(
-                        "Call to undefined function '{}', line {}",
-                        var.name,
-                        var.metadata.line
+                    return Ok(());
+                }
+
+                let mut err =
+                    Diagnostic::error(format!("Call to undefined function '{}'", var.name))
+                        .with_code("E0201")
+                        .primary(var.metadata.span, "not found in any module");
+
+                let names = self.fn_decls.iter().map(|decl| decl.name.as_str());
+                if let Some(similar) = closest_match(&var.name, names) {
+                    err = err.with_suggestion(
+                        var.metadata.span,
+                        "a function with a similar name exists",
+                        similar,
                     );
                 }
+                Err(vec![err])
             }
+            // methods are checked along with the type of their receiver.
+            Expr::FieldAccess(_) => Ok(()),
             _ => unreachable!(),
         }
     }
@@ -1,6 +1,6 @@
 use crate::{
     ast::{FnDecl, Stmt, StructDecl},
-    utils::{MessageType, WarningType, report_message},
+    diagnostic::Diagnostic,
 };
 
 use super::{EvePass, EvePassMutable, PassResult};
@@ -10,18 +10,25 @@ use super::{EvePass, EvePassMutable, PassResult};
 pub struct DeadCodeElimination {
     pub fn_decls: Vec<FnDecl>,
     pub st_decls: Vec<StructDecl>,
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl EvePass for DeadCodeElimination {
     fn new(fn_decls: Vec<FnDecl>, st_decls: Vec<StructDecl>) -> Self {
-        Self { fn_decls, st_decls }
+        Self {
+            fn_decls,
+            st_decls,
+            warnings: vec![],
+        }
     }
 }
 
 impl EvePassMutable for DeadCodeElimination {
     fn run_pass(&mut self) -> PassResult {
         for fns in self.fn_decls.iter_mut().filter(|x| x.name != "main") {
-            Self::remove_stmt_after_return(fns);
+            if let Some(warning) = Self::remove_stmt_after_return(fns) {
+                self.warnings.push(warning);
+            }
         }
 
         Ok((self.fn_decls.to_owned(), self.st_decls.to_owned()))
@@ -29,18 +36,17 @@ impl EvePassMutable for DeadCodeElimination {
 }
 
 impl DeadCodeElimination {
-    fn remove_stmt_after_return(fns: &mut FnDecl) {
+    fn remove_stmt_after_return(fns: &mut FnDecl) -> Option<Diagnostic> {
         let index = fns.body.iter().position(|x| matches!(x, &Stmt::Return(_)));
 
-        if let Some(i) = index.filter(|&i| i != fns.body.len() - 1) {
-            report_message(
-                format!(
-                    "Code after return statement in function '{}' will be ignored, line {}",
-                    &fns.name, fns.metadata.line
-                ),
-                MessageType::Warning(WarningType::None),
-            );
-            fns.body.truncate(i);
-        }
+        let i = index.filter(|&i| i != fns.body.len() - 1)?;
+        let warning = Diagnostic::warning(format!(
+            "Code after return statement in function '{}' will be ignored",
+            &fns.name
+        ))
+        .primary(fns.body[i + 1].span, "unreachable statement")
+        .secondary(fns.body[i].span, "any code following this is unreachable");
+        fns.body.truncate(i + 1);
+        Some(warning)
     }
 }
@@ -0,0 +1,41 @@
+use crate::ast::{DType, EnumDecl, FnDecl, StructDecl, visit_types_mut};
+
+/// Replaces names of enums used as types with enum types,
+/// the parser can't tell them apart from names of structs.
+/// This runs before all other passes, which only see structs as derived types.
+pub fn resolve_enum_types(
+    enum_decls: &[EnumDecl],
+    fn_decls: &mut [FnDecl],
+    st_decls: &mut [StructDecl],
+) {
+    if enum_decls.is_empty() {
+        return;
+    }
+
+    let mut resolve = |ty: &mut DType| resolve_type(enum_decls, ty);
+    for st in st_decls.iter_mut() {
+        for field in &mut st.fields {
+            resolve(&mut field.field_type);
+        }
+    }
+
+    for func in fn_decls.iter_mut() {
+        for param in &mut func.parameters {
+            resolve(&mut param.field_type);
+        }
+        resolve(&mut func.return_type);
+        visit_types_mut(&mut func.body, &mut resolve);
+    }
+}
+
+fn resolve_type(enum_decls: &[EnumDecl], ty: &mut DType) {
+    match ty {
+        DType::Derived(name) if enum_decls.iter().any(|x| &x.name == name) => {
+            *ty = DType::Enum(std::mem::take(name));
+        }
+        DType::Array(elem, _) | DType::Vector(elem) | DType::Pointer(elem) => {
+            resolve_type(enum_decls, elem)
+        }
+        DType::Primitive(_) | DType::Derived(_) | DType::Enum(_) => {}
+    }
+}
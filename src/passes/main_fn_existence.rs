@@ -1,7 +1,7 @@
-use anyhow::anyhow;
 use colored::Colorize;
 
 use crate::ast::{FnDecl, StructDecl};
+use crate::diagnostic::Diagnostic;
 
 use super::{EvePass, EvePassImmutable, PassResult};
 
@@ -23,9 +23,13 @@ impl EvePassImmutable for MainFnExistence {
             return Ok((self.fn_decls.to_owned(), self.st_decls.to_owned()));
         }
 
-        Err(vec![anyhow!(
-            "No main function found.\nHelp: consider adding:\n{}",
-            "\nfn main() -> void {\n\tprint \"Hello, World\";\n}\n".green()
-        )])
+        Err(vec![
+            Diagnostic::error("No main function found")
+                .with_code("E0200")
+                .with_help(format!(
+                    "consider adding:\n{}",
+                    "\nfn main() -> void {\n\tprint \"Hello, World\";\n}\n".green()
+                )),
+        ])
     }
 }
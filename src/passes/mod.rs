@@ -2,17 +2,23 @@ use all_fn_existence::AllFnExistence;
 use dead_code_elimination::DeadCodeElimination;
 use main_fn_existence::MainFnExistence;
 use struct_field_missing_and_unknown::StructFieldMissingAndUnknown;
+use struct_field_types::StructFieldTypes;
 use struct_init_unique_fields::StructInitUniqueField;
 
-use crate::ast::{FnDecl, StructDecl};
+use crate::ast::{Expr, FnDecl, Stmt, StructDecl, StructInitExpr, visit_exprs};
+use crate::diagnostic::Diagnostic;
+
+pub use enum_types::resolve_enum_types;
 
 mod all_fn_existence;
 mod dead_code_elimination;
+mod enum_types;
 mod main_fn_existence;
 mod struct_field_missing_and_unknown;
+mod struct_field_types;
 mod struct_init_unique_fields;
 
-type PassResultGeneric<T> = anyhow::Result<T, Vec<anyhow::Error>>;
+type PassResultGeneric<T> = anyhow::Result<T, Vec<Diagnostic>>;
 type PassResult = PassResultGeneric<(Vec<FnDecl>, Vec<StructDecl>)>;
 
 pub(super) trait EvePass {
@@ -27,7 +33,12 @@ pub(super) trait EvePassMutable: EvePass {
     fn run_pass(&mut self) -> PassResult;
 }
 
-pub fn run_passes(fn_: Vec<FnDecl>, st_: Vec<StructDecl>) -> PassResult {
+/// Runs all passes in order, returns the modified declarations along with
+/// warnings reported by the passes.
+pub fn run_passes(
+    fn_: Vec<FnDecl>,
+    st_: Vec<StructDecl>,
+) -> PassResultGeneric<(Vec<FnDecl>, Vec<StructDecl>, Vec<Diagnostic>)> {
     let p = MainFnExistence::new(fn_, st_);
     let (fn_, st_) = p.run_pass()?;
 
@@ -40,8 +51,23 @@ pub fn run_passes(fn_: Vec<FnDecl>, st_: Vec<StructDecl>) -> PassResult {
     let p = StructFieldMissingAndUnknown::new(fn_, st_);
     let (fn_, st_) = p.run_pass()?;
 
+    let p = StructFieldTypes::new(fn_, st_);
+    let (fn_, st_) = p.run_pass()?;
+
     let mut p = DeadCodeElimination::new(fn_, st_);
     let (fn_, st_) = p.run_pass()?;
+    let warnings = p.warnings;
+
+    Ok((fn_, st_, warnings))
+}
 
-    Ok((fn_, st_))
+/// Collects all struct initialisers in the given statements, including nested ones.
+fn struct_inits(stmts: &[Stmt]) -> Vec<&StructInitExpr> {
+    let mut inits = vec![];
+    visit_exprs(stmts, &mut |expr| {
+        if let Expr::StructInit(st_init) = expr {
+            inits.push(st_init.as_ref());
+        }
+    });
+    inits
 }
@@ -1,8 +1,8 @@
 use std::collections::HashSet;
 
-use anyhow::anyhow;
-
-use crate::ast::{FnDecl, Stmt, StructDecl, StructInitStmt};
+use crate::ast::{FnDecl, StructDecl, StructInitExpr};
+use crate::diagnostic::Diagnostic;
+use crate::utils::closest_match;
 
 use super::{EvePass, EvePassImmutable, PassResult, PassResultGeneric};
 
@@ -21,10 +21,8 @@ impl EvePass for StructFieldMissingAndUnknown {
 impl EvePassImmutable for StructFieldMissingAndUnknown {
     fn run_pass(&self) -> PassResult {
         for fns in &self.fn_decls {
-            for stmt in &fns.body {
-                if let Stmt::StructInit(st) = stmt {
-                    self.check_struct(st)?;
-                }
+            for st in super::struct_inits(&fns.body) {
+                self.check_struct(st)?;
             }
         }
         Ok((self.fn_decls.to_owned(), self.st_decls.to_owned()))
@@ -32,7 +30,7 @@ impl EvePassImmutable for StructFieldMissingAndUnknown {
 }
 
 impl StructFieldMissingAndUnknown {
-    fn check_struct(&self, st_init: &StructInitStmt) -> PassResultGeneric<()> {
+    fn check_struct(&self, st_init: &StructInitExpr) -> PassResultGeneric<()> {
         let mut err = vec![];
         if let Some(decl) = self.st_decls.iter().find(|x| x.name == st_init.struct_name) {
             // Make sets from fields list
@@ -49,18 +47,48 @@ impl StructFieldMissingAndUnknown {
 
             // decl_fields - init_fields
             for missing_field in decl_fields.difference(&init_fields) {
-                err.push(anyhow!(format!(
-                    "Field '{}' missing in struct '{}', line {}",
-                    missing_field, &decl.name, decl.metadata.line
-                )));
+                err.push(
+                    Diagnostic::error(format!(
+                        "Field '{}' missing in struct '{}'",
+                        missing_field, &decl.name
+                    ))
+                    .with_code("E0203")
+                    .primary(
+                        st_init.metadata.span,
+                        format!("missing '{}'", missing_field),
+                    )
+                    .secondary(decl.metadata.span, "struct declared here"),
+                );
             }
 
             // init_fields - decl_fields
             for unknown_field in init_fields.difference(&decl_fields) {
-                err.push(anyhow!(format!(
-                    "Unknown field '{}' in struct '{}', line {}",
-                    unknown_field, &decl.name, decl.metadata.line
-                )));
+                let span = st_init
+                    .arguments
+                    .iter()
+                    .find(|x| &x.field_name == unknown_field)
+                    .map(|x| x.metadata.span)
+                    .unwrap_or(st_init.metadata.span);
+                let names = decl.fields.iter().map(|x| x.field_name.as_str());
+                let mut diag = Diagnostic::error(format!(
+                    "Unknown field '{}' in struct '{}'",
+                    unknown_field, &decl.name
+                ))
+                .with_code("E0204")
+                .primary(span, "unknown field")
+                .with_help(format!(
+                    "available fields are: {}",
+                    names.clone().collect::<Vec<_>>().join(", ")
+                ));
+
+                // only suggest fields which weren't initialised already.
+                if let Some(similar) =
+                    closest_match(unknown_field, names.filter(|x| !init_fields.contains(*x)))
+                {
+                    diag =
+                        diag.with_suggestion(span, "a field with a similar name exists", similar);
+                }
+                err.push(diag);
             }
         }
 
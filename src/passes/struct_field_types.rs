@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use crate::ast::{FnDecl, FnStDeclField, StructDecl};
+use crate::diagnostic::Diagnostic;
+
+use super::{EvePass, EvePassImmutable, PassResult};
+
+/// This pass checks that struct field types are declared, and that no struct contains itself.
+pub struct StructFieldTypes {
+    fn_decls: Vec<FnDecl>,
+    st_decls: Vec<StructDecl>,
+}
+
+impl EvePass for StructFieldTypes {
+    fn new(fn_decls: Vec<FnDecl>, st_decls: Vec<StructDecl>) -> Self {
+        Self { fn_decls, st_decls }
+    }
+}
+
+impl EvePassImmutable for StructFieldTypes {
+    fn run_pass(&self) -> PassResult {
+        let mut err = vec![];
+        for st in &self.st_decls {
+            for field in &st.fields {
+                if let Some(name) = field.field_type.struct_name()
+                    && self.find_decl(name).is_none()
+                {
+                    err.push(
+                        Diagnostic::error(format!("Struct '{}' not defined", name))
+                            .with_code("E0303")
+                            .primary(field.metadata.span, "unknown field type"),
+                    );
+                }
+            }
+        }
+
+        // structs which are already reported as part of a cycle.
+        let mut reported: HashSet<&str> = HashSet::new();
+        for st in &self.st_decls {
+            if reported.contains(st.name.as_str()) {
+                continue;
+            }
+
+            let mut path = vec![];
+            if self.find_cycle(&st.name, st, &mut path, &mut HashSet::new()) {
+                let mut diag =
+                    Diagnostic::error(format!("Recursive struct '{}' has infinite size", st.name))
+                        .with_code("E0205")
+                        .primary(st.metadata.span, "recursive without indirection");
+
+                for (parent, field) in path {
+                    reported.insert(parent.name.as_str());
+                    diag = diag.secondary(
+                        field.metadata.span,
+                        format!("'{}' contains '{}'", parent.name, field.field_type),
+                    );
+                }
+                err.push(diag);
+            }
+        }
+
+        if !err.is_empty() {
+            return Err(err);
+        }
+
+        Ok((self.fn_decls.to_owned(), self.st_decls.to_owned()))
+    }
+}
+
+impl StructFieldTypes {
+    fn find_decl(&self, name: &str) -> Option<&StructDecl> {
+        self.st_decls.iter().find(|x| x.name == name)
+    }
+
+    /// Looks for a chain of fields leading from 'current' back to the struct 'start'.
+    /// On success 'path' holds the fields which make up the cycle.
+    fn find_cycle<'a>(
+        &'a self,
+        start: &str,
+        current: &'a StructDecl,
+        path: &mut Vec<(&'a StructDecl, &'a FnStDeclField)>,
+        visited: &mut HashSet<&'a str>,
+    ) -> bool {
+        for field in &current.fields {
+            let Some(name) = field.field_type.inline_struct() else {
+                continue;
+            };
+
+            path.push((current, field));
+            if name == start {
+                return true;
+            }
+
+            if visited.insert(name)
+                && let Some(decl) = self.find_decl(name)
+                && self.find_cycle(start, decl, path, visited)
+            {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
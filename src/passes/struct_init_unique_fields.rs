@@ -1,8 +1,7 @@
-use anyhow::bail;
+use crate::ast::{FnDecl, StInitField, StructDecl, StructInitExpr};
+use crate::diagnostic::Diagnostic;
 
-use crate::ast::{FnDecl, Stmt, StructDecl, StructInitStmt};
-
-use super::{EvePass, EvePassImmutable};
+use super::{EvePass, EvePassImmutable, PassResult, PassResultGeneric};
 
 /// This passes checks that all fields specified in struct initialization are unique.
 pub struct StructInitUniqueField {
@@ -17,14 +16,12 @@ impl EvePass for StructInitUniqueField {
 }
 
 impl EvePassImmutable for StructInitUniqueField {
-    fn run_pass(&self) -> anyhow::Result<(Vec<FnDecl>, Vec<StructDecl>), Vec<anyhow::Error>> {
+    fn run_pass(&self) -> PassResult {
         let mut err = vec![];
         for fns in &self.fn_decls {
-            for stmt in &fns.body {
-                if let Stmt::StructInit(st) = stmt {
-                    if let Err(e) = self.check_struct(st) {
-                        err.push(e);
-                    }
+            for st in super::struct_inits(&fns.body) {
+                if let Err(mut e) = self.check_struct(st) {
+                    err.append(&mut e);
                 }
             }
         }
@@ -38,20 +35,22 @@ impl EvePassImmutable for StructInitUniqueField {
 }
 
 impl StructInitUniqueField {
-    fn check_struct(&self, st_init: &StructInitStmt) -> anyhow::Result<()> {
-        let mut m = vec![];
+    fn check_struct(&self, st_init: &StructInitExpr) -> PassResultGeneric<()> {
+        let mut m: Vec<&StInitField> = vec![];
         for i in &st_init.arguments {
-            if m.contains(&i.field_name) {
-                bail!(
-                    "field '{}' is already defined for '{}' of type '{}', line {}",
-                    &i.field_name,
-                    &st_init.name,
-                    &st_init.struct_name,
-                    st_init.metadata.line
-                );
+            if let Some(first) = m.iter().find(|x| x.field_name == i.field_name) {
+                return Err(vec![
+                    Diagnostic::error(format!(
+                        "field '{}' is already defined in initialiser of '{}'",
+                        &i.field_name, &st_init.struct_name
+                    ))
+                    .with_code("E0202")
+                    .primary(i.metadata.span, "field initialised again")
+                    .secondary(first.metadata.span, "first initialised here"),
+                ]);
             }
 
-            m.push(i.field_name.clone())
+            m.push(i)
         }
         Ok(())
     }
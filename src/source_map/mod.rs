@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+
+/// Index of a file registered in a [`SourceMap`].
+pub type FileId = usize;
+
+/// Location of a piece of source code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// file this span belongs to.
+    pub file_id: FileId,
+
+    /// byte offset of the first character.
+    pub byte_start: usize,
+
+    /// byte offset just after the last character.
+    pub byte_end: usize,
+
+    /// line number of the first character, starts from 1.
+    pub line: usize,
+
+    /// column number of the first character, starts from 1.
+    pub col: usize,
+}
+
+impl Span {
+    /// Returns a span which starts at `self` and ends at `other`.
+    /// * `other` - span marking the end.
+    pub fn to(&self, other: &Span) -> Span {
+        Span {
+            byte_end: other.byte_end.max(self.byte_end),
+            ..*self
+        }
+    }
+}
+
+impl std::fmt::Display for Span {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.col)
+    }
+}
+
+/// A single source file.
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    /// path the file was read from.
+    pub path: PathBuf,
+
+    /// content of the file.
+    pub src: String,
+}
+
+/// Keeps track of all source files in a compilation,
+/// resolves file ids back to the files.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new file, returns its id.
+    /// * `path` - path of the file.
+    /// * `src` - content of the file.
+    pub fn add_file<P: Into<PathBuf>>(&mut self, path: P, src: String) -> FileId {
+        self.files.push(SourceFile {
+            path: path.into(),
+            src,
+        });
+        self.files.len() - 1
+    }
+
+    /// Returns file with the given id.
+    pub fn get(&self, file_id: FileId) -> Option<&SourceFile> {
+        self.files.get(file_id)
+    }
+
+    /// Returns path of the file with given id.
+    pub fn path(&self, file_id: FileId) -> Option<&Path> {
+        self.get(file_id).map(|f| f.path.as_path())
+    }
+
+    /// Formats span as 'path:line:col'.
+    /// * `span` - span to format.
+    pub fn location(&self, span: &Span) -> String {
+        match self.path(span.file_id) {
+            Some(path) => format!("{}:{}", path.display(), span),
+            None => span.to_string(),
+        }
+    }
+}
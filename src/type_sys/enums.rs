@@ -0,0 +1,80 @@
+use log::trace;
+
+use crate::ast::{DType, EnumDecl, EnumVariant, EnumVariantExpr};
+use crate::diagnostic::Diagnostic;
+use crate::err_at;
+use crate::source_map::Span;
+
+use super::TypeSystem;
+
+impl<'a> TypeSystem<'a> {
+    /// Checks that names of enums and of their variants are unique.
+    pub(super) fn check_enums(&mut self) {
+        for (i, decl) in self.enum_decls.iter().enumerate() {
+            trace!("checking enum : '{}'", &decl.name);
+            let first_span = self.enum_decls[..i]
+                .iter()
+                .find(|x| x.name == decl.name)
+                .map(|x| x.metadata.span)
+                .or_else(|| {
+                    self.st_decls
+                        .iter()
+                        .find(|x| x.name == decl.name)
+                        .map(|x| x.metadata.span)
+                });
+            if let Some(first_span) = first_span {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("'{}' is declared more than once", decl.name))
+                        .with_code("E0320")
+                        .primary(decl.metadata.span, "declared again")
+                        .secondary(first_span, "first declared here"),
+                );
+            }
+
+            for (j, variant) in decl.variants.iter().enumerate() {
+                if let Some(first) = decl.variants[..j].iter().find(|x| x.name == variant.name) {
+                    self.diagnostics.push(
+                        Diagnostic::error(format!(
+                            "Variant '{}' is declared more than once in enum '{}'",
+                            variant.name, decl.name
+                        ))
+                        .with_code("E0320")
+                        .primary(variant.metadata.span, "declared again")
+                        .secondary(first.metadata.span, "first declared here"),
+                    );
+                }
+            }
+        }
+    }
+
+    pub(super) fn check_enum_variant(&self, var: &mut EnumVariantExpr) -> anyhow::Result<DType> {
+        self.find_variant(&var.enum_name, &var.variant, var.metadata.span)?;
+        let ty = DType::Enum(var.enum_name.clone());
+        var.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+
+    /// Returns the variant named by 'enum_name::variant'.
+    pub(super) fn find_variant(
+        &self,
+        enum_name: &str,
+        variant: &str,
+        span: Span,
+    ) -> anyhow::Result<&'a EnumVariant> {
+        let enum_decls: &'a Vec<EnumDecl> = self.enum_decls;
+        let decl = enum_decls
+            .iter()
+            .find(|x| x.name == enum_name)
+            .ok_or_else(|| err_at!(span, "Enum '{}' not defined", enum_name).with_code("E0321"))?;
+
+        decl.variants
+            .iter()
+            .find(|x| x.name == variant)
+            .ok_or_else(|| {
+                err_at!(span, "Enum '{}' has no variant '{}'", enum_name, variant)
+                    .with_code("E0321")
+                    .secondary(decl.metadata.span, "enum declared here")
+                    .into()
+            })
+    }
+}
@@ -1,12 +1,17 @@
-use anyhow::{anyhow, bail};
+use std::collections::HashSet;
+
 use log::trace;
 
 use crate::ast::{
-    AssignmentExpr, BinExpr, CallExpr, DType, EveTypes, Expr, FieldAccessExpr, LiteralExpr,
-    LiteralValue, NativeCallExpr, UnaryExpr, VariableExpr,
+    ArrayInitExpr, AssignKind, AssignmentExpr, BinExpr, BinOp, CallExpr, DType, EveTypes, Expr,
+    FieldAccessExpr, IndexExpr, LValue, LiteralExpr, LiteralValue, Metadata, NativeCallExpr,
+    StructInitExpr, UnaryExpr, VariableExpr,
 };
+use crate::diagnostic::Diagnostic;
+use crate::source_map::Span;
+use crate::{bail_at, err_at};
 
-use super::TypeSystem;
+use super::{TypeSystem, is_coercible, is_pointer_cast};
 
 impl TypeSystem<'_> {
     pub(super) fn check_expr(&self, expr: &mut Expr) -> anyhow::Result<DType> {
@@ -18,161 +23,339 @@ impl TypeSystem<'_> {
             Expr::NativeCall(nacall) => self.check_native_call(nacall),
             Expr::Unary(un) => self.check_unary(un),
             Expr::Grouping(group) => self.check_expr(&mut group.value),
-            Expr::Variable(var) => self.check_var(var),
+            Expr::Variable(var) => {
+                // constants are inlined.
+                if let Some(value) = self.const_value(&var.name) {
+                    let span = var.metadata.span;
+                    *expr = Expr::Literal(LiteralExpr {
+                        value,
+                        metadata: Metadata {
+                            span,
+                            node_type: None,
+                        },
+                    });
+                    return self.check_expr(expr);
+                }
+                self.check_var(var)
+            }
             Expr::Literal(lit) => Ok(self.check_literal(lit)),
+            Expr::StructInit(st_init) => self.check_struct_init(st_init),
+            Expr::Index(idx) => self.check_index(idx),
+            Expr::ArrayInit(arr) => self.check_array_init(arr),
+            Expr::VecNew(vec) => self.check_vec_new(vec),
+            Expr::AddrOf(addr) => self.check_addr_of(addr),
+            Expr::Deref(deref) => self.check_deref(deref),
+            Expr::EnumVariant(var) => self.check_enum_variant(var),
+            Expr::Error(err) => bail_at!(
+                err.metadata.span,
+                "Cannot type check an erroneous expression"
+            ),
         }
     }
 
     fn check_assignment(&self, ass: &mut AssignmentExpr) -> anyhow::Result<DType> {
-        let defined_val = self
-            .get_env(&ass.name)
-            .ok_or_else(|| anyhow!("Undefined '{}', line {}", &ass.name, ass.metadata.line))?;
-        let ty = self.check_expr(&mut ass.value)?;
-
-        if ty != *defined_val {
-            bail!(
-                "Cannot assign value of type '{}' to variable '{}' of type '{}'",
+        if let LValue::Variable(var) = &ass.target
+            && let Some(decl) = self.get_const(&var.name)
+        {
+            return Err(err_at!(
+                ass.metadata.span,
+                "Cannot assign to constant '{}'",
+                &var.name
+            )
+            .with_code("E0312")
+            .secondary(decl.metadata.span, "declared as a constant here")
+            .with_help("declare it as 'static' to update it at runtime")
+            .into());
+        }
+        let (place, defined_ty) = self.check_place(&mut ass.target, ass.metadata.span)?;
+        let mut ty = self.check_expr(&mut ass.value)?;
+        if let AssignKind::Compound(op) | AssignKind::Postfix(op) = ass.kind {
+            let spans = (ass.metadata.span, ass.target.span, ass.value.span);
+            ty = Self::binary_type(op, &defined_ty, &ty, spans)?;
+        }
+
+        if !is_coercible(&ty, &defined_ty) {
+            return Err(err_at!(
+                ass.metadata.span,
+                "Cannot assign value of type '{}' to {} of type '{}'",
                 ty,
-                ass.name,
-                defined_val
-            );
+                place,
+                defined_ty
+            )
+            .with_code("E0300")
+            .secondary(ass.value.span, format!("this is of type '{}'", ty))
+            .into());
         }
 
-        ass.metadata.node_type = Some(ty.clone());
-        Ok(ty)
+        // assignments evaluate to the value stored in the place.
+        ass.metadata.node_type = Some(defined_ty.clone());
+        Ok(defined_ty)
+    }
+
+    /// Returns description and type of a place which is assigned to or whose address is taken.
+    pub(super) fn check_place(
+        &self,
+        target: &mut LValue,
+        span: Span,
+    ) -> anyhow::Result<(String, DType)> {
+        Ok(match target {
+            LValue::Variable(var) => {
+                let defined_ty = self
+                    .get_env(&var.name)
+                    .ok_or_else(|| err_at!(span, "Undefined '{}'", &var.name).with_code("E0301"))?;
+                var.metadata.node_type = Some(defined_ty.clone());
+                (format!("variable '{}'", var.name), defined_ty.clone())
+            }
+            LValue::FieldAccess(fiac) => {
+                let defined_ty = self.check_field_access(fiac)?;
+                (
+                    format!("field '{}.{}'", fiac.parent, fiac.field),
+                    defined_ty,
+                )
+            }
+            LValue::Index(idx) => {
+                let defined_ty = self.check_index(idx)?;
+                (format!("element of '{}'", idx.object), defined_ty)
+            }
+            LValue::Deref(deref) => {
+                let defined_ty = self.check_deref(deref)?;
+                (
+                    format!("value pointed to by '{}'", deref.pointer),
+                    defined_ty,
+                )
+            }
+        })
     }
 
     fn check_binary(&self, bin: &mut BinExpr) -> anyhow::Result<DType> {
         let left = self.check_expr(&mut bin.left)?;
         let right = self.check_expr(&mut bin.right)?;
 
-        let ty = match (left, right) {
+        let spans = (bin.metadata.span, bin.left.span, bin.right.span);
+        let ty = Self::binary_type(bin.op, &left, &right, spans)?;
+        // ints mixed with floats are converted to floats.
+        let float = DType::Primitive(EveTypes::Float);
+        if ty == float && left != float {
+            bin.left.node_type = Some(float.clone());
+        }
+        if ty == float && right != float {
+            bin.right.node_type = Some(float);
+        }
+
+        bin.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+
+    /// Returns type of 'left op right', spans are of the whole operation and of both sides.
+    fn binary_type(
+        op: BinOp,
+        left: &DType,
+        right: &DType,
+        (span, left_span, right_span): (Span, Span, Span),
+    ) -> anyhow::Result<DType> {
+        let labels = (format!("{}", left), format!("{}", right));
+        let mismatch = |message: String| {
+            Diagnostic::error(message)
+                .with_code("E0300")
+                .primary(span, "")
+                .secondary(left_span, labels.0.clone())
+                .secondary(right_span, labels.1.clone())
+        };
+
+        Ok(match (left.clone(), right.clone()) {
             (DType::Primitive(EveTypes::Int), DType::Primitive(EveTypes::Int)) => {
                 DType::Primitive(EveTypes::Int)
             }
 
             (DType::Primitive(EveTypes::Int), DType::Primitive(EveTypes::Float)) => {
-                bin.left.node_type = Some(DType::Primitive(EveTypes::Float));
                 DType::Primitive(EveTypes::Float)
             }
-            (DType::Primitive(EveTypes::Int), DType::Primitive(EveTypes::String)) => bail!(
-                "{} operation cannot be applied between Int and String, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
-            (DType::Primitive(EveTypes::Int), DType::Primitive(EveTypes::Void)) => bail!(
-                "{} operation cannot be applied between Int and Void, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
+            (DType::Primitive(EveTypes::Int), DType::Primitive(EveTypes::String)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between Int and String",
+                    &op
+                ))
+                .into());
+            }
+            (DType::Primitive(EveTypes::Int), DType::Primitive(EveTypes::Void)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between Int and Void",
+                    &op
+                ))
+                .into());
+            }
             (DType::Primitive(EveTypes::Float), DType::Primitive(EveTypes::Int)) => {
-                bin.right.node_type = Some(DType::Primitive(EveTypes::Float));
                 DType::Primitive(EveTypes::Float)
             }
             (DType::Primitive(EveTypes::Float), DType::Primitive(EveTypes::Float)) => {
                 DType::Primitive(EveTypes::Float)
             }
-            (DType::Primitive(EveTypes::Float), DType::Primitive(EveTypes::String)) => bail!(
-                "{} operation cannot be applied between Float and String, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
-            (DType::Primitive(EveTypes::Float), DType::Primitive(EveTypes::Void)) => bail!(
-                "{} operation cannot be applied between Float and Void, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
-            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::Int)) => bail!(
-                "{} operation cannot be applied between String and Int, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
-            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::Float)) => bail!(
-                "{} operation cannot be applied between String and Float, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
-            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::String)) => bail!(
-                "{} operation cannot be applied between String and String, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
-            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::Void)) => bail!(
-                "{} operation cannot be applied between String and Void, line {}",
-                &bin.op,
-                bin.metadata.line
-            ),
+            (DType::Primitive(EveTypes::Float), DType::Primitive(EveTypes::String)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between Float and String",
+                    &op
+                ))
+                .into());
+            }
+            (DType::Primitive(EveTypes::Float), DType::Primitive(EveTypes::Void)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between Float and Void",
+                    &op
+                ))
+                .into());
+            }
+            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::Int)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between String and Int",
+                    &op
+                ))
+                .into());
+            }
+            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::Float)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between String and Float",
+                    &op
+                ))
+                .into());
+            }
+            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::String)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between String and String",
+                    &op
+                ))
+                .into());
+            }
+            (DType::Primitive(EveTypes::String), DType::Primitive(EveTypes::Void)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between String and Void",
+                    &op
+                ))
+                .into());
+            }
 
             (DType::Primitive(EveTypes::Void), _) => unreachable!(),
-            (DType::Derived(derived_name), DType::Primitive(primitive_ty)) => bail!(
-                "{} operation cannot be applied between {} and {}, line {}",
-                &bin.op,
-                derived_name,
-                primitive_ty,
-                bin.metadata.line
-            ),
+            (DType::Derived(derived_name), DType::Primitive(primitive_ty)) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between {} and {}",
+                    &op, derived_name, primitive_ty
+                ))
+                .into());
+            }
             (DType::Derived(_), DType::Derived(_)) => {
-                bail!("Struct method overloading not available",)
+                return Err(mismatch("Struct method overloading not available".to_string()).into());
             }
             (DType::Primitive(EveTypes::Int), DType::Derived(_))
             | (DType::Primitive(EveTypes::Float), DType::Derived(_))
             | (DType::Primitive(EveTypes::String), DType::Derived(_)) => {
-                bail!(
-                    "Operations between derived and primitive type not available, line {}",
-                    bin.metadata.line
+                return Err(mismatch(
+                    "Operations between derived and primitive type not available".to_string(),
                 )
+                .into());
             }
-        };
-        bin.metadata.node_type = Some(ty.clone());
-        Ok(ty)
+            // pointers can only be compared for equality.
+            (left @ DType::Pointer(_), right @ DType::Pointer(_))
+                if matches!(op, BinOp::EqualEqual | BinOp::BangEqual)
+                    && is_pointer_cast(&left, &right) =>
+            {
+                DType::Primitive(EveTypes::Int)
+            }
+            // variants of an enum can be compared, but not used in arithmetic.
+            (DType::Enum(left), DType::Enum(right))
+                if left == right
+                    && matches!(
+                        op,
+                        BinOp::EqualEqual
+                            | BinOp::BangEqual
+                            | BinOp::Less
+                            | BinOp::LessEqual
+                            | BinOp::Greater
+                            | BinOp::GreaterEqual
+                    ) =>
+            {
+                DType::Primitive(EveTypes::Int)
+            }
+            (left, right) => {
+                return Err(mismatch(format!(
+                    "{} operation cannot be applied between {} and {}",
+                    &op, left, right
+                ))
+                .into());
+            }
+        })
     }
 
     fn check_call(&self, call: &mut CallExpr) -> anyhow::Result<DType> {
         trace!("checking function call");
         let fn_name = match &call.callee {
             Expr::Variable(var) => &var.name,
-            _ => bail!("callee is not a identifier, line {}", call.metadata.line),
+            Expr::FieldAccess(_) => return self.check_method_call(call),
+            _ => bail_at!(call.metadata.span, "callee is not a identifier"),
         };
         trace!("checking function call with fn_name = '{}'", &fn_name);
 
-        if let Some(expr) = &mut call.arg {
-            self.check_expr(expr)?;
-        }
+        let arg_types = call
+            .args
+            .iter_mut()
+            .map(|arg| self.check_expr(arg))
+            .collect::<anyhow::Result<Vec<_>>>()?;
 
         let fn_decl = match self.fn_decls.iter().find(|x| &x.name == fn_name) {
             Some(f) => f,
-            None => bail!(
-                "Function '{}' not defined, line {}",
-                fn_name,
-                call.metadata.line
-            ),
+            None => bail_at!(call.metadata.span, "Function '{}' not defined", fn_name),
         };
 
+        if arg_types.len() != fn_decl.parameters.len() {
+            return Err(err_at!(
+                call.metadata.span,
+                "Function '{}' takes {} argument(s) but {} were supplied",
+                fn_name,
+                fn_decl.parameters.len(),
+                arg_types.len()
+            )
+            .with_code("E0305")
+            .secondary(fn_decl.metadata.span, "function defined here")
+            .into());
+        }
+
+        for ((arg, ty), param) in call.args.iter().zip(&arg_types).zip(&fn_decl.parameters) {
+            if !is_coercible(ty, &param.field_type) {
+                return Err(Diagnostic::error(format!(
+                    "Mismatched type for argument '{}' of function '{}'",
+                    param.field_name, fn_name
+                ))
+                .with_code("E0300")
+                .primary(
+                    arg.span,
+                    format!("expected '{}', found '{}'", param.field_type, ty),
+                )
+                .secondary(param.metadata.span, "parameter declared here")
+                .into());
+            }
+        }
+
         call.metadata.node_type = Some(fn_decl.return_type.clone());
         Ok(fn_decl.return_type.clone())
     }
 
     fn check_field_access(&self, field_access: &mut FieldAccessExpr) -> anyhow::Result<DType> {
-        let var_name = match &field_access.parent {
-            Expr::Variable(var) => &var.name,
-            _ => unreachable!(
-                "field access's parent is not a variable, line {}",
-                field_access.metadata.line
-            ),
-        };
+        let parent_type = self.check_expr(&mut field_access.parent)?;
+        let parent = &field_access.parent;
 
-        let var_type = self.get_env(var_name).ok_or(anyhow!(
-            "Variable '{}' not defined, line {}",
-            &var_name,
-            field_access.metadata.line
-        ))?;
-
-        let st_name = match var_type {
-            DType::Primitive(_) => bail!(
-                "'{}' is not a struct instance, line {}",
-                &var_name,
-                field_access.metadata.line
-            ),
+        let st_name = match &parent_type {
+            DType::Primitive(_)
+            | DType::Array(..)
+            | DType::Vector(_)
+            | DType::Pointer(_)
+            | DType::Enum(_) => {
+                return Err(err_at!(
+                    field_access.metadata.span,
+                    "'{}' is not a struct instance",
+                    parent
+                )
+                .with_code("E0302")
+                .with_note(format!("'{}' is of type '{}'", parent, parent_type))
+                .into());
+            }
             DType::Derived(st_name) => st_name,
         };
 
@@ -180,30 +363,185 @@ impl TypeSystem<'_> {
             .st_decls
             .iter()
             .find(|x| &x.name == st_name)
-            .ok_or(anyhow!(
-                "Struct '{}' not defined, line {}",
-                var_name,
-                field_access.metadata.line
-            ))?;
+            .ok_or_else(|| {
+                err_at!(
+                    field_access.metadata.span,
+                    "Struct '{}' not defined",
+                    st_name
+                )
+                .with_code("E0303")
+            })?;
 
         let field = st_decl
             .fields
             .iter()
             .find(|x| x.field_name == field_access.field)
-            .ok_or(anyhow!(
-                "Struct '{}' as no field '{}', line {}",
-                &var_name,
-                &field_access.field,
-                field_access.metadata.line
-            ))?;
-
-        let ty = DType::Primitive(EveTypes::try_from(&field.field_type)?);
+            .ok_or_else(|| {
+                err_at!(
+                    field_access.metadata.span,
+                    "Struct '{}' as no field '{}'",
+                    parent,
+                    &field_access.field
+                )
+                .with_code("E0304")
+                .secondary(st_decl.metadata.span, "struct declared here")
+            })?;
+
+        let ty = field.field_type.clone();
         field_access.metadata.node_type = Some(ty.clone());
         Ok(ty)
     }
 
+    fn check_index(&self, idx: &mut IndexExpr) -> anyhow::Result<DType> {
+        let object_ty = self.check_expr(&mut idx.object)?;
+        let index_ty = self.check_expr(&mut idx.index)?;
+
+        let (elem, len) = match object_ty {
+            DType::Array(elem, len) => (elem, Some(len)),
+            // vectors are checked at runtime.
+            DType::Vector(elem) => (elem, None),
+            _ => {
+                return Err(err_at!(
+                    idx.metadata.span,
+                    "'{}' is not an array or a vector",
+                    idx.object
+                )
+                .with_code("E0315")
+                .with_note(format!("'{}' is of type '{}'", idx.object, object_ty))
+                .into());
+            }
+        };
+
+        if index_ty != DType::Primitive(EveTypes::Int) {
+            return Err(Diagnostic::error(format!(
+                "Array index must be of type 'Int', found '{}'",
+                index_ty
+            ))
+            .with_code("E0300")
+            .primary(idx.index.span, format!("this is of type '{}'", index_ty))
+            .into());
+        }
+
+        // constants are already inlined, so this catches them as well.
+        if let Expr::Literal(LiteralExpr {
+            value: LiteralValue::NumberInt(i),
+            ..
+        }) = idx.index
+            && let Some(len) = len
+            && (i < 0 || i as usize >= len)
+        {
+            return Err(Diagnostic::error(format!(
+                "Index {} is out of bounds for array of length {}",
+                i, len
+            ))
+            .with_code("E0316")
+            .primary(idx.index.span, "index out of bounds")
+            .into());
+        }
+
+        idx.metadata.node_type = Some(*elem.clone());
+        Ok(*elem)
+    }
+
+    fn check_array_init(&self, arr: &mut ArrayInitExpr) -> anyhow::Result<DType> {
+        let mut elem_ty: Option<DType> = None;
+        for element in &mut arr.elements {
+            let ty = self.check_expr(element)?;
+            match &elem_ty {
+                Some(expected) if expected != &ty => {
+                    return Err(Diagnostic::error(format!(
+                        "Mismatched types in array literal, expected '{}', found '{}'",
+                        expected, ty
+                    ))
+                    .with_code("E0300")
+                    .primary(element.span, format!("this is of type '{}'", ty))
+                    .secondary(
+                        arr.elements[0].span,
+                        format!("first element is of type '{}'", expected),
+                    )
+                    .into());
+                }
+                Some(_) => {}
+                None => elem_ty = Some(ty),
+            }
+        }
+
+        let Some(elem_ty) = elem_ty else {
+            bail_at!(arr.metadata.span, "Cannot infer the type of an empty array");
+        };
+        let ty = DType::Array(Box::new(elem_ty), arr.count.unwrap_or(arr.elements.len()));
+        arr.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+
+    fn check_struct_init(&self, st_init: &mut StructInitExpr) -> anyhow::Result<DType> {
+        let decl = self
+            .st_decls
+            .iter()
+            .find(|x| x.name == st_init.struct_name)
+            .ok_or_else(|| {
+                err_at!(
+                    st_init.metadata.span,
+                    "Struct '{}' not defined",
+                    &st_init.struct_name
+                )
+                .with_code("E0303")
+            })?;
+
+        let decl_fields: HashSet<(String, DType)> = decl
+            .fields
+            .iter()
+            .map(|x| (x.field_name.clone(), x.field_type.clone()))
+            .collect();
+
+        let init_fields: HashSet<(String, DType)> = st_init
+            .arguments
+            .iter_mut()
+            .map(|x| {
+                let mut expr_ty = self.check_expr(&mut x.field_expr)?;
+                // pointers such as null take the type of the field.
+                if let Some(field) = decl.fields.iter().find(|f| f.field_name == x.field_name)
+                    && is_pointer_cast(&expr_ty, &field.field_type)
+                {
+                    expr_ty = field.field_type.clone();
+                }
+                Ok::<(String, DType), anyhow::Error>((x.field_name.clone(), expr_ty))
+            })
+            .collect::<Result<_, _>>()?;
+
+        // decl_fields - init_fields
+        if let Some(missing_field) = decl_fields.difference(&init_fields).next() {
+            return Err(err_at!(
+                st_init.metadata.span,
+                "Invalid type for '{}' in struct '{}'",
+                missing_field.0,
+                &decl.name
+            )
+            .with_code("E0300")
+            .with_note(format!("expected '{}'", missing_field.1))
+            .into());
+        }
+
+        let ty = DType::Derived(st_init.struct_name.clone());
+        st_init.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+
     fn check_native_call(&self, na_call: &mut NativeCallExpr) -> anyhow::Result<DType> {
-        let ty = DType::Primitive(EveTypes::Int);
+        // results are assumed to be C ints unless declared.
+        let ty = na_call
+            .return_type
+            .clone()
+            .unwrap_or(DType::Primitive(EveTypes::Int));
+        if let Some(name) = ty.struct_name()
+            && !self.st_decls.iter().any(|x| x.name == name)
+        {
+            return Err(
+                err_at!(na_call.metadata.span, "Struct '{}' not defined", name)
+                    .with_code("E0303")
+                    .into(),
+            );
+        }
 
         for arg in na_call.args.iter_mut() {
             self.check_expr(arg)?;
@@ -224,21 +562,22 @@ impl TypeSystem<'_> {
             return Ok(ty.to_owned());
         }
 
-        bail!(
-            "Variable '{}' not defined, line {}",
-            &var.name,
-            var.metadata.line
+        Err(
+            err_at!(var.metadata.span, "Variable '{}' not defined", &var.name)
+                .with_code("E0301")
+                .into(),
         )
     }
 
     fn check_literal(&self, literal: &mut LiteralExpr) -> DType {
-        let ty = DType::Primitive(match literal.value {
-            LiteralValue::NumberFloat(_) => EveTypes::Float,
-            LiteralValue::NumberInt(_) => EveTypes::Int,
-            LiteralValue::String(_) => EveTypes::String,
-            LiteralValue::Boolean(_) => EveTypes::Int,
-            LiteralValue::Null => EveTypes::Int,
-        });
+        let ty = match literal.value {
+            LiteralValue::NumberFloat(_) => DType::Primitive(EveTypes::Float),
+            LiteralValue::NumberInt(_) => DType::Primitive(EveTypes::Int),
+            LiteralValue::String(_) => DType::Primitive(EveTypes::String),
+            LiteralValue::Boolean(_) => DType::Primitive(EveTypes::Int),
+            // null is a pointer to anything.
+            LiteralValue::Null => DType::Pointer(Box::new(DType::Primitive(EveTypes::Void))),
+        };
 
         literal.metadata.node_type = Some(ty.clone());
         ty
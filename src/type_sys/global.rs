@@ -0,0 +1,226 @@
+use log::trace;
+
+use crate::ast::{
+    BinOp, DType, EveTypes, Expr, GlobalDecl, GlobalKind, LiteralExpr, LiteralValue, Metadata, UnOp,
+};
+use crate::diagnostic::Diagnostic;
+use crate::source_map::Span;
+use crate::{bail_at, err_at};
+
+use super::{TypeSystem, is_coercible};
+
+impl TypeSystem<'_> {
+    /// Checks const and static declarations in order,
+    /// replaces their initialisers with the value they evaluate to.
+    pub(super) fn check_globals(&mut self) {
+        for i in 0..self.global_decls.len() {
+            let mut decl = self.global_decls[i].clone();
+            trace!("checking {} : '{}'", decl.kind, &decl.name);
+
+            if let Some(first) = self.global_decls[..i].iter().find(|x| x.name == decl.name) {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("'{}' is declared more than once", decl.name))
+                        .with_code("E0314")
+                        .primary(decl.metadata.span, "declared again")
+                        .secondary(first.metadata.span, "first declared here"),
+                );
+                continue;
+            }
+
+            match self.check_global(&mut decl, i) {
+                Ok(()) => self.global_decls[i] = decl,
+                Err(e) => self
+                    .diagnostics
+                    .push(Diagnostic::from_anyhow(&e, decl.metadata.span)),
+            }
+        }
+    }
+
+    /// Evaluates the initialiser of a global, 'index' is the position of its declaration.
+    fn check_global(&self, decl: &mut GlobalDecl, index: usize) -> anyhow::Result<()> {
+        if !matches!(
+            decl.ty,
+            DType::Primitive(EveTypes::Int | EveTypes::Float | EveTypes::String)
+        ) {
+            bail_at!(
+                decl.metadata.span,
+                "Type of {} '{}' must be 'int', 'float' or 'string', found '{}'",
+                decl.kind,
+                decl.name,
+                decl.ty
+            );
+        }
+
+        let span = decl.value.span;
+        let mut value = self.eval_const(&decl.value, index)?;
+        let ty = Self::const_type(&value);
+        if !is_coercible(&ty, &decl.ty) {
+            return Err(err_at!(
+                decl.metadata.span,
+                "Cannot initialise {} '{}' of type '{}' with value of type '{}'",
+                decl.kind,
+                decl.name,
+                decl.ty,
+                ty
+            )
+            .with_code("E0300")
+            .secondary(span, format!("this is of type '{}'", ty))
+            .into());
+        }
+
+        if let LiteralValue::NumberInt(v) = value
+            && decl.ty == DType::Primitive(EveTypes::Float)
+        {
+            value = LiteralValue::NumberFloat(v as f64);
+        }
+
+        decl.value = Expr::Literal(LiteralExpr {
+            value,
+            metadata: Metadata {
+                span,
+                node_type: Some(decl.ty.clone()),
+            },
+        });
+        decl.metadata.node_type = Some(decl.ty.clone());
+        Ok(())
+    }
+
+    /// Evaluates a constant expression,
+    /// only constants declared before 'index' can be used in it.
+    fn eval_const(&self, expr: &Expr, index: usize) -> anyhow::Result<LiteralValue> {
+        match expr {
+            Expr::Literal(lit) => Ok(lit.value.clone()),
+            Expr::Grouping(group) => self.eval_const(&group.value, index),
+            Expr::Variable(var) => self.global_decls[..index]
+                .iter()
+                .find(|x| x.name == var.name && x.kind == GlobalKind::Const)
+                .and_then(|x| match &x.value {
+                    Expr::Literal(lit) => Some(lit.value.clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    err_at!(
+                        var.metadata.span,
+                        "'{}' is not a constant declared before this",
+                        var.name
+                    )
+                    .with_code("E0313")
+                    .into()
+                }),
+            Expr::Unary(un) => match (&un.op, self.eval_const(&un.operand, index)?) {
+                (UnOp::OpSub, LiteralValue::NumberInt(v)) => Ok(LiteralValue::NumberInt(
+                    v.checked_neg().ok_or_else(|| Self::overflow(un.metadata.span))?,
+                )),
+                (UnOp::OpSub, LiteralValue::NumberFloat(v)) => Ok(LiteralValue::NumberFloat(-v)),
+                (_, value) => bail_at!(
+                    un.metadata.span,
+                    "Cannot apply '{}' to '{}' in a constant expression",
+                    un.op,
+                    value
+                ),
+            },
+            Expr::Binary(bin) => {
+                let left = self.eval_const(&bin.left, index)?;
+                let right = self.eval_const(&bin.right, index)?;
+                let span = bin.metadata.span;
+                match (left, right) {
+                    (LiteralValue::NumberInt(l), LiteralValue::NumberInt(r)) => {
+                        if r == 0 && matches!(bin.op, BinOp::Div | BinOp::Mod) {
+                            return Err(err_at!(span, "Division by zero in constant expression")
+                                .with_code("E0313")
+                                .into());
+                        }
+                        let value = match bin.op {
+                            BinOp::Add => l.checked_add(r),
+                            BinOp::Sub => l.checked_sub(r),
+                            BinOp::Mul => l.checked_mul(r),
+                            BinOp::Div => l.checked_div(r),
+                            BinOp::Mod => l.checked_rem(r),
+                            _ => bail_at!(
+                                span,
+                                "Operator '{}' is not supported in constant expressions",
+                                bin.op
+                            ),
+                        };
+                        Ok(LiteralValue::NumberInt(
+                            value.ok_or_else(|| Self::overflow(span))?,
+                        ))
+                    }
+                    (
+                        l @ (LiteralValue::NumberInt(_) | LiteralValue::NumberFloat(_)),
+                        r @ (LiteralValue::NumberInt(_) | LiteralValue::NumberFloat(_)),
+                    ) => {
+                        let (l, r) = (Self::const_float(&l), Self::const_float(&r));
+                        Ok(LiteralValue::NumberFloat(match bin.op {
+                            BinOp::Add => l + r,
+                            BinOp::Sub => l - r,
+                            BinOp::Mul => l * r,
+                            BinOp::Div => l / r,
+                            _ => bail_at!(
+                                span,
+                                "Operator '{}' is not supported for floats in constant expressions",
+                                bin.op
+                            ),
+                        }))
+                    }
+                    (l, r) => bail_at!(
+                        span,
+                        "Cannot apply '{}' to '{}' and '{}' in a constant expression",
+                        bin.op,
+                        l,
+                        r
+                    ),
+                }
+            }
+            _ => Err(Diagnostic::error("Expected a constant expression")
+                .with_code("E0313")
+                .primary(expr.span, "not known at compile time")
+                .with_help("initialisers of constants and statics can only use literals, arithmetic and earlier constants")
+                .into()),
+        }
+    }
+
+    /// Returns value of a constant which isn't shadowed by a local binding.
+    pub(super) fn const_value(&self, name: &String) -> Option<LiteralValue> {
+        self.get_const(name).and_then(|x| match &x.value {
+            Expr::Literal(lit) => Some(lit.value.clone()),
+            _ => None,
+        })
+    }
+
+    /// Returns declaration of a constant which isn't shadowed by a local binding.
+    pub(super) fn get_const(&self, name: &String) -> Option<&GlobalDecl> {
+        if self.env.contains_key(name) {
+            return None;
+        }
+
+        self.global_decls
+            .iter()
+            .find(|x| &x.name == name && x.kind == GlobalKind::Const)
+    }
+
+    fn const_type(value: &LiteralValue) -> DType {
+        DType::Primitive(match value {
+            LiteralValue::NumberFloat(_) => EveTypes::Float,
+            LiteralValue::String(_) => EveTypes::String,
+            LiteralValue::NumberInt(_) | LiteralValue::Boolean(_) => EveTypes::Int,
+            LiteralValue::Null => {
+                return DType::Pointer(Box::new(DType::Primitive(EveTypes::Void)));
+            }
+        })
+    }
+
+    fn const_float(value: &LiteralValue) -> f64 {
+        match value {
+            LiteralValue::NumberInt(v) => *v as f64,
+            LiteralValue::NumberFloat(v) => *v,
+            _ => unreachable!("only numbers are converted to floats"),
+        }
+    }
+
+    fn overflow(span: Span) -> anyhow::Error {
+        err_at!(span, "Constant expression overflows 'int'")
+            .with_code("E0313")
+            .into()
+    }
+}
@@ -1,69 +1,117 @@
+mod enums;
 mod expr;
+mod global;
+mod pointer;
 mod stmt;
+mod vector;
 
 use std::collections::HashMap;
 
 use log::{debug, trace};
 
 use crate::{
-    ast::{DType, FnDecl, Stmt, StructDecl},
-    utils::{ErrorType, MessageType, report_message},
+    ast::{DType, EnumDecl, EveTypes, FnDecl, GlobalDecl, Stmt, StructDecl},
+    bail_at,
+    diagnostic::Diagnostic,
 };
 
 /// Anotate ast with types and check them.
 pub struct TypeSystem<'a> {
     fn_decls: &'a mut Vec<FnDecl>,
     st_decls: &'a mut Vec<StructDecl>,
-    pub errors_count: usize,
+    global_decls: &'a mut Vec<GlobalDecl>,
+    enum_decls: &'a Vec<EnumDecl>,
+    pub diagnostics: Vec<Diagnostic>,
     pub env: HashMap<String, DType>,
+    /// labels of the loops around the current statement, innermost last.
+    loop_labels: Vec<Option<String>>,
+    /// declared return type of the function being checked.
+    return_type: DType,
 }
 
 impl<'a> TypeSystem<'a> {
-    pub fn new(fn_decls: &'a mut Vec<FnDecl>, st_decls: &'a mut Vec<StructDecl>) -> Self {
+    pub fn new(
+        fn_decls: &'a mut Vec<FnDecl>,
+        st_decls: &'a mut Vec<StructDecl>,
+        global_decls: &'a mut Vec<GlobalDecl>,
+        enum_decls: &'a Vec<EnumDecl>,
+    ) -> Self {
         debug!("creating new type checker");
         Self {
             fn_decls,
             st_decls,
-            errors_count: 0,
+            global_decls,
+            enum_decls,
+            diagnostics: vec![],
             env: HashMap::new(),
+            loop_labels: vec![],
+            return_type: DType::Primitive(EveTypes::Void),
         }
     }
 
-    pub fn check(mut self) -> (usize, Vec<FnDecl>) {
+    /// Checks all globals, enums and functions, returns all type errors along with the annotated functions.
+    /// Initialisers of globals are replaced with their values in place.
+    pub fn check(mut self) -> (Vec<Diagnostic>, Vec<FnDecl>) {
         debug!("running type check");
+        self.check_globals();
+        self.check_enums();
+
         let mut fns = vec![];
         for mut fn_decl in self.fn_decls.clone() {
             trace!("checking function : '{}'", &fn_decl.name);
             self.env.clear();
+            self.loop_labels.clear();
+            self.return_type = fn_decl.return_type.clone();
 
-            if let Some(p) = &fn_decl.parameter {
+            for (i, p) in fn_decl.parameters.iter().enumerate() {
+                if let Some(first) = fn_decl.parameters[..i]
+                    .iter()
+                    .find(|x| x.field_name == p.field_name)
+                {
+                    self.diagnostics.push(
+                        Diagnostic::error(format!(
+                            "Parameter '{}' is declared more than once in function '{}'",
+                            p.field_name, fn_decl.name
+                        ))
+                        .with_code("E0306")
+                        .primary(p.metadata.span, "declared again")
+                        .secondary(first.metadata.span, "first declared here"),
+                    );
+                }
                 self.def_env(p.field_name.clone(), p.field_type.clone());
             }
 
             for stmt in &mut fn_decl.body {
                 if let Err(e) = self.check_stmt(stmt) {
-                    self.errors_count += 1;
-                    Self::report_msg(e.to_string());
+                    self.diagnostics
+                        .push(Diagnostic::from_anyhow(&e, fn_decl.metadata.span));
                 }
             }
             trace!("checked function : '{}'", &fn_decl.name);
             fns.push(fn_decl);
         }
 
-        (self.errors_count, fns)
+        (self.diagnostics, fns)
     }
 
     pub(super) fn check_stmt(&mut self, stmt: &mut Stmt) -> Result<DType, anyhow::Error> {
         match stmt {
             Stmt::Block(block) => self.check_block(block),
             Stmt::Let(le) => self.check_let(le),
-            Stmt::StructInit(st_init) => self.check_stinit(st_init),
             Stmt::If(ifst) => self.check_if(ifst),
             Stmt::Loop(lop) => self.check_loop(lop),
+            Stmt::While(whil) => self.check_while(whil),
+            Stmt::For(fo) => self.check_for(fo),
+            Stmt::Match(mat) => self.check_match(mat),
             Stmt::Break(bre) => self.check_break(bre),
+            Stmt::Continue(cont) => self.check_continue(cont),
             Stmt::Print(p) => self.check_print(p),
             Stmt::Return(ret) => self.check_return(ret),
             Stmt::Expression(expr) => self.check_expr(expr),
+            Stmt::Error(err) => bail_at!(
+                err.metadata.span,
+                "Cannot type check an erroneous statement"
+            ),
         }
     }
 
@@ -74,14 +122,40 @@ impl<'a> TypeSystem<'a> {
         l
     }
 
+    /// Retrieves type of a variable, falls back to globals.
     pub(super) fn get_env(&self, name: &String) -> Option<&DType> {
         trace!("retrieve var: {}", &name);
-        let l = self.env.get(name);
+        let l = self.env.get(name).or_else(|| {
+            self.global_decls
+                .iter()
+                .find(|x| &x.name == name)
+                .map(|x| &x.ty)
+        });
         trace!("env: {:?}", self.env);
         l
     }
+}
+
+/// Returns whether a value of type 'from' can be used where 'to' is expected.
+/// Ints are implicitly converted to Floats.
+pub(super) fn is_coercible(from: &DType, to: &DType) -> bool {
+    from == to
+        || is_pointer_cast(from, to)
+        || matches!(
+            (from, to),
+            (
+                DType::Primitive(EveTypes::Int),
+                DType::Primitive(EveTypes::Float)
+            )
+        )
+}
 
-    fn report_msg<M: Into<String>>(msg: M) {
-        report_message(msg.into(), MessageType::Error(ErrorType::TypeError))
+/// Returns whether a pointer of type 'from' can be used where 'to' is expected,
+/// like in C '*void' converts to and from every other pointer type.
+pub(super) fn is_pointer_cast(from: &DType, to: &DType) -> bool {
+    let void = DType::Primitive(EveTypes::Void);
+    match (from, to) {
+        (DType::Pointer(from), DType::Pointer(to)) => from == to || **from == void || **to == void,
+        _ => false,
     }
 }
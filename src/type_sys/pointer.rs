@@ -0,0 +1,55 @@
+use crate::ast::{AddrOfExpr, DType, DerefExpr, EveTypes, LValue};
+use crate::err_at;
+
+use super::TypeSystem;
+
+impl TypeSystem<'_> {
+    pub(super) fn check_addr_of(&self, addr: &mut AddrOfExpr) -> anyhow::Result<DType> {
+        // constants are inlined, so they don't live anywhere.
+        if let LValue::Variable(var) = &addr.target
+            && let Some(decl) = self.get_const(&var.name)
+        {
+            return Err(err_at!(
+                addr.metadata.span,
+                "Cannot take the address of constant '{}'",
+                &var.name
+            )
+            .with_code("E0318")
+            .secondary(decl.metadata.span, "declared as a constant here")
+            .with_help("declare it as 'static' to give it an address")
+            .into());
+        }
+
+        let (_, ty) = self.check_place(&mut addr.target, addr.metadata.span)?;
+        let ty = DType::Pointer(Box::new(ty));
+        addr.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+
+    pub(super) fn check_deref(&self, deref: &mut DerefExpr) -> anyhow::Result<DType> {
+        let pointer_ty = self.check_expr(&mut deref.pointer)?;
+        let ty = match pointer_ty {
+            DType::Pointer(pointee) if *pointee == DType::Primitive(EveTypes::Void) => {
+                return Err(err_at!(
+                    deref.metadata.span,
+                    "Cannot dereference a pointer to 'void'"
+                )
+                .with_code("E0319")
+                .with_help("assign it to a typed pointer first, 'let p: *int = ...'")
+                .into());
+            }
+            DType::Pointer(pointee) => *pointee,
+            ty => {
+                return Err(
+                    err_at!(deref.metadata.span, "'{}' is not a pointer", deref.pointer)
+                        .with_code("E0319")
+                        .with_note(format!("'{}' is of type '{}'", deref.pointer, ty))
+                        .into(),
+                );
+            }
+        };
+
+        deref.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+}
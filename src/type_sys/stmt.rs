@@ -1,13 +1,13 @@
-use std::collections::HashSet;
-
-use anyhow::{anyhow, bail};
-
 use crate::ast::{
-    BlockStmt, BreakStmt, DType, EveTypes, IfStmt, LetStmt, LoopStmt, PrintStmt, ReturnStmt,
-    StructInitStmt,
+    BlockStmt, BreakStmt, ContinueStmt, DType, EnumDecl, EveTypes, ForStmt, IfStmt, LetStmt,
+    LoopStmt, MatchPattern, MatchStmt, PrintStmt, ReturnStmt, Stmt, WhileStmt,
 };
+use crate::diagnostic::Diagnostic;
+use crate::err_at;
+use crate::source_map::Span;
+use crate::utils::closest_match;
 
-use super::TypeSystem;
+use super::{TypeSystem, is_coercible};
 
 impl TypeSystem<'_> {
     pub(super) fn check_block(&mut self, block: &mut BlockStmt) -> anyhow::Result<DType> {
@@ -18,53 +18,35 @@ impl TypeSystem<'_> {
     }
 
     pub(super) fn check_let(&mut self, le: &mut LetStmt) -> anyhow::Result<DType> {
-        let ty = self.check_expr(&mut le.initialiser)?;
-        self.def_env(le.name.clone(), ty.clone());
-        Ok(ty)
-    }
+        // names of enums are already resolved, so the remaining ones have to be structs.
+        if let Some(name) = le.ty.as_ref().and_then(|x| x.struct_name())
+            && !self.st_decls.iter().any(|x| x.name == name)
+        {
+            return Err(err_at!(le.metadata.span, "Unknown type '{}'", name)
+                .with_code("E0303")
+                .into());
+        }
 
-    pub(super) fn check_stinit(&mut self, st_init: &mut StructInitStmt) -> anyhow::Result<DType> {
-        let decl = self
-            .st_decls
-            .iter()
-            .find(|x| x.name == st_init.struct_name)
-            .ok_or(anyhow!(
-                "Struct '{}' not defined at, line {}",
-                &st_init.struct_name,
-                st_init.metadata.line
-            ))?;
-
-        let decl_fields: HashSet<(String, DType)> = decl
-            .fields
-            .iter()
-            .map(|x| (x.field_name.clone(), x.field_type.clone()))
-            .collect();
-
-        let init_fields: HashSet<(String, DType)> = st_init
-            .arguments
-            .iter_mut()
-            .map(|x| {
-                let expr_ty = self.check_expr(&mut x.field_expr)?;
-                Ok::<(String, DType), anyhow::Error>((x.field_name.clone(), expr_ty))
-            })
-            .collect::<Result<_, _>>()?;
-
-        // decl_fields - init_fields
-        if let Some(missing_field) = decl_fields.difference(&init_fields).next() {
-            bail!(
-                "Invalid type for '{}' in struct '{}', line {}",
-                missing_field.0,
-                &decl.name,
-                decl.metadata.line
-            );
+        let mut ty = self.check_expr(&mut le.initialiser)?;
+        if let Some(annotated) = &le.ty {
+            if !is_coercible(&ty, annotated) {
+                return Err(Diagnostic::error(format!(
+                    "Cannot initialise '{}' of type '{}' with value of type '{}'",
+                    le.name, annotated, ty
+                ))
+                .with_code("E0300")
+                .primary(
+                    le.initialiser.span,
+                    format!("expected '{}', found '{}'", annotated, ty),
+                )
+                .into());
+            }
+            ty = annotated.clone();
         }
 
-        // f.field_name
-        self.def_env(
-            st_init.name.clone(),
-            DType::Derived(st_init.struct_name.clone()),
-        );
-        Ok(DType::Primitive(EveTypes::Void))
+        le.metadata.node_type = Some(ty.clone());
+        self.def_env(le.name.clone(), ty.clone());
+        Ok(ty)
     }
 
     pub(super) fn check_if(&mut self, ifst: &mut IfStmt) -> anyhow::Result<DType> {
@@ -77,21 +59,248 @@ impl TypeSystem<'_> {
     }
 
     pub(super) fn check_loop(&mut self, loop_stmt: &mut LoopStmt) -> anyhow::Result<DType> {
-        self.check_stmt(&mut loop_stmt.body)
+        self.check_loop_body(
+            &loop_stmt.label,
+            loop_stmt.metadata.span,
+            &mut loop_stmt.body,
+        )
+    }
+
+    pub(super) fn check_while(&mut self, while_stmt: &mut WhileStmt) -> anyhow::Result<DType> {
+        self.check_expr(&mut while_stmt.condition)?;
+        self.check_loop_body(
+            &while_stmt.label,
+            while_stmt.metadata.span,
+            &mut while_stmt.body,
+        )
+    }
+
+    pub(super) fn check_for(&mut self, for_stmt: &mut ForStmt) -> anyhow::Result<DType> {
+        if let Some(initialiser) = &mut for_stmt.initialiser {
+            self.check_stmt(initialiser)?;
+        }
+        if let Some(condition) = &mut for_stmt.condition {
+            self.check_expr(condition)?;
+        }
+        if let Some(increment) = &mut for_stmt.increment {
+            self.check_expr(increment)?;
+        }
+        self.check_loop_body(&for_stmt.label, for_stmt.metadata.span, &mut for_stmt.body)
     }
 
-    pub(super) fn check_break(&mut self, _p: &mut BreakStmt) -> anyhow::Result<DType> {
+    /// Checks value and arms of a match, reports arms which can never be taken
+    /// and values which aren't covered by any arm.
+    pub(super) fn check_match(&mut self, match_stmt: &mut MatchStmt) -> anyhow::Result<DType> {
+        let ty = self.check_expr(&mut match_stmt.value)?;
+        // variants of an enum are covered by their values, like integers.
+        let enum_decls: &Vec<EnumDecl> = self.enum_decls;
+        let enum_decl = match &ty {
+            DType::Primitive(EveTypes::Int) => None,
+            DType::Enum(name) => enum_decls.iter().find(|x| &x.name == name),
+            _ => {
+                return Err(err_at!(
+                    match_stmt.value.span,
+                    "Cannot match on value of type '{}'",
+                    ty
+                )
+                .with_code("E0300")
+                .with_note("only 'Int' and enum values can be matched")
+                .into());
+            }
+        };
+
+        // sorted, non overlapping ranges of values covered by the arms so far.
+        let mut covered: Vec<(i64, i64)> = vec![];
+        for arm in &mut match_stmt.arms {
+            let (low, high) = match (&arm.pattern, enum_decl) {
+                (MatchPattern::Wildcard, _) => (i32::MIN as i64, i32::MAX as i64),
+                (MatchPattern::Int(v), None) => (*v as i64, *v as i64),
+                (MatchPattern::Range(low, high), None) => (*low as i64, *high as i64),
+                (MatchPattern::Variant(enum_name, variant), Some(decl))
+                    if *enum_name == decl.name =>
+                {
+                    let value = self
+                        .find_variant(enum_name, variant, arm.metadata.span)?
+                        .value;
+                    (value as i64, value as i64)
+                }
+                (pattern, _) => {
+                    return Err(err_at!(
+                        arm.metadata.span,
+                        "Pattern '{}' can't match value of type '{}'",
+                        pattern,
+                        ty
+                    )
+                    .with_code("E0300")
+                    .into());
+                }
+            };
+
+            if covered.iter().any(|&(l, h)| l <= low && high <= h) {
+                self.diagnostics.push(
+                    Diagnostic::error(format!("Unreachable match arm '{}'", arm.pattern))
+                        .with_code("E0311")
+                        .primary(arm.metadata.span, "already covered by the arms above"),
+                );
+            }
+            add_covered(&mut covered, low, high);
+
+            self.check_stmt(&mut arm.body)?;
+        }
+
+        let missing = match enum_decl {
+            None => first_uncovered(&covered).map(|v| v.to_string()),
+            Some(decl) => decl
+                .variants
+                .iter()
+                .find(|x| {
+                    let value = x.value as i64;
+                    !covered.iter().any(|&(l, h)| l <= value && value <= h)
+                })
+                .map(|x| format!("{}::{}", decl.name, x.name)),
+        };
+        if let Some(missing) = missing {
+            self.diagnostics.push(
+                Diagnostic::error("Non-exhaustive match")
+                    .with_code("E0310")
+                    .primary(
+                        match_stmt.metadata.span,
+                        format!("value '{}' not covered", missing),
+                    )
+                    .with_help("add a '_ => { }' arm to handle all remaining values"),
+            );
+        }
+
+        Ok(DType::Primitive(EveTypes::Void))
+    }
+
+    /// Checks body of a loop with its label in scope.
+    fn check_loop_body(
+        &mut self,
+        label: &Option<String>,
+        span: Span,
+        body: &mut Stmt,
+    ) -> anyhow::Result<DType> {
+        if let Some(label) = label
+            && self.loop_labels.iter().flatten().any(|x| x == label)
+        {
+            return Err(err_at!(
+                span,
+                "Label `'{}` is already in use by an outer loop",
+                label
+            )
+            .with_code("E0309")
+            .into());
+        }
+
+        self.loop_labels.push(label.clone());
+        let ty = self.check_stmt(body);
+        self.loop_labels.pop();
+        ty
+    }
+
+    pub(super) fn check_break(&mut self, bre: &mut BreakStmt) -> anyhow::Result<DType> {
+        self.check_jump("break", &bre.label, bre.metadata.span)
+    }
+
+    pub(super) fn check_continue(&mut self, cont: &mut ContinueStmt) -> anyhow::Result<DType> {
+        self.check_jump("continue", &cont.label, cont.metadata.span)
+    }
+
+    /// Checks that a break or continue is inside a loop and its label is in scope.
+    fn check_jump(
+        &self,
+        keyword: &str,
+        label: &Option<String>,
+        span: Span,
+    ) -> anyhow::Result<DType> {
+        if self.loop_labels.is_empty() {
+            return Err(err_at!(span, "'{}' outside of a loop", keyword)
+                .with_code("E0307")
+                .into());
+        }
+
+        if let Some(label) = label
+            && !self.loop_labels.iter().flatten().any(|x| x == label)
+        {
+            let mut err = Diagnostic::error(format!("Use of undeclared label `'{}`", label))
+                .with_code("E0308")
+                .primary(span, "label not found in any enclosing loop");
+
+            let names = self.loop_labels.iter().flatten().map(|x| x.as_str());
+            if let Some(similar) = closest_match(label, names) {
+                err = err.with_suggestion(
+                    span,
+                    "a label with a similar name is in scope",
+                    format!("{} '{}", keyword, similar),
+                );
+            }
+            return Err(err.into());
+        }
+
         Ok(DType::Primitive(EveTypes::Void))
     }
 
     pub(super) fn check_print(&mut self, p: &mut PrintStmt) -> anyhow::Result<DType> {
-        self.check_expr(&mut p.value)
+        let ty = self.check_expr(&mut p.value)?;
+        // enums are printed as the name of their variant.
+        if !matches!(ty, DType::Primitive(_) | DType::Enum(_)) {
+            return Err(
+                Diagnostic::error(format!("Cannot print value of type '{}'", ty))
+                    .with_code("E0300")
+                    .primary(p.value.span, format!("this is of type '{}'", ty))
+                    .with_help("only 'int', 'float', 'string' and enum values can be printed")
+                    .into(),
+            );
+        }
+        Ok(ty)
     }
 
     pub(super) fn check_return(&mut self, ret: &mut ReturnStmt) -> anyhow::Result<DType> {
-        match &mut ret.value {
-            Some(val) => self.check_expr(val),
-            None => Ok(DType::Primitive(EveTypes::Void)),
+        let Some(val) = &mut ret.value else {
+            return Ok(DType::Primitive(EveTypes::Void));
+        };
+
+        let ty = self.check_expr(val)?;
+        if !is_coercible(&ty, &self.return_type) {
+            return Err(Diagnostic::error(format!(
+                "Cannot return value of type '{}' from function returning '{}'",
+                ty, self.return_type
+            ))
+            .with_code("E0300")
+            .primary(
+                val.span,
+                format!("expected '{}', found '{}'", self.return_type, ty),
+            )
+            .into());
+        }
+        Ok(ty)
+    }
+}
+
+/// Adds range of values to sorted, non overlapping covered ranges.
+fn add_covered(covered: &mut Vec<(i64, i64)>, low: i64, high: i64) {
+    covered.push((low, high));
+    covered.sort();
+
+    let mut merged: Vec<(i64, i64)> = vec![];
+    for &(low, high) in covered.iter() {
+        match merged.last_mut() {
+            Some(last) if low <= last.1 + 1 => last.1 = last.1.max(high),
+            _ => merged.push((low, high)),
+        }
+    }
+    *covered = merged;
+}
+
+/// Returns the smallest Int which isn't in any of the covered ranges.
+fn first_uncovered(covered: &[(i64, i64)]) -> Option<i64> {
+    let mut next = i32::MIN as i64;
+    for &(low, high) in covered {
+        if low > next {
+            return Some(next);
         }
+        next = high + 1;
     }
+    (next <= i32::MAX as i64).then_some(next)
 }
@@ -0,0 +1,100 @@
+use log::trace;
+
+use crate::ast::{CallExpr, DType, EveTypes, Expr, VecNewExpr};
+use crate::diagnostic::Diagnostic;
+use crate::{bail_at, err_at};
+
+use super::{TypeSystem, is_coercible};
+
+impl TypeSystem<'_> {
+    pub(super) fn check_vec_new(&self, vec: &mut VecNewExpr) -> anyhow::Result<DType> {
+        if let Some(name) = vec.elem_type.struct_name()
+            && !self.st_decls.iter().any(|x| x.name == name)
+        {
+            return Err(err_at!(vec.metadata.span, "Struct '{}' not defined", name)
+                .with_code("E0303")
+                .into());
+        }
+
+        if vec.elem_type == DType::Primitive(EveTypes::Void) {
+            bail_at!(
+                vec.metadata.span,
+                "Vector elements cannot be of type 'void'"
+            );
+        }
+
+        let ty = DType::Vector(Box::new(vec.elem_type.clone()));
+        vec.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+
+    /// Checks a method call 'receiver.method(args)', only vectors have methods.
+    pub(super) fn check_method_call(&self, call: &mut CallExpr) -> anyhow::Result<DType> {
+        let Expr::FieldAccess(fiac) = &mut call.callee else {
+            bail_at!(call.metadata.span, "callee is not a method");
+        };
+        trace!("checking method call '{}'", &fiac.field);
+
+        let receiver_ty = self.check_expr(&mut fiac.parent)?;
+        let DType::Vector(elem) = &receiver_ty else {
+            return Err(err_at!(
+                call.metadata.span,
+                "No method '{}' on type '{}'",
+                fiac.field,
+                receiver_ty
+            )
+            .with_code("E0317")
+            .with_note("only vectors have methods")
+            .into());
+        };
+
+        let (params, ty) = match fiac.field.as_str() {
+            "push" => (vec![*elem.clone()], DType::Primitive(EveTypes::Void)),
+            "pop" => (vec![], *elem.clone()),
+            "len" => (vec![], DType::Primitive(EveTypes::Int)),
+            method => {
+                return Err(err_at!(
+                    call.metadata.span,
+                    "No method '{}' on type '{}'",
+                    method,
+                    receiver_ty
+                )
+                .with_code("E0317")
+                .with_help("vectors have the methods 'push', 'pop' and 'len'")
+                .into());
+            }
+        };
+
+        if call.args.len() != params.len() {
+            return Err(err_at!(
+                call.metadata.span,
+                "Method '{}' takes {} argument(s) but {} were supplied",
+                fiac.field,
+                params.len(),
+                call.args.len()
+            )
+            .with_code("E0305")
+            .into());
+        }
+
+        for (arg, param) in call.args.iter_mut().zip(&params) {
+            let arg_ty = self.check_expr(arg)?;
+            if !is_coercible(&arg_ty, param) {
+                return Err(Diagnostic::error(format!(
+                    "Mismatched type for argument of method '{}'",
+                    fiac.field
+                ))
+                .with_code("E0300")
+                .primary(
+                    arg.span,
+                    format!("expected '{}', found '{}'", param, arg_ty),
+                )
+                .into());
+            }
+        }
+
+        fiac.metadata.node_type = Some(ty.clone());
+        call.metadata.node_type = Some(ty.clone());
+        Ok(ty)
+    }
+}
@@ -1,8 +1,3 @@
-use std::fmt::Display;
-
-use colored::Colorize;
-use log::{error, warn};
-
 /// Wrapper around error! and panic!, so that i dont have to call them individually.
 #[macro_export]
 macro_rules! die {
@@ -12,6 +7,22 @@ macro_rules! die {
     }
 }
 
+/// Builds an error diagnostic pointing to the given span.
+#[macro_export]
+macro_rules! err_at {
+    ($span:expr, $($arg:tt)+) => {
+        $crate::diagnostic::Diagnostic::error(format!($($arg)+)).primary($span, "")
+    };
+}
+
+/// Returns early with an error pointing to the given span.
+#[macro_export]
+macro_rules! bail_at {
+    ($span:expr, $($arg:tt)+) => {
+        return Err($crate::err_at!($span, $($arg)+).into())
+    };
+}
+
 /// Checks whether the given char is ascii alpabetic or _; return True.
 /// else returns False.
 /// # Arguments
@@ -64,50 +75,55 @@ pub fn is_alphanumeric(target_char: char) -> bool {
     is_alpha(target_char) || is_numeric(target_char)
 }
 
-pub enum WarningType {
-    None,
-}
-
-pub enum ErrorType {
-    #[allow(dead_code)]
-    None,
-    TypeError,
-    ParsingError,
+/// Finds the candidate closest to the given name, used for 'did you mean' suggestions.
+/// Returns None if no candidate is close enough.
+/// # Arguments
+/// * `name` - the misspelled name.
+/// * `candidates` - names to choose from.
+///
+/// # Examples
+/// ```rust
+/// use evelin::utils::*;
+/// assert_eq!(closest_match("pritn", ["print", "main"]), Some("print".to_string()));
+/// assert_eq!(closest_match("foo", ["main"]), None);
+/// ```
+pub fn closest_match<'a, I>(name: &str, candidates: I) -> Option<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|c| (edit_distance(name, c), c))
+        .filter(|(d, _)| *d <= max_distance)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c.to_string())
 }
 
-pub enum MessageType {
-    Warning(WarningType),
-    Error(ErrorType),
-}
+/// Levenshtein distance between two strings, with adjacent swaps counted as one edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
 
-impl Display for MessageType {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let v = match self {
-            MessageType::Warning(w) => match w {
-                WarningType::None => "Warning",
-            }
-            .yellow(),
-            MessageType::Error(e) => match e {
-                ErrorType::None => "Error",
-                ErrorType::TypeError => "Type Error",
-                ErrorType::ParsingError => "Syntax Error",
-            }
-            .red(),
-        };
-        write!(f, "{}", v)
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in dist[0].iter_mut().enumerate() {
+        *cell = j;
     }
-}
 
-/// Reports messages to user.
-/// # Arguments
-/// * `msg` - Message
-/// * `ty` - Message Type
-pub fn report_message<M: Into<String>>(msg: M, ty: MessageType) {
-    let msg = msg.into();
-    if matches!(ty, MessageType::Error(_)) {
-        error!("{}: {}", ty, &msg);
-    } else {
-        warn!("{}: {}", ty, &msg);
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
     }
-    eprintln!("{}: {}", ty, msg);
+    dist[a.len()][b.len()]
 }
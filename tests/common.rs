@@ -1,6 +1,9 @@
+use std::collections::HashMap;
+
 use anyhow::bail;
 use evelin::{
-    ast::{FnDecl, StructDecl, Token},
+    ast::{EnumDecl, FnDecl, StructDecl, Token},
+    diagnostic::Diagnostic,
     emitter::{Emitter, qbee::QBEEmitter},
     lexer::Lexer,
     parser::Parser,
@@ -37,33 +40,177 @@ pub fn parser_struct(source: &str) -> Vec<StructDecl> {
     parser.struct_decls
 }
 
+#[allow(dead_code)]
+pub fn parse_enum(source: &str) -> Vec<EnumDecl> {
+    let tokens = tokenize(source.to_string());
+    let mut parser = Parser::from(&tokens);
+    parser.parse();
+    parser.enum_decls
+}
+
+/// Parses input which may contain errors, returns the parsed functions and diagnostics.
+#[allow(dead_code)]
+pub fn parse_recovering(source: &str) -> (Vec<FnDecl>, Vec<Diagnostic>) {
+    let source = source.to_string();
+    let mut lexer = Lexer::from(&source);
+    let _ = lexer.start();
+    let mut parser = Parser::from(lexer.tokens());
+    parser.parse();
+    (parser.fn_decls, parser.diagnostics)
+}
+
 #[allow(dead_code)]
 pub fn compile<T: Into<String>>(input: T) -> Result<String, anyhow::Error> {
-    let tokens = tokenize(&input.into());
+    compile_with(input, false)
+}
 
-    let (fns, sts, errors_count) = lex(tokens);
-    if errors_count != 0 {
-        bail!("Failed to compile due to {} parsing error(s)", errors_count);
+/// Same as 'compile', with runtime bounds checks of array indexing enabled or disabled.
+#[allow(dead_code)]
+pub fn compile_with<T: Into<String>>(
+    input: T,
+    bounds_checks: bool,
+) -> Result<String, anyhow::Error> {
+    let tokens = tokenize(input);
+    let mut parser = Parser::from(&tokens);
+    parser.parse();
+    if parser.errors_count != 0 {
+        bail!(
+            "Failed to compile due to {} parsing error(s)",
+            parser.errors_count
+        );
     }
 
-    let (mut fn_decls, mut struct_decls) = match passes::run_passes(fns, sts) {
-        Ok((fn_, st)) => (fn_, st),
+    let mut global_decls = parser.global_decls;
+    let enum_decls = parser.enum_decls;
+    let (mut fn_decls, mut struct_decls) = (parser.fn_decls, parser.struct_decls);
+    passes::resolve_enum_types(&enum_decls, &mut fn_decls, &mut struct_decls);
+    let (mut fn_decls, mut struct_decls) = match passes::run_passes(fn_decls, struct_decls) {
+        Ok((fn_, st, _)) => (fn_, st),
         Err(errs) => {
             bail!("Failed to compile due to {} error(s)", &errs.len());
         }
     };
 
-    let type_sys = type_sys::TypeSystem::new(&mut fn_decls, &mut struct_decls);
-    let (type_error_count, fn_decls) = type_sys.check();
-    if type_error_count != 0 {
+    let type_sys = type_sys::TypeSystem::new(
+        &mut fn_decls,
+        &mut struct_decls,
+        &mut global_decls,
+        &enum_decls,
+    );
+    let (type_errors, fn_decls) = type_sys.check();
+    if !type_errors.is_empty() {
         bail!(
             "Failed to compile due to {} type error(s)",
-            type_error_count
+            type_errors.len()
         );
     }
 
-    let mut qbe_generator = QBEEmitter::from((&fn_decls, &struct_decls));
+    let mut qbe_generator =
+        QBEEmitter::from((&fn_decls, &struct_decls, &global_decls, &enum_decls))
+            .set_bounds_checks(bounds_checks);
     let ir = qbe_generator.emit_ir()?;
 
     Ok(ir)
 }
+
+/// Runs everything before the emitter, returns diagnostics of the first failing stage.
+#[allow(dead_code)]
+pub fn check<T: Into<String>>(input: T) -> Vec<Diagnostic> {
+    let tokens = tokenize(input);
+    let mut parser = Parser::from(&tokens);
+    parser.parse();
+    if !parser.diagnostics.is_empty() {
+        return parser.diagnostics;
+    }
+
+    let mut global_decls = parser.global_decls;
+    let enum_decls = parser.enum_decls;
+    let (mut fn_decls, mut struct_decls) = (parser.fn_decls, parser.struct_decls);
+    passes::resolve_enum_types(&enum_decls, &mut fn_decls, &mut struct_decls);
+    let (mut fn_decls, mut struct_decls) = match passes::run_passes(fn_decls, struct_decls) {
+        Ok((fn_, st, _)) => (fn_, st),
+        Err(errs) => return errs,
+    };
+
+    let type_sys = type_sys::TypeSystem::new(
+        &mut fn_decls,
+        &mut struct_decls,
+        &mut global_decls,
+        &enum_decls,
+    );
+    type_sys.check().0
+}
+
+/// Returns whether lines of 'pattern' appear in a row in 'ir', ignoring indentation and
+/// blank lines. '%name' in the pattern matches any temporary and '#name' any number, like
+/// ids in labels, a name has to match the same text everywhere in the pattern and different
+/// names have to match different text, so numbering of temporaries doesn't matter.
+#[allow(dead_code)]
+pub fn contains_ir(ir: &str, pattern: &str) -> bool {
+    let ir: Vec<&str> = ir
+        .lines()
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .collect();
+    let pattern: Vec<&str> = pattern
+        .lines()
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .collect();
+
+    (0..ir.len()).any(|start| {
+        let mut bindings = HashMap::new();
+        pattern.iter().enumerate().all(|(i, pat)| {
+            ir.get(start + i)
+                .is_some_and(|line| match_ir_line(line, pat, &mut bindings))
+        })
+    })
+}
+
+fn match_ir_line<'a>(
+    line: &'a str,
+    pattern: &'a str,
+    bindings: &mut HashMap<&'a str, &'a str>,
+) -> bool {
+    let (mut line, mut pattern) = (line, pattern);
+    while let Some(p) = pattern.chars().next() {
+        let name_len = pattern[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(pattern.len() - 1);
+        if matches!(p, '%' | '#') && name_len > 0 {
+            let name = &pattern[..name_len + 1];
+            // temporaries are names with dots, numbers are only digits.
+            let text_len = if p == '%' {
+                if !line.starts_with('%') {
+                    return false;
+                }
+                1 + line[1..]
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_' && c != '.')
+                    .unwrap_or(line.len() - 1)
+            } else {
+                line.find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(line.len())
+            };
+            let text = &line[..text_len];
+            if text.is_empty() || text == "%" {
+                return false;
+            }
+            match bindings.get(name) {
+                Some(bound) if *bound != text => return false,
+                None if bindings.values().any(|x| *x == text) => return false,
+                _ => {
+                    bindings.insert(name, text);
+                }
+            }
+            line = &line[text_len..];
+            pattern = &pattern[name_len + 1..];
+        } else {
+            if !line.starts_with(p) {
+                return false;
+            }
+            line = &line[p.len_utf8()..];
+            pattern = &pattern[p.len_utf8()..];
+        }
+    }
+    line.is_empty()
+}
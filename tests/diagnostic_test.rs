@@ -0,0 +1,120 @@
+use evelin::cli::ErrorFormat;
+use evelin::diagnostic::{Diagnostic, Reporter};
+use evelin::source_map::{SourceMap, Span};
+
+fn span(byte_start: usize, byte_end: usize, line: usize, col: usize) -> Span {
+    Span {
+        file_id: 0,
+        byte_start,
+        byte_end,
+        line,
+        col,
+    }
+}
+
+#[test]
+fn test_render_primary_label() {
+    colored::control::set_override(false);
+    let mut source_map = SourceMap::new();
+    source_map.add_file(
+        "main.eve",
+        "fn main() -> void {\n  foo(1);\n}\n".to_string(),
+    );
+
+    let diag = Diagnostic::error("Call to undefined function 'foo'")
+        .with_code("E0201")
+        .primary(span(22, 25, 2, 3), "not found in any module");
+
+    let expected = "\
+error[E0201]: Call to undefined function 'foo'
+ --> main.eve:2:3
+  |
+2 |   foo(1);
+  |   ^^^ not found in any module";
+    assert_eq!(
+        Reporter::new(&source_map, ErrorFormat::Human).render(&diag),
+        expected
+    );
+}
+
+#[test]
+fn test_render_secondary_label_and_help() {
+    colored::control::set_override(false);
+    let mut source_map = SourceMap::new();
+    source_map.add_file("a.eve", "let a = 1;\nlet b = a + \"s\";\n".to_string());
+
+    let diag = Diagnostic::error("+ operation cannot be applied between Int and String")
+        .with_code("E0300")
+        .primary(span(21, 22, 2, 11), "")
+        .secondary(span(23, 26, 2, 13), "String")
+        .with_help("convert one of the operands");
+
+    let expected = "\
+error[E0300]: + operation cannot be applied between Int and String
+ --> a.eve:2:11
+  |
+2 | let b = a + \"s\";
+  |           ^
+  |             --- String
+  |
+  = help: convert one of the operands";
+    assert_eq!(
+        Reporter::new(&source_map, ErrorFormat::Human).render(&diag),
+        expected
+    );
+}
+
+#[test]
+fn test_render_without_labels() {
+    colored::control::set_override(false);
+    let diag = Diagnostic::warning("something happened").with_note("a note");
+    assert_eq!(
+        Reporter::new(&SourceMap::new(), ErrorFormat::Human).render(&diag),
+        "warning: something happened\n = note: a note"
+    );
+}
+
+#[test]
+fn test_render_short() {
+    colored::control::set_override(false);
+    let mut source_map = SourceMap::new();
+    source_map.add_file(
+        "main.eve",
+        "fn main() -> void {\n  foo(1);\n}\n".to_string(),
+    );
+
+    let diag = Diagnostic::error("Call to undefined function 'foo'")
+        .with_code("E0201")
+        .primary(span(22, 25, 2, 3), "not found in any module");
+
+    assert_eq!(
+        Reporter::new(&source_map, ErrorFormat::Short).render(&diag),
+        "main.eve:2:3: error[E0201]: Call to undefined function 'foo'"
+    );
+}
+
+#[test]
+fn test_render_json() {
+    let mut source_map = SourceMap::new();
+    source_map.add_file("main.eve", "fn main() -> void {\n  fo(1);\n}\n".to_string());
+
+    let diag = Diagnostic::error("Call to undefined function 'fo'")
+        .with_code("E0201")
+        .primary(span(22, 24, 2, 3), "not found in any module")
+        .with_suggestion(
+            span(22, 24, 2, 3),
+            "a function with a similar name exists",
+            "foo",
+        );
+
+    let rendered = Reporter::new(&source_map, ErrorFormat::Json).render(&diag);
+    let json: serde_json::Value = serde_json::from_str(&rendered).unwrap();
+    assert!(!rendered.contains('\n'));
+    assert_eq!(json["file"], "main.eve");
+    assert_eq!(json["severity"], "error");
+    assert_eq!(json["code"], "E0201");
+    assert_eq!(json["span"]["line"], 2);
+    assert_eq!(json["span"]["byte_start"], 22);
+    assert_eq!(json["labels"][0]["primary"], true);
+    assert_eq!(json["suggestions"][0]["replacement"], "foo");
+}
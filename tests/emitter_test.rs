@@ -0,0 +1,1020 @@
+mod common;
+use common::{compile, compile_with, contains_ir};
+
+#[test]
+fn emits_string_escapes_as_bytes() {
+    let ir = compile("fn main() -> void { print \"a\\t\\\"b\\\"\\n\"; }").unwrap();
+    assert!(
+        ir.contains("b \"a\", b 9, b 34, b \"b\", b 34, b 10, b 0"),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_raw_strings_verbatim() {
+    let ir = compile("fn main() -> void { print r\"\\d+\"; }").unwrap();
+    assert!(ir.contains("b \"d+\", b 0"), "{}", ir);
+    assert!(ir.contains("b 92, b \"d+\""), "{}", ir);
+}
+
+#[test]
+fn emits_multi_param_functions() {
+    let ir = compile(
+        "fn add(a: int, b: int) -> int { return a + b; }\n\
+         fn main() -> void { print add(1, 2); }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "export function w $add(w %a, w %b) {
+             @start
+             %sum =w add %a, %b",
+        ),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("call $add(w "), "{}", ir);
+}
+
+#[test]
+fn emits_int_to_float_return() {
+    let ir =
+        compile("fn one() -> float { return 1; }\nfn main() -> void { print one(); }").unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%one =w copy 1
+             %float =d swtof %one
+             ret %float",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_while_loop_with_continue() {
+    let ir = compile(
+        "fn main() -> void {\n\
+           let a = 0;\n\
+           while (a < 5) {\n\
+             a = a + 1;\n\
+             if (a == 2) { continue; }\n\
+             print a;\n\
+           }\n\
+         }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "@loop.#l.cond
+             %five =w copy 5
+             %cond =w csltw %a, %five
+             jnz %cond, @loop.#l.body, @loop.#l.end",
+        ),
+        "{}",
+        ir
+    );
+    // continue goes back to the condition.
+    assert!(
+        contains_ir(
+            &ir,
+            "@cond.#c.if
+             jmp @loop.#l.cond",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_for_loop_with_latch() {
+    let ir = compile(
+        "fn main() -> void {\n\
+           for (let i = 0; i < 3; i = i + 1) {\n\
+             if (i == 1) { continue; }\n\
+             print i;\n\
+           }\n\
+         }",
+    )
+    .unwrap();
+    // continue goes to the latch, which runs the increment.
+    assert!(
+        contains_ir(
+            &ir,
+            "@cond.#c.if
+             jmp @loop.#l.latch",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "@loop.#l.latch
+             %one =w copy 1
+             %next =w add %i, %one
+             %i =w copy %next
+             jmp @loop.#l.cond
+             @loop.#l.end",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_labeled_jumps_to_outer_loop() {
+    let ir = compile(
+        "fn main() -> void {\n\
+           'outer: loop {\n\
+             for (let i = 0; i < 3; i = i + 1) {\n\
+               if (i == 1) { continue 'outer; }\n\
+               break 'outer;\n\
+             }\n\
+           }\n\
+         }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             @loop.#outer.start",
+        ),
+        "{}",
+        ir
+    );
+    // both jumps skip the inner loop.
+    let outer = ir
+        .lines()
+        .find_map(|x| {
+            x.strip_suffix(".start")
+                .and_then(|x| x.strip_prefix("@loop."))
+        })
+        .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            &format!(
+                "@cond.#c.if
+                 jmp @loop.{outer}.start
+                 @cond.#c.end
+                 jmp @loop.{outer}.end"
+            ),
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_match_as_jump_chain() {
+    let ir = compile(
+        "fn main() -> void {\n\
+           let a = 3;\n\
+           match (a) {\n\
+             1 => print 1;\n\
+             2..=5 => { print 2; }\n\
+             _ => print 3;\n\
+           }\n\
+         }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "@match.#m.test.0
+             %eq =w ceqw %a, 1
+             jnz %eq, @match.#m.arm.0, @match.#m.test.1",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%above =w csgew %a, 2
+             %below =w cslew %a, 5
+             %in =w and %above, %below",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "@match.#m.test.2
+             jmp @match.#m.arm.2",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_match_on_enums() {
+    let ir = compile(
+        "enum Dir { Up, Down = 3 }\n\
+         fn main() -> void { let d = Dir::Down; match (d) { Dir::Up => print 0; Dir::Down => print 1; } }",
+    )
+    .unwrap();
+    // variants are compared by value.
+    assert!(
+        contains_ir(
+            &ir,
+            "@match.#m.test.1
+             %eq =w ceqw %d, 3
+             jnz %eq, @match.#m.arm.1, @match.#m.end",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_compound_assignment() {
+    let ir = compile("fn main() -> void { let a = 1; a += 2; let b = a++; print b; }").unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%two =w copy 2
+             %sum =w add %a, %two
+             %a =w copy %sum",
+        ),
+        "{}",
+        ir
+    );
+    // postfix updates evaluate to the old value.
+    assert!(
+        contains_ir(
+            &ir,
+            "%old =w copy %a
+             %one =w copy 1
+             %new =w add %old, %one
+             %a =w copy %new
+             %b =w copy %old",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_compound_assignment_to_element_once() {
+    let ir = compile(
+        "fn idx() -> int { return 1; }\n\
+         fn main() -> void { let a: [float; 2] = [1.0, 2.0]; a[idx()] *= 2; }",
+    )
+    .unwrap();
+    assert_eq!(ir.matches("call $idx()").count(), 1, "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%old =d loadd %elem
+             %two =w copy 2
+             %float =d swtof %two
+             %new =d mul %old, %float
+             stored %new, %elem",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_int_to_float_assignment() {
+    let ir = compile(
+        "struct P { x: float }\n\
+         fn main() -> void { let a = 1.5; a = 2; let p = P { x: a }; p.x = 3; }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%two =w copy 2
+             %float =d swtof %two
+             %a =d copy %float",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%three =w copy 3
+             %float =d swtof %three
+             %field =l add %p, 0
+             stored %float, %field",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_field_assignment() {
+    let ir = compile(
+        "struct P { x: int, y: int }\n\
+         fn main() -> void { let p = P { x: 1, y: 2 }; p.y = 5; print p.y; }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%five =w copy 5
+             %field =l add %p, 4
+             storew %five, %field",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_nested_structs() {
+    let ir = compile(
+        "struct Line { start: Point, end: Point, width: int }\n\
+         struct Point { x: int, y: float }\n\
+         fn main() -> void {\n\
+           let a = Point { x: 1, y: 2.0 };\n\
+           let l = Line { start: a, end: a, width: 2 };\n\
+           l.end.x = 7;\n\
+           l.start = a;\n\
+           print l.start.x + l.end.x;\n\
+         }",
+    )
+    .unwrap();
+    assert!(
+        ir.contains("type :Point = align 8 { w, d }\ntype :Line = align 8 { :Point, :Point, w }\n"),
+        "{}",
+        ir
+    );
+    // nested structs are copied into the outer one.
+    assert!(
+        contains_ir(
+            &ir,
+            "%start =l add %line, 0
+             blit %a, %start, 16
+             %end =l add %line, 16
+             blit %a, %end, 16",
+        ),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("=l alloc8 40\n"), "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%seven =w copy 7
+             %end =l add %l, 16
+             %x =l add %end, 0
+             storew %seven, %x
+             %start =l add %l, 0
+             blit %a, %start, 16",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_struct_init_expr() {
+    let ir = compile(
+        "struct P { x: int, y: int }\n\
+         struct Line { start: P, end: P }\n\
+         fn main() -> void {\n\
+           let l = Line { start: P { x: 1, y: 2 }, end: P { x: 3, y: 4 } };\n\
+           l.end = P { x: 5, y: 6 };\n\
+         }",
+    )
+    .unwrap();
+    assert_eq!(ir.matches("=l alloc8 16\n").count(), 1, "{}", ir);
+    assert_eq!(ir.matches("=l alloc8 8\n").count(), 3, "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%end =l add %line, 8
+             blit %p, %end, 8
+             %l =l copy %line",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%end =l add %l, 8
+             blit %p, %end, 8
+             ret",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_struct_literals_in_loops_once() {
+    let ir = compile(
+        "struct P { x: int, y: int }\n\
+         fn main() -> void { let i = 0; while (i < 10) { let p = P { x: i, y: 1 }; i = p.x + p.y; } }",
+    )
+    .unwrap();
+    // the literal is allocated at the start of the function, and filled in every iteration.
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             %p =l alloc8 8",
+        ),
+        "{}",
+        ir
+    );
+    assert_eq!(ir.matches("alloc8").count(), 1, "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%x =l add %p, 0
+             storew %i, %x",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_struct_return() {
+    let ir = compile(
+        "struct Vec2 { x: float, y: float }\n\
+         fn vec2(x: float) -> Vec2 { return Vec2 { x: x, y: 0.0 }; }\n\
+         fn main() -> void { let v = vec2(1.5); print v.x + vec2(2.0).x; }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "export function :Vec2 $vec2(d %x) {
+             @start
+             %v =l alloc8 16",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "stored %y, %field
+             ret %v",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%result =:Vec2 call $vec2(d %arg)
+             %v =l copy %result",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%result =:Vec2 call $vec2(d %arg)
+             %field =l add %result, 0",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_call_with_callee_signature() {
+    let ir = compile(
+        "fn half(x: float) -> float { return x / 2.0; }\n\
+         fn name() -> string { return \"eve\"; }\n\
+         fn main() -> void { print half(3); print name(); }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(&ir, "export function d $half(d %x) {"),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("export function l $name() {"), "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%three =w copy 3
+             %float =d swtof %three
+             %half =d call $half(d %float)",
+        ),
+        "{}",
+        ir
+    );
+    assert!(contains_ir(&ir, "%name =l call $name()"), "{}", ir);
+}
+
+#[test]
+fn emits_typed_let_coercion() {
+    let ir = compile("fn main() -> void { let x: float = 3; print x; }").unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%three =w copy 3
+             %float =d swtof %three
+             %x =d copy %float
+             call $printf(l $___FMT_DOUBLE, ..., d %x)",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_globals() {
+    let ir = compile(
+        "const WIDTH: int = 800;\n\
+         const HALF: float = WIDTH / 2;\n\
+         static count: int = -1;\n\
+         static name: string = \"eve\";\n\
+         fn main() -> void { count = count + WIDTH; print HALF; print name; }",
+    )
+    .unwrap();
+    assert!(
+        ir.contains("data $static.count = { w 4294967295 }"),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "data $glob.#s = { b \"eve\", b 0 }
+             data $static.name = { l $glob.#s }",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%count =w loadw $static.count
+             %width =w copy 800
+             %sum =w add %count, %width
+             storew %sum, $static.count",
+        ),
+        "{}",
+        ir
+    );
+    // 400.0, constants are inlined as literals.
+    assert!(
+        contains_ir(&ir, "%half =d copy 4645744490609377280"),
+        "{}",
+        ir
+    );
+    assert!(contains_ir(&ir, "%name =l loadl $static.name"), "{}", ir);
+}
+
+#[test]
+fn emits_arrays() {
+    let ir = compile(
+        "struct Grid { cells: [int; 4] }\n\
+         fn main() -> void { let a = [1, 2]; a[1] = a[0]; let b = [0.5; 8]; let g = Grid { cells: [0; 4] }; extern puts(a); }",
+    )
+    .unwrap();
+    assert!(
+        ir.contains("type :array.w.4 = align 4 { w 4 }\ntype :Grid = align 4 { :array.w.4 }\n"),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("type :array.d.8 = align 8 { d 8 }"), "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%one =w copy 1
+             %first =l add %arr, 0
+             storew %one, %first
+             %two =w copy 2
+             %second =l add %arr, 4
+             storew %two, %second
+             %a =l copy %arr",
+        ),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("=l alloc8 8\n"), "{}", ir);
+    // a[1] = a[0]
+    assert!(
+        contains_ir(
+            &ir,
+            "%zero =w copy 0
+             %index =l extsw %zero
+             %offset =l mul %index, 4
+             %elem =l add %a, %offset
+             %value =w loadw %elem",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%dest =l add %a, %offset
+             storew %value, %dest",
+        ),
+        "{}",
+        ir
+    );
+    // [0.5; 8] is filled in a loop.
+    assert!(
+        contains_ir(
+            &ir,
+            "%offset =l copy 0
+             @array.#f.fill
+             %elem =l add %arr, %offset
+             stored %value, %elem
+             %offset =l add %offset, 8
+             %more =w cultl %offset, 64
+             jnz %more, @array.#f.fill, @array.#f.end",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%cells =l add %grid, 0
+             blit %arr, %cells, 16",
+        ),
+        "{}",
+        ir
+    );
+    // arrays are passed to C as pointers.
+    assert!(contains_ir(&ir, "%r =w call $puts(l %a)"), "{}", ir);
+}
+
+#[test]
+fn emits_array_literals_in_loops_once() {
+    let ir = compile(
+        "fn main() -> void { let i = 0; while (i < 10) { let a = [0; 1024]; a[0] = i; i = i + 1; } }",
+    )
+    .unwrap();
+    // the literal is allocated at the start of the function, not in every iteration.
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             %a =l alloc8 4096",
+        ),
+        "{}",
+        ir
+    );
+    assert_eq!(ir.matches("alloc8").count(), 1, "{}", ir);
+}
+
+#[test]
+fn emits_bounds_checks() {
+    let src = "fn main() -> void { let a = [1, 2]; let i = 1; print a[i]; }";
+    let ir = compile(src).unwrap();
+    assert!(!ir.contains("@bounds"), "{}", ir);
+
+    // the index is compared unsigned, so negative ones fail as well.
+    let ir = compile_with(src, true).unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%ok =w cultw %i, 2
+             jnz %ok, @bounds.#b.ok, @bounds.#b.fail
+             @bounds.#b.fail
+             call $printf(l $___FMT_BOUNDS, ..., w %i, w 2)
+             call $exit(w 1)
+             @bounds.#b.ok
+             %index =l extsw %i",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_vectors() {
+    let ir = compile(
+        "struct P { x: int }\n\
+         fn main() -> void { let v = Vec<float>(); v.push(1); v[0] = 2.5; print v.len(); let ps = Vec<P>(); ps.push(P { x: 1 }); let p = ps.pop(); }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(&ir, "%v =l call $___eve_vec_new(l 8)"),
+        "{}",
+        ir
+    );
+    // ints pushed to float vectors are converted first.
+    assert!(
+        contains_ir(
+            &ir,
+            "%float =d swtof %one
+             %slot =l call $___eve_vec_push(l %v)
+             stored %float, %slot",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%slot =l call $___eve_vec_get(l %v, w %index)
+             stored %value, %slot",
+        ),
+        "{}",
+        ir
+    );
+    assert!(ir.contains("=w call $___eve_vec_len(l "), "{}", ir);
+    // structs are copied in and out of the vector.
+    assert!(ir.contains("call $___eve_vec_new(l 4)"), "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%slot =l call $___eve_vec_push(l %ps)
+             blit %p, %slot, 4",
+        ),
+        "{}",
+        ir
+    );
+    // popped structs are copied into a slot allocated once in the start block.
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             %popped =l alloc8 4",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%slot =l call $___eve_vec_pop(l %ps)
+             blit %slot, %popped, 4",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_vector_push_of_own_element() {
+    let ir = compile(
+        "struct P { x: int }\n\
+         fn main() -> void { let v = Vec<P>(); v.push(P { x: 1 }); let i = 0; v.push(v[i]); }",
+    )
+    .unwrap();
+    // pushing may move the elements, so the element is copied out before the push.
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             %copy =l alloc8 4",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%elem =l call $___eve_vec_get(l %v, w %i)
+             blit %elem, %copy, 4
+             %slot =l call $___eve_vec_push(l %v)
+             blit %copy, %slot, 4",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_pointers() {
+    let ir = compile(
+        "fn bump(p: *int) -> void { *p = *p + 1; }\n\
+         fn main() -> void { let x = 1; bump(&x); print x; let p = extern malloc(8) -> *int; print p == null; }",
+    )
+    .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "%old =w loadw %p
+             %one =w copy 1
+             %new =w add %old, %one
+             storew %new, %p",
+        ),
+        "{}",
+        ir
+    );
+    // a local whose address is taken lives in a stack slot.
+    assert!(
+        contains_ir(
+            &ir,
+            "export function w $main() {
+             @start
+             %x =l alloc8 4",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%one =w copy 1
+             storew %one, %x
+             %r =w call $bump(l %x)
+             %value =w loadw %x",
+        ),
+        "{}",
+        ir
+    );
+    // declared result types of native calls are used instead of int.
+    assert!(ir.contains("=l call $malloc("), "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%null =l copy 0
+             %eq =w ceql %p, %null",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_stack_slots_in_loops_once() {
+    let ir = compile(
+        "fn bump(p: *int) -> void { *p = *p + 1; }\n\
+         fn main() -> void { let i = 0; while (i < 10) { let x = i; bump(&x); i = x; } }",
+    )
+    .unwrap();
+    // the slot is allocated once, and the value is stored in every iteration.
+    assert!(
+        contains_ir(
+            &ir,
+            "export function w $main() {
+             @start
+             %x =l alloc8 4",
+        ),
+        "{}",
+        ir
+    );
+    assert_eq!(ir.matches("alloc8").count(), 1, "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "storew %i, %x
+             %r =w call $bump(l %x)",
+        ),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_enums() {
+    let ir = compile(
+        "enum State { Idle, Running = 5, Failed = -1 }\n\
+         fn main() -> void { let s = State::Failed; if (s != State::Idle) { print s; } }",
+    )
+    .unwrap();
+    // enums are words, negative values are written as their 32 bit pattern.
+    assert!(contains_ir(&ir, "%failed =w copy 4294967295"), "{}", ir);
+    assert!(
+        contains_ir(
+            &ir,
+            "%idle =w copy 0
+             %ne =w cnew %s, %idle",
+        ),
+        "{}",
+        ir
+    );
+    // variants are printed by name.
+    assert!(
+        contains_ir(
+            &ir,
+            "%name =l call $enum_name.State(w %s)
+             call $printf(l $___FMT_LONG, ..., l %name)",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%eq =w ceqw %value, 5
+             jnz %eq, @variant.Running, @not.Running
+             @variant.Running
+             ret $enum.State.Running",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        ir.contains("data $enum.State.Failed = { b \"Failed\", b 0 }"),
+        "{}",
+        ir
+    );
+}
+
+#[test]
+fn emits_struct_copies() {
+    let ir = compile(
+        "struct P { x: int, y: int }\n\
+         fn main() -> void { let a = P { x: 1, y: 2 }; let b = a; b.x = 10; print a.x; b = a; }",
+    )
+    .unwrap();
+    // 'b' gets its own slot, allocated once at the start of the function.
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             %b =l alloc8 8",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%a =l copy %p
+             blit %a, %b, 8",
+        ),
+        "{}",
+        ir
+    );
+    // updating 'b' doesn't touch 'a', and assigning 'a' copies it again.
+    assert!(
+        contains_ir(
+            &ir,
+            "%ten =w copy 10
+             %x =l add %b, 0
+             storew %ten, %x",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%x =l add %a, 0
+             %value =w loadw %x",
+        ),
+        "{}",
+        ir
+    );
+    assert_eq!(ir.matches("blit ").count(), 2, "{}", ir);
+    assert!(ir.contains(", 8\n\tret\n"), "{}", ir);
+}
+
+#[test]
+fn emits_array_copies() {
+    let ir =
+        compile("fn main() -> void { let a = [1, 2]; let b = a; b[0] = 9; print a[0]; b = a; }")
+            .unwrap();
+    assert!(
+        contains_ir(
+            &ir,
+            "@start
+             %b =l alloc8 8",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%a =l copy %arr
+             blit %a, %b, 8",
+        ),
+        "{}",
+        ir
+    );
+    // the element is stored into the copy, and read from the original.
+    assert!(
+        contains_ir(
+            &ir,
+            "%elem =l add %b, %offset
+             storew %nine, %elem",
+        ),
+        "{}",
+        ir
+    );
+    assert!(
+        contains_ir(
+            &ir,
+            "%elem =l add %a, %offset
+             %value =w loadw %elem",
+        ),
+        "{}",
+        ir
+    );
+    assert_eq!(ir.matches("blit ").count(), 2, "{}", ir);
+}
@@ -1,17 +1,20 @@
 use common::tokenize;
-use evelin::lexer::{LiteralValue, TokenType};
+use evelin::lexer::{Lexer, LiteralValue, TokenType};
+use evelin::source_map::{SourceMap, Span};
 
 mod common;
 
 #[test]
 fn test_single_char_tokens() {
-    let input = "(){},.-->+:;*%";
+    let input = "(){}[],.- ->+:;*%&";
     let tokens = tokenize(input);
     let expected = vec![
         TokenType::LeftParen,
         TokenType::RightParen,
         TokenType::LeftBrace,
         TokenType::RightBrace,
+        TokenType::LeftBracket,
+        TokenType::RightBracket,
         TokenType::Comma,
         TokenType::Dot,
         TokenType::Minus,
@@ -21,6 +24,7 @@ fn test_single_char_tokens() {
         TokenType::Semicolon,
         TokenType::Star,
         TokenType::Mod,
+        TokenType::Ampersand,
         TokenType::Eof,
     ];
     let actual: Vec<_> = tokens.iter().map(|t| t.ttype.clone()).collect();
@@ -54,6 +58,7 @@ fn test_integer() {
 }
 
 #[test]
+#[allow(clippy::approx_constant)]
 fn test_float() {
     let tokens = tokenize("3.14");
     assert_eq!(tokens[0].ttype, TokenType::NumberFloat);
@@ -89,12 +94,20 @@ fn test_keywords() {
         ("if", TokenType::If),
         ("else", TokenType::Else),
         ("loop", TokenType::Loop),
+        ("while", TokenType::While),
+        ("for", TokenType::For),
         ("break", TokenType::Break),
+        ("continue", TokenType::Continue),
+        ("match", TokenType::Match),
         ("print", TokenType::Print),
         ("struct", TokenType::Struct),
         ("extern", TokenType::Extern),
+        ("const", TokenType::Const),
+        ("static", TokenType::Static),
+        ("enum", TokenType::Enum),
         ("float", TokenType::TypeFloat),
         ("int", TokenType::TypeInt),
+        ("string", TokenType::TypeString),
         ("void", TokenType::TypeVoid),
     ];
 
@@ -123,7 +136,349 @@ fn test_illegal_char() {
 }
 
 #[test]
-#[should_panic(expected = "Non terminated string.")]
+fn test_token_spans() {
+    let src = "let a\n  = 1.5;".to_string();
+    let mut lexer = Lexer::from((&src, 3));
+    lexer.start().unwrap();
+    let tokens = lexer.tokens();
+
+    let eq = &tokens[2];
+    assert_eq!(eq.ttype, TokenType::Equal);
+    assert_eq!(
+        eq.span,
+        Span {
+            file_id: 3,
+            byte_start: 8,
+            byte_end: 9,
+            line: 2,
+            col: 3,
+        }
+    );
+
+    let num = &tokens[3];
+    assert_eq!(&src[num.span.byte_start..num.span.byte_end], "1.5");
+    assert_eq!((num.span.line, num.span.col), (2, 5));
+}
+
+#[test]
+fn test_source_map_location() {
+    let mut source_map = SourceMap::new();
+    source_map.add_file("a.eve", "fn main() -> void {}".to_owned());
+    let id = source_map.add_file("b.eve", "\n  @".to_owned());
+
+    let src = source_map.get(id).unwrap().src.clone();
+    let errs = Lexer::from((&src, id)).start().unwrap_err();
+    let diag = &errs[0];
+    assert_eq!(diag.code, Some("E0001"));
+    assert_eq!(
+        source_map.location(&diag.primary_span().unwrap()),
+        "b.eve:2:3"
+    );
+}
+
+#[test]
+fn test_lexer_recovers_from_illegal_chars() {
+    let src = "let a = @ 1; $ print a;".to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+
+    assert_eq!(errs.len(), 2);
+    assert!(errs.iter().all(|e| e.code == Some("E0001")));
+    assert_eq!(errs[1].primary_span().unwrap().col, 14);
+
+    let ttypes: Vec<TokenType> = lexer.tokens().iter().map(|t| t.ttype.clone()).collect();
+    assert_eq!(
+        ttypes,
+        vec![
+            TokenType::Let,
+            TokenType::Identifier,
+            TokenType::Equal,
+            TokenType::Error,
+            TokenType::NumberInt,
+            TokenType::Semicolon,
+            TokenType::Error,
+            TokenType::Print,
+            TokenType::Identifier,
+            TokenType::Semicolon,
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
 fn test_unterminated_string() {
-    tokenize("\"unclosed");
+    let src = "print \"abc;\nprint 1;".to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].code, Some("E0002"));
+    let span = errs[0].primary_span().unwrap();
+    assert_eq!((span.line, span.col), (1, 7));
+
+    let tokens = lexer.tokens();
+    assert_eq!(tokens[1].ttype, TokenType::Error);
+    assert_eq!(tokens.last().unwrap().ttype, TokenType::Eof);
+}
+
+#[test]
+fn test_utf8_source() {
+    let src = "// héllo wörld ✓\nlet s = \"naïve – 日本\"; let π = 1;".to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+    let tokens = lexer.tokens();
+
+    assert_eq!(tokens[0].ttype, TokenType::Let);
+    assert_eq!(tokens[0].span.line, 2);
+
+    let string = &tokens[3];
+    assert_eq!(string.ttype, TokenType::String);
+    assert_eq!(
+        string.literal,
+        LiteralValue::String("naïve – 日本".to_string())
+    );
+    assert_eq!(
+        &src[string.span.byte_start..string.span.byte_end],
+        "\"naïve – 日本\""
+    );
+
+    // columns are counted in characters.
+    let semicolon = &tokens[4];
+    assert_eq!((semicolon.span.line, semicolon.span.col), (2, 21));
+
+    // non ascii identifiers are not supported.
+    assert_eq!(errs.len(), 1);
+    assert_eq!(tokens[6].ttype, TokenType::Error);
+    assert_eq!(tokens[6].lexeme, "π");
+}
+
+#[test]
+fn test_string_escapes() {
+    let tokens = tokenize(r#""a\n\t\\\"\0\x41\u{1F600}""#);
+    assert_eq!(
+        tokens[0].literal,
+        LiteralValue::String("a\n\t\\\"\0A\u{1F600}".to_string())
+    );
+    assert_eq!(tokens[0].lexeme, r#""a\n\t\\\"\0\x41\u{1F600}""#);
+}
+
+#[test]
+fn test_raw_string() {
+    let tokens = tokenize(r#"r"\d+\n" r"#);
+    assert_eq!(tokens[0].ttype, TokenType::String);
+    assert_eq!(
+        tokens[0].literal,
+        LiteralValue::String(r"\d+\n".to_string())
+    );
+    // 'r' not followed by a quote is still an identifier.
+    assert_eq!(tokens[1].ttype, TokenType::Identifier);
+}
+
+#[test]
+fn test_invalid_escapes() {
+    let src = r#"print "\q \x8F \xZ \u{110000} \u{41"; print 1;"#.to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+
+    assert_eq!(errs.len(), 5);
+    assert!(errs.iter().all(|e| e.code == Some("E0003")));
+    let span = errs[0].primary_span().unwrap();
+    assert_eq!(&src[span.byte_start..span.byte_end], r"\q");
+    assert_eq!(span.col, 8);
+
+    // the string is still scanned, and lexing continues after it.
+    let tokens = lexer.tokens();
+    assert_eq!(tokens[1].ttype, TokenType::String);
+    assert_eq!(tokens[3].ttype, TokenType::Print);
+}
+
+#[test]
+fn test_number_literal_forms() {
+    let tokens = tokenize("0xFF 0o17 0b1010 1_000_000 1e-3 2.5E+2 1.5f 3f 10i 0xFFFFFFFFu");
+    let literals: Vec<LiteralValue> = tokens.iter().map(|t| t.literal.clone()).collect();
+    assert_eq!(
+        literals,
+        vec![
+            LiteralValue::NumberInt(255),
+            LiteralValue::NumberInt(15),
+            LiteralValue::NumberInt(10),
+            LiteralValue::NumberInt(1_000_000),
+            LiteralValue::NumberFloat(1e-3),
+            LiteralValue::NumberFloat(250.0),
+            LiteralValue::NumberFloat(1.5),
+            LiteralValue::NumberFloat(3.0),
+            LiteralValue::NumberInt(10),
+            LiteralValue::NumberInt(-1),
+            LiteralValue::Null,
+        ]
+    );
+    assert_eq!(tokens[7].ttype, TokenType::NumberFloat);
+    assert_eq!(tokens[0].lexeme, "0xFF");
+}
+
+#[test]
+fn test_number_literal_errors() {
+    let src = "3000000000; 0b102; 12xy; 0x; 1e999; 5000000000u; 1.5u;".to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+
+    let codes: Vec<&str> = errs.iter().map(|e| e.code.unwrap()).collect();
+    assert_eq!(
+        codes,
+        vec![
+            "E0004", "E0005", "E0005", "E0005", "E0004", "E0004", "E0005"
+        ]
+    );
+    assert!(errs[0].help.as_ref().unwrap().contains("'u' suffix"));
+    assert!(errs[1].message.contains("'2'"));
+
+    // every literal still produces a number token.
+    let numbers = lexer
+        .tokens()
+        .iter()
+        .filter(|t| matches!(t.ttype, TokenType::NumberInt | TokenType::NumberFloat))
+        .count();
+    assert_eq!(numbers, 7);
+}
+
+#[test]
+fn test_negative_number_literals() {
+    let tokens = tokenize("= -2147483648; -0x10 - -1.5; a -1");
+    let types: Vec<TokenType> = tokens.iter().map(|t| t.ttype.clone()).collect();
+    assert_eq!(
+        types,
+        vec![
+            TokenType::Equal,
+            TokenType::NumberInt,
+            TokenType::Semicolon,
+            TokenType::NumberInt,
+            TokenType::Minus,
+            TokenType::NumberFloat,
+            TokenType::Semicolon,
+            TokenType::Identifier,
+            TokenType::Minus,
+            TokenType::NumberInt,
+            TokenType::Eof,
+        ]
+    );
+    // the range is checked after negation.
+    assert_eq!(tokens[1].literal, LiteralValue::NumberInt(i32::MIN));
+    assert_eq!(tokens[1].lexeme, "-2147483648");
+    assert_eq!(tokens[3].literal, LiteralValue::NumberInt(-16));
+    assert_eq!(tokens[5].literal, LiteralValue::NumberFloat(-1.5));
+    assert_eq!(tokens[9].literal, LiteralValue::NumberInt(1));
+
+    let src = "-2147483649; -1u;".to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+    let codes: Vec<&str> = errs.iter().map(|e| e.code.unwrap()).collect();
+    assert_eq!(codes, vec!["E0004", "E0004"]);
+    assert_eq!(errs[0].notes[0], "the minimum value of int is -2147483648");
+}
+
+#[test]
+fn test_block_comments() {
+    let tokens = tokenize("let /* a /* nested */ comment\n */ a = 1;");
+    let ttypes: Vec<TokenType> = tokens.iter().map(|t| t.ttype.clone()).collect();
+    assert_eq!(
+        ttypes,
+        vec![
+            TokenType::Let,
+            TokenType::Identifier,
+            TokenType::Equal,
+            TokenType::NumberInt,
+            TokenType::Semicolon,
+            TokenType::Eof,
+        ]
+    );
+    assert_eq!(tokens[1].span.line, 2);
+}
+
+#[test]
+fn test_unterminated_block_comment() {
+    let src = "let a = 1; /* /* */".to_string();
+    let mut lexer = Lexer::from(&src);
+    let errs = lexer.start().unwrap_err();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].code, Some("E0006"));
+    assert_eq!(errs[0].primary_span().unwrap().col, 12);
+}
+
+#[test]
+fn test_doc_comments() {
+    let tokens = tokenize("/// first line\n///second\n//// not a doc\n// plain\nfn");
+    assert_eq!(tokens[0].ttype, TokenType::Fn);
+    assert_eq!(tokens[0].docs, vec!["first line", "second"]);
+    assert!(tokens[1].docs.is_empty());
+}
+
+#[test]
+fn test_loop_labels() {
+    let tokens = tokenize("'outer: loop { break 'outer; }");
+    assert_eq!(tokens[0].ttype, TokenType::Label);
+    assert_eq!(tokens[0].lexeme, "'outer");
+    assert_eq!(tokens[0].literal, LiteralValue::String("outer".to_string()));
+    assert_eq!(tokens[1].ttype, TokenType::Colon);
+    assert_eq!(tokens[5].ttype, TokenType::Label);
+}
+
+#[test]
+fn test_match_tokens() {
+    let tokens = tokenize("1..5 2..=3 _ => 1.5");
+    let types: Vec<TokenType> = tokens.iter().map(|x| x.ttype.clone()).collect();
+    assert_eq!(
+        types,
+        vec![
+            TokenType::NumberInt,
+            TokenType::DotDot,
+            TokenType::NumberInt,
+            TokenType::NumberInt,
+            TokenType::DotDotEqual,
+            TokenType::NumberInt,
+            TokenType::Identifier,
+            TokenType::MatchArrow,
+            TokenType::NumberFloat,
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_path_tokens() {
+    let tokens = tokenize("State::Idle a: b");
+    let types: Vec<TokenType> = tokens.iter().map(|x| x.ttype.clone()).collect();
+    assert_eq!(
+        types,
+        vec![
+            TokenType::Identifier,
+            TokenType::ColonColon,
+            TokenType::Identifier,
+            TokenType::Identifier,
+            TokenType::Colon,
+            TokenType::Identifier,
+            TokenType::Eof,
+        ]
+    );
+}
+
+#[test]
+fn test_compound_assignment_tokens() {
+    let tokens = tokenize("+= -= *= /= %= ++ -- -> - /");
+    let types: Vec<TokenType> = tokens.iter().map(|x| x.ttype.clone()).collect();
+    assert_eq!(
+        types,
+        vec![
+            TokenType::PlusEqual,
+            TokenType::MinusEqual,
+            TokenType::StarEqual,
+            TokenType::SlashEqual,
+            TokenType::ModEqual,
+            TokenType::PlusPlus,
+            TokenType::MinusMinus,
+            TokenType::FatArrow,
+            TokenType::Minus,
+            TokenType::Slash,
+            TokenType::Eof,
+        ]
+    );
 }
@@ -1,9 +1,13 @@
 use evelin::ast::{
-    BinOp, DType, EveTypes, Expr, FnStDeclField, LiteralExpr, LiteralValue, Metadata, Stmt,
+    AssignKind, BinOp, DType, EveTypes, Expr, FnStDeclField, GlobalKind, LValue, LiteralExpr,
+    LiteralValue, MatchPattern, Metadata, Stmt,
 };
+use evelin::lexer::Lexer;
+use evelin::parser::Parser;
+use evelin::source_map::Span;
 
 mod common;
-use common::{parse_fn, parser_struct};
+use common::{parse_enum, parse_fn, parse_recovering, parser_struct, tokenize};
 
 #[test]
 fn parses_empty_struct() {
@@ -29,17 +33,31 @@ fn parses_struct_with_fields() {
                 field_name: "x".to_string(),
                 field_type: DType::Primitive(EveTypes::Int),
                 metadata: Metadata {
-                    line: 1,
+                    span: Span {
+                        file_id: 0,
+                        byte_start: 15,
+                        byte_end: 16,
+                        line: 1,
+                        col: 16,
+                    },
                     node_type: None
-                }
+                },
+                docs: vec![],
             },
             FnStDeclField {
                 field_name: "y".to_string(),
                 field_type: DType::Primitive(EveTypes::Float),
                 metadata: Metadata {
-                    line: 1,
+                    span: Span {
+                        file_id: 0,
+                        byte_start: 23,
+                        byte_end: 24,
+                        line: 1,
+                        col: 24,
+                    },
                     node_type: None
-                }
+                },
+                docs: vec![],
             },
         ]
     );
@@ -66,9 +84,11 @@ fn parses_let_stmt() {
 #[test]
 fn parses_struct_init_stmt() {
     let parser = parse_fn("fn test() -> int { let a = Point { x: 2, y: 3 }; }");
-    if let Stmt::StructInit(struct_init) = &parser[0].body[0] {
+    if let Stmt::Let(le) = &parser[0].body[0]
+        && let Expr::StructInit(struct_init) = &le.initialiser
+    {
         assert_eq!("Point".to_string(), struct_init.struct_name);
-        assert_eq!("a".to_string(), struct_init.name);
+        assert_eq!("a".to_string(), le.name);
 
         let first = &struct_init.arguments[0];
         assert_eq!(first.field_name, "x".to_owned());
@@ -77,8 +97,8 @@ fn parses_struct_init_stmt() {
             Expr::Literal(LiteralExpr {
                 value: LiteralValue::NumberInt(2),
                 metadata: Metadata {
-                    line: 1,
-                    node_type: None
+                    node_type: None,
+                    ..
                 }
             }),
         );
@@ -90,8 +110,8 @@ fn parses_struct_init_stmt() {
             Expr::Literal(LiteralExpr {
                 value: LiteralValue::NumberInt(3),
                 metadata: Metadata {
-                    line: 1,
-                    node_type: None
+                    node_type: None,
+                    ..
                 }
             }),
         );
@@ -107,7 +127,7 @@ fn parses_function_without_param() {
     assert_eq!(parser.len(), 1);
     let f = &parser[0];
     assert_eq!(f.name, "main");
-    assert!(f.parameter.is_none());
+    assert!(f.parameters.is_empty());
     assert_eq!(f.return_type, DType::Primitive(EveTypes::Void));
     assert!(!f.body.is_empty());
 }
@@ -120,15 +140,22 @@ fn parses_function_with_param() {
     let f = &parser[0];
     assert_eq!(f.name, "inc");
     assert_eq!(
-        f.parameter,
-        Some(FnStDeclField {
+        f.parameters,
+        vec![FnStDeclField {
             field_name: "x".to_string(),
             field_type: DType::Primitive(EveTypes::Int),
             metadata: Metadata {
-                line: 1,
+                span: Span {
+                    file_id: 0,
+                    byte_start: 7,
+                    byte_end: 8,
+                    line: 1,
+                    col: 8,
+                },
                 node_type: None
-            }
-        })
+            },
+            docs: vec![],
+        }]
     );
     assert_eq!(f.return_type, DType::Primitive(EveTypes::Int));
 }
@@ -206,7 +233,7 @@ fn parses_call_without_arg() {
                     _ => panic!("Expected Expr::Variable"),
                 }
 
-                assert!(call.arg.is_none());
+                assert!(call.args.is_empty());
             }
             _ => panic!("Expressed call expression."),
         },
@@ -230,7 +257,7 @@ fn parses_call_with_arg() {
                     _ => panic!("Expected Expr::Variable"),
                 }
 
-                match call.arg.clone() {
+                match call.args.first().cloned() {
                     Some(arg) => match arg {
                         Expr::Binary(bin) => {
                             match bin.left {
@@ -278,3 +305,628 @@ fn parses_native_call_without_arg() {
         _ => panic!("Expected expression stmt."),
     }
 }
+
+#[test]
+fn parses_through_lexer_errors() {
+    let src = "fn main() -> void { let a = @; print a; }\nfn other() -> void {}".to_string();
+    let mut lexer = Lexer::from(&src);
+    assert!(lexer.start().is_err());
+
+    let mut parser = Parser::from(lexer.tokens());
+    parser.parse();
+    assert_eq!(parser.fn_decls.len(), 2);
+    assert_eq!(parser.fn_decls[0].body.len(), 2);
+    assert!(parser.diagnostics.is_empty());
+}
+
+#[test]
+fn attaches_doc_comments() {
+    let source = "/// A point.\nstruct Point {\n  /// x axis.\n  x: int,\n  y: int\n}\n\
+                  /// Adds one.\n/// Returns the result.\nfn inc(/// value\nx: int) -> int { return x + 1; }";
+    let structs = parser_struct(source);
+    assert_eq!(structs[0].docs, vec!["A point."]);
+    assert_eq!(structs[0].fields[0].docs, vec!["x axis."]);
+    assert!(structs[0].fields[1].docs.is_empty());
+
+    let fns = parse_fn(source);
+    assert_eq!(fns[0].docs, vec!["Adds one.", "Returns the result."]);
+    assert_eq!(fns[0].parameters[0].docs, vec!["value"]);
+}
+
+#[test]
+fn parses_function_with_multiple_params() {
+    let parser = parse_fn("fn add(a: int, b: float, p: Point) -> int { return add(1, 2.0, p); }");
+
+    let f = &parser[0];
+    let params: Vec<(&str, DType)> = f
+        .parameters
+        .iter()
+        .map(|p| (p.field_name.as_str(), p.field_type.clone()))
+        .collect();
+    assert_eq!(
+        params,
+        vec![
+            ("a", DType::Primitive(EveTypes::Int)),
+            ("b", DType::Primitive(EveTypes::Float)),
+            ("p", DType::Derived("Point".to_string())),
+        ]
+    );
+
+    match &f.body[0] {
+        Stmt::Return(ret) => match ret.value.as_ref().unwrap() {
+            Expr::Call(call) => assert_eq!(call.args.len(), 3),
+            _ => panic!("Expected call expression."),
+        },
+        _ => panic!("Expected return stmt."),
+    }
+}
+
+#[test]
+fn recovers_from_errors_inside_function_body() {
+    let (fns, diagnostics) = parse_recovering(
+        "fn main() -> void {\n  let x = 1\n  print x;\n  if (x > 1) { print 2 }\n  let y = ;\n  return;\n}\n\
+         fn other() -> void { print foo(1 2); }",
+    );
+
+    let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Expected ';' after let statement, found `print`",
+            "Expected ';' after print statement, found `}`",
+            "expected expression, found `;`",
+            "expected one of `,` or `)`, found `2`",
+        ]
+    );
+
+    assert_eq!(fns.len(), 2);
+    let body = &fns[0].body;
+    assert!(matches!(body[0], Stmt::Error(_)));
+    assert!(matches!(body[1], Stmt::Print(_)));
+    assert!(matches!(body[2], Stmt::If(_)));
+    assert!(matches!(body[3], Stmt::Error(_)));
+    assert!(matches!(body[4], Stmt::Return(_)));
+}
+
+#[test]
+fn reports_unclosed_block() {
+    let (fns, diagnostics) = parse_recovering("fn a() -> void {\n  print 1;\nfn main() -> void {}");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "expected `}`, found `fn`");
+    assert_eq!(fns.len(), 2);
+}
+
+#[test]
+fn recovers_from_global_inside_function_body() {
+    let (fns, diagnostics) = parse_recovering("fn main() -> void { const }\nfn other() -> void {}");
+
+    assert_eq!(diagnostics[0].message, "expected `}`, found `const`");
+    assert_eq!(fns.len(), 2);
+}
+
+#[test]
+fn reports_unexpected_top_level_item() {
+    let (fns, diagnostics) = parse_recovering("let a = 1;\nfn main() -> void {}");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "expected one of `fn`, `struct`, `const`, `static` or `enum`, found `let`"
+    );
+    assert_eq!(fns.len(), 1);
+}
+
+#[test]
+fn parses_error_tokens_as_error_expressions() {
+    let (fns, _) = parse_recovering("fn main() -> void { let a = @; }");
+
+    match &fns[0].body[0] {
+        Stmt::Let(le) => assert!(matches!(le.initialiser, Expr::Error(_))),
+        _ => panic!("Expected let stmt."),
+    }
+}
+
+#[test]
+fn parses_while_and_for_loops() {
+    let parser = parse_fn(
+        "fn main() -> void {\n\
+           while (true) { continue; }\n\
+           for (let i = 0; i < 10; i = i + 1) { break; }\n\
+           for (;;) {}\n\
+         }",
+    );
+
+    let body = &parser[0].body;
+    match &body[0] {
+        Stmt::While(whil) => match &whil.body {
+            Stmt::Block(block) => assert!(matches!(block.stmts[0], Stmt::Continue(_))),
+            _ => panic!("Expected block."),
+        },
+        _ => panic!("Expected while stmt."),
+    }
+
+    match &body[1] {
+        Stmt::For(fo) => {
+            assert!(matches!(fo.initialiser, Some(Stmt::Let(_))));
+            assert!(matches!(fo.condition, Some(Expr::Binary(_))));
+            assert!(matches!(fo.increment, Some(Expr::Assignment(_))));
+        }
+        _ => panic!("Expected for stmt."),
+    }
+
+    match &body[2] {
+        Stmt::For(fo) => {
+            assert!(fo.initialiser.is_none());
+            assert!(fo.condition.is_none());
+            assert!(fo.increment.is_none());
+        }
+        _ => panic!("Expected for stmt."),
+    }
+}
+
+#[test]
+fn parses_labeled_loops() {
+    let parser = parse_fn(
+        "fn main() -> void {\n\
+           'outer: loop {\n\
+             'inner: while (true) { break 'outer; }\n\
+             continue;\n\
+           }\n\
+         }",
+    );
+
+    let outer = match &parser[0].body[0] {
+        Stmt::Loop(lop) => lop,
+        _ => panic!("Expected loop stmt."),
+    };
+    assert_eq!(outer.label.as_deref(), Some("outer"));
+
+    let Stmt::Block(block) = &outer.body else {
+        panic!("Expected block.");
+    };
+    match &block.stmts[0] {
+        Stmt::While(whil) => {
+            assert_eq!(whil.label.as_deref(), Some("inner"));
+            let Stmt::Block(inner) = &whil.body else {
+                panic!("Expected block.");
+            };
+            match &inner.stmts[0] {
+                Stmt::Break(bre) => assert_eq!(bre.label.as_deref(), Some("outer")),
+                _ => panic!("Expected break stmt."),
+            }
+        }
+        _ => panic!("Expected while stmt."),
+    }
+    match &block.stmts[1] {
+        Stmt::Continue(cont) => assert!(cont.label.is_none()),
+        _ => panic!("Expected continue stmt."),
+    }
+}
+
+#[test]
+fn parses_match_stmt() {
+    let parser = parse_fn(
+        "fn main() -> void {\n\
+           match (1) {\n\
+             -1 => print 1;\n\
+             2..5 => { print 2; }\n\
+             5..=9 => print 3;\n\
+             _ => {}\n\
+           }\n\
+         }",
+    );
+
+    match &parser[0].body[0] {
+        Stmt::Match(mat) => {
+            let patterns: Vec<MatchPattern> = mat.arms.iter().map(|x| x.pattern.clone()).collect();
+            assert_eq!(
+                patterns,
+                vec![
+                    MatchPattern::Int(-1),
+                    MatchPattern::Range(2, 4),
+                    MatchPattern::Range(5, 9),
+                    MatchPattern::Wildcard,
+                ]
+            );
+        }
+        _ => panic!("Expected match stmt."),
+    }
+}
+
+#[test]
+fn reports_empty_range_pattern() {
+    let (_, diagnostics) = parse_recovering("fn main() -> void { match (1) { 5..5 => {} } }");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Range pattern doesn't match any value"
+    );
+}
+
+#[test]
+fn parses_compound_assignment() {
+    let parser = parse_fn("fn main() -> void { a *= 2 + 1; a++; }");
+
+    let body = &parser[0].body;
+    match &body[0] {
+        Stmt::Expression(Expr::Assignment(ass)) => {
+            assert!(matches!(&ass.target, LValue::Variable(var) if var.name == "a"));
+            assert_eq!(ass.kind, AssignKind::Compound(BinOp::Mul));
+            assert!(matches!(&ass.value, Expr::Binary(_)));
+        }
+        _ => panic!("Expected assignment."),
+    }
+
+    match &body[1] {
+        Stmt::Expression(Expr::Assignment(ass)) => {
+            assert_eq!(ass.kind, AssignKind::Postfix(BinOp::Add));
+            assert!(matches!(
+                &ass.value,
+                Expr::Literal(LiteralExpr {
+                    value: LiteralValue::NumberInt(1),
+                    ..
+                })
+            ));
+        }
+        _ => panic!("Expected assignment."),
+    }
+
+    let printed: Vec<_> = body
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Expression(expr) => format!("{}", expr),
+            _ => panic!("Expected expression."),
+        })
+        .collect();
+    assert_eq!(printed, vec!["a *= 2 + 1", "a++"]);
+}
+
+#[test]
+fn parses_field_assignment() {
+    let parser = parse_fn("fn main() -> void { p.x = 3; p.y += 1; }");
+
+    let body = &parser[0].body;
+    for stmt in body {
+        match stmt {
+            Stmt::Expression(Expr::Assignment(ass)) => {
+                assert!(matches!(&ass.target, LValue::FieldAccess(_)));
+            }
+            _ => panic!("Expected assignment."),
+        }
+    }
+    match &body[0] {
+        Stmt::Expression(expr) => assert_eq!(format!("{}", expr), "p.x = 3"),
+        _ => panic!("Expected expression."),
+    }
+}
+
+#[test]
+fn reports_invalid_assignment_target() {
+    let (_, diagnostics) = parse_recovering("fn main() -> void { 1 + 2 = 3; }");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Invalid left-hand side of assignment"
+    );
+}
+
+#[test]
+fn parses_chained_field_access() {
+    let parser = parse_fn("fn main() -> void { line.start.x = line.end.x; }");
+
+    match &parser[0].body[0] {
+        Stmt::Expression(Expr::Assignment(ass)) => {
+            match &ass.target {
+                LValue::FieldAccess(fac) => {
+                    assert_eq!(fac.field, "x");
+                    assert!(matches!(&fac.parent, Expr::FieldAccess(p) if p.field == "start"));
+                }
+                _ => panic!("Expected field access."),
+            }
+            assert_eq!(format!("{}", ass.value), "line.end.x");
+        }
+        _ => panic!("Expected assignment."),
+    }
+}
+
+#[test]
+fn parses_struct_init_expr() {
+    let parser = parse_fn(
+        "fn main() -> void { len(Vec2 { x: 1, y: 2 }); a.b = P { q: Q { r: 1 } }; return P { q: 1 }; }",
+    );
+
+    let body = &parser[0].body;
+    match &body[0] {
+        Stmt::Expression(Expr::Call(call)) => {
+            assert!(matches!(&call.args[0], Expr::StructInit(st) if st.struct_name == "Vec2"));
+        }
+        _ => panic!("Expected call."),
+    }
+    match &body[1] {
+        Stmt::Expression(expr) => assert_eq!(format!("{}", expr), "a.b = P { q: Q { r: 1 } }"),
+        _ => panic!("Expected assignment."),
+    }
+    match &body[2] {
+        Stmt::Return(ret) => assert!(matches!(&ret.value, Some(Expr::StructInit(_)))),
+        _ => panic!("Expected return."),
+    }
+}
+
+#[test]
+fn parses_typed_let() {
+    let parser =
+        parse_fn("fn main() -> void { let x: float = 3; let v: Vec2 = vec2(1.0); let n = 1; }");
+
+    let body = &parser[0].body;
+    let types: Vec<_> = body
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Let(le) => le.ty.clone(),
+            _ => panic!("Expected let stmt."),
+        })
+        .collect();
+    assert_eq!(
+        types,
+        vec![
+            Some(DType::Primitive(EveTypes::Float)),
+            Some(DType::Derived("Vec2".to_string())),
+            None
+        ]
+    );
+}
+
+#[test]
+fn parses_global_decls() {
+    let tokens =
+        tokenize("const WIDTH: int = 800;\nstatic title: string = \"eve\";\nfn main() -> void {}");
+    let mut parser = Parser::from(&tokens);
+    parser.parse();
+
+    assert!(parser.diagnostics.is_empty(), "{:?}", parser.diagnostics);
+    assert_eq!(parser.fn_decls.len(), 1);
+    let globals: Vec<_> = parser
+        .global_decls
+        .iter()
+        .map(|x| (x.kind, x.name.as_str(), x.ty.clone(), x.value.to_string()))
+        .collect();
+    assert_eq!(
+        globals,
+        vec![
+            (
+                GlobalKind::Const,
+                "WIDTH",
+                DType::Primitive(EveTypes::Int),
+                "800".to_string()
+            ),
+            (
+                GlobalKind::Static,
+                "title",
+                DType::Primitive(EveTypes::String),
+                "eve".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn parses_arrays() {
+    let parser = parse_fn(
+        "fn main() -> void { let a: [int; 3] = [1, 2, 3]; let b = [0.0; 16]; a[0] = b[1 + 1]; }",
+    );
+
+    let body = &parser[0].body;
+    let Stmt::Let(a) = &body[0] else {
+        panic!("Expected let stmt.");
+    };
+    assert_eq!(
+        a.ty,
+        Some(DType::Array(Box::new(DType::Primitive(EveTypes::Int)), 3))
+    );
+    assert_eq!(a.initialiser.to_string(), "[1, 2, 3]");
+
+    let Stmt::Let(b) = &body[1] else {
+        panic!("Expected let stmt.");
+    };
+    assert_eq!(b.initialiser.to_string(), "[0; 16]");
+
+    let Stmt::Expression(Expr::Assignment(ass)) = &body[2] else {
+        panic!("Expected assignment.");
+    };
+    assert!(matches!(ass.target, LValue::Index(_)));
+    assert_eq!(ass.target.to_string(), "a[0]");
+    assert_eq!(ass.value.to_string(), "b[1 + 1]");
+}
+
+#[test]
+fn parses_vectors() {
+    let parser = parse_fn(
+        "fn main() -> void { let v: Vec<Vec<int>> = Vec<Vec<int>>(); v.push(Vec<int>()); print v[0].len(); }",
+    );
+
+    let body = &parser[0].body;
+    let Stmt::Let(v) = &body[0] else {
+        panic!("Expected let stmt.");
+    };
+    assert_eq!(
+        v.ty,
+        Some(DType::Vector(Box::new(DType::Vector(Box::new(
+            DType::Primitive(EveTypes::Int)
+        )))))
+    );
+    assert!(matches!(v.initialiser, Expr::VecNew(_)));
+    assert_eq!(v.initialiser.to_string(), "Vec<Vec<Int>>()");
+
+    let Stmt::Expression(expr @ Expr::Call(call)) = &body[1] else {
+        panic!("Expected method call.");
+    };
+    assert!(matches!(call.callee, Expr::FieldAccess(_)));
+    assert_eq!(expr.to_string(), "v.push(Vec<Int>())");
+}
+
+#[test]
+fn parses_vec_as_a_name_outside_of_constructors() {
+    let (parser, diags) =
+        parse_recovering("fn main() -> void { let Vec = 1; let b = 2; print Vec < b; }");
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let Stmt::Print(print) = &parser[0].body[2] else {
+        panic!("Expected print stmt.");
+    };
+    assert!(matches!(print.value, Expr::Binary(_)));
+    assert_eq!(print.value.to_string(), "Vec < b");
+}
+
+#[test]
+fn parses_pointers() {
+    let parser = parse_fn(
+        "fn main() -> void { let p: **int = null; *p = &x.y; let m = extern malloc(8) -> *void; }",
+    );
+
+    let body = &parser[0].body;
+    let Stmt::Let(p) = &body[0] else {
+        panic!("Expected let stmt.");
+    };
+    assert_eq!(
+        p.ty,
+        Some(DType::Pointer(Box::new(DType::Pointer(Box::new(
+            DType::Primitive(EveTypes::Int)
+        )))))
+    );
+    assert!(matches!(
+        p.initialiser,
+        Expr::Literal(LiteralExpr {
+            value: LiteralValue::Null,
+            ..
+        })
+    ));
+
+    let Stmt::Expression(Expr::Assignment(ass)) = &body[1] else {
+        panic!("Expected assignment.");
+    };
+    assert!(matches!(ass.target, LValue::Deref(_)));
+    assert!(matches!(ass.value, Expr::AddrOf(_)));
+    assert_eq!(ass.value.to_string(), "&x.y");
+
+    let Stmt::Let(m) = &body[2] else {
+        panic!("Expected let stmt.");
+    };
+    let Expr::NativeCall(call) = &m.initialiser else {
+        panic!("Expected native call.");
+    };
+    assert_eq!(
+        call.return_type,
+        Some(DType::Pointer(Box::new(DType::Primitive(EveTypes::Void))))
+    );
+}
+
+#[test]
+fn reports_address_of_non_place() {
+    let (_, diagnostics) = parse_recovering("fn main() -> void { let p = &1; }");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Cannot take the address of this expression"
+    );
+}
+
+#[test]
+fn reports_zero_length_array_type() {
+    let (_, diagnostics) = parse_recovering("fn main() -> void { let a: [int; 0] = [1]; }");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Array length must be greater than 0"
+    );
+}
+
+#[test]
+fn parses_constant_array_lengths() {
+    let parser = parse_fn(
+        "const N: int = 4;\n\
+         fn main() -> void { let a: [int; N] = [0; N]; }",
+    );
+    let Stmt::Let(le) = &parser[0].body[0] else {
+        panic!("Expected let stmt.");
+    };
+    assert_eq!(
+        le.ty,
+        Some(DType::Array(Box::new(DType::Primitive(EveTypes::Int)), 4))
+    );
+
+    let (_, diagnostics) = parse_recovering(
+        "const N: int = 2 * 2;\n\
+         fn main() -> void { let a: [int; N] = [1]; let b: [int; M] = [1]; }\n\
+         const M: int = 1;",
+    );
+    let messages: Vec<_> = diagnostics.iter().map(|x| x.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Array length 'N' must be a constant with an integer literal value",
+            "'M' is not a constant declared before this in the same file",
+        ]
+    );
+}
+
+#[test]
+fn parses_enums() {
+    let enums = parse_enum("enum Key { Up, Down = 5, Left, Right = -1, }");
+
+    assert_eq!(enums.len(), 1);
+    assert_eq!(enums[0].name, "Key");
+    let variants: Vec<(&str, i32)> = enums[0]
+        .variants
+        .iter()
+        .map(|x| (x.name.as_str(), x.value))
+        .collect();
+    assert_eq!(
+        variants,
+        vec![("Up", 0), ("Down", 5), ("Left", 6), ("Right", -1)]
+    );
+
+    let parser = parse_fn("fn main() -> void { if (key == Key::Left) { print key; } }");
+    let Stmt::If(ifst) = &parser[0].body[0] else {
+        panic!("Expected if stmt.");
+    };
+    let Expr::Binary(bin) = &ifst.condition else {
+        panic!("Expected binary expression.");
+    };
+    assert!(matches!(bin.right, Expr::EnumVariant(_)));
+    assert_eq!(bin.right.to_string(), "Key::Left");
+}
+
+#[test]
+fn parses_variant_patterns() {
+    let parser = parse_fn("fn main() -> void { match (key) { Key::Up => {} _ => {} } }");
+
+    let Stmt::Match(mat) = &parser[0].body[0] else {
+        panic!("Expected match stmt.");
+    };
+    assert_eq!(
+        mat.arms[0].pattern,
+        MatchPattern::Variant("Key".into(), "Up".into())
+    );
+    assert_eq!(mat.arms[0].pattern.to_string(), "Key::Up");
+}
+
+#[test]
+fn recovers_from_enum_inside_function_body() {
+    let (fns, diagnostics) = parse_recovering("fn main() -> void { enum }\nfn other() -> void {}");
+
+    assert_eq!(diagnostics[0].message, "expected `}`, found `enum`");
+    assert_eq!(fns.len(), 2);
+}
+
+#[test]
+fn reports_enum_without_variants() {
+    let (_, diagnostics) = parse_recovering("enum Empty {}\nfn main() -> void {}");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Enum 'Empty' must have at least one variant"
+    );
+}
@@ -0,0 +1,21 @@
+use evelin::ast::Stmt;
+use evelin::passes;
+
+mod common;
+use common::parse_fn;
+
+#[test]
+fn removes_code_after_return() {
+    let fns = parse_fn(
+        "fn f() -> int { print 1; return 2; print 3; print 4; }\n\
+         fn main() -> void { f(); }",
+    );
+    let (fns, _, warnings) = passes::run_passes(fns, vec![]).unwrap();
+    assert_eq!(warnings.len(), 1);
+
+    // the return itself is kept, only the statements after it are removed.
+    let f = fns.iter().find(|x| x.name == "f").unwrap();
+    assert_eq!(f.body.len(), 2);
+    assert!(matches!(f.body[0], Stmt::Print(_)));
+    assert!(matches!(f.body[1], Stmt::Return(_)));
+}
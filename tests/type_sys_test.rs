@@ -0,0 +1,638 @@
+mod common;
+use common::check;
+
+#[test]
+fn checks_multi_param_calls() {
+    let diags = check(
+        "fn add(a: int, b: int) -> int { return a + b; }\n\
+         fn main() -> void { print add(1, 2); }",
+    );
+    assert!(diags.is_empty(), "{:?}", diags);
+}
+
+#[test]
+fn reports_wrong_arity() {
+    let diags = check(
+        "fn add(a: int, b: int) -> int { return a + b; }\n\
+         fn main() -> void { print add(1); }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0305"));
+    assert!(
+        diags[0]
+            .message
+            .contains("takes 2 argument(s) but 1 were supplied")
+    );
+}
+
+#[test]
+fn reports_mismatched_argument() {
+    let diags = check(
+        "fn add(a: int, b: int) -> int { return a + b; }\n\
+         fn main() -> void { print add(1, \"two\"); }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(diags[0].labels[0].message, "expected 'Int', found 'String'");
+}
+
+#[test]
+fn reports_duplicate_parameter() {
+    let diags = check("fn f(a: int, a: float) -> void {}\nfn main() -> void {}");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0306"));
+}
+
+#[test]
+fn reports_break_outside_loop() {
+    let diags = check("fn main() -> void { break; }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0307"));
+}
+
+#[test]
+fn reports_undeclared_label() {
+    let diags = check(
+        "fn main() -> void {\n\
+           'outer: loop { loop { continue 'outr; } }\n\
+         }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0308"));
+    assert_eq!(diags[0].message, "Use of undeclared label `'outr`");
+    assert_eq!(diags[0].suggestions[0].replacement, "continue 'outer");
+}
+
+#[test]
+fn reports_label_out_of_scope() {
+    let diags = check(
+        "fn main() -> void {\n\
+           'first: loop { break; }\n\
+           loop { break 'first; }\n\
+         }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0308"));
+}
+
+#[test]
+fn reports_shadowed_label() {
+    let diags = check("fn main() -> void { 'a: loop { 'a: loop { break 'a; } } }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0309"));
+}
+
+#[test]
+fn checks_exhaustive_match() {
+    let diags = check(
+        "fn main() -> void {\n\
+           match (3) {\n\
+             0 => print 0;\n\
+             1..=10 => print 1;\n\
+             _ => print 2;\n\
+           }\n\
+         }",
+    );
+    assert!(diags.is_empty(), "{:?}", diags);
+}
+
+#[test]
+fn reports_non_exhaustive_match() {
+    let diags = check("fn main() -> void { match (3) { 0 => print 0; 1..=10 => print 1; } }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0310"));
+    assert_eq!(
+        diags[0].labels[0].message,
+        "value '-2147483648' not covered"
+    );
+}
+
+#[test]
+fn reports_unreachable_match_arm() {
+    let diags = check(
+        "fn main() -> void { match (3) { 1..=10 => print 1; 4 => print 4; _ => {} 7 => {} } }",
+    );
+    let codes: Vec<_> = diags.iter().map(|x| x.code).collect();
+    assert_eq!(codes, vec![Some("E0311"), Some("E0311")]);
+    assert_eq!(diags[0].message, "Unreachable match arm '4'");
+}
+
+#[test]
+fn reports_match_on_non_int() {
+    let diags = check("fn main() -> void { match (1.5) { _ => {} } }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0300"));
+}
+
+#[test]
+fn checks_compound_assignment_like_binary() {
+    let diags = check("fn main() -> void { let a = 1.5; a += 1; a--; }");
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("fn main() -> void { let a = 1; a += \"s\"; }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "+ operation cannot be applied between Int and String"
+    );
+
+    let diags = check("fn main() -> void { let a = 1; a /= 2.0; }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'Float' to variable 'a' of type 'Int'"
+    );
+}
+
+#[test]
+fn checks_field_assignment() {
+    let src = "struct P { x: int, y: float }\n\
+               fn main() -> void { let p = P { x: 1, y: 2.0 }; p.x = 3; p.y = 1.5; p.y = 2; }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check(
+        "struct P { x: int, y: float }\n\
+         fn main() -> void { let p = P { x: 1, y: 2.0 }; p.x = 1.5; }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'Float' to field 'p.x' of type 'Int'"
+    );
+
+    let diags = check(
+        "struct P { x: int, y: float }\n\
+         fn main() -> void { let p = P { x: 1, y: 2.0 }; p.z = 1; }",
+    );
+    assert_eq!(diags[0].code, Some("E0304"));
+}
+
+#[test]
+fn checks_chained_field_access() {
+    let src = "struct Point { x: int, y: int }\n\
+               struct Line { start: Point, end: Point }\n\
+               fn main() -> void {\n\
+                 let p = Point { x: 1, y: 2 };\n\
+                 let l = Line { start: p, end: p };\n\
+                 l.end.y = l.start.x + 1;\n\
+                 l.start = p;\n\
+               }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check(
+        "struct Point { x: int, y: int }\n\
+         struct Line { start: Point, end: Point }\n\
+         fn main() -> void { let p = Point { x: 1, y: 2 }; let l = Line { start: p, end: p }; l.start = 1; }",
+    );
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'Int' to field 'l.start' of type 'Point'"
+    );
+
+    let diags = check(
+        "struct Point { x: int, y: int }\n\
+         fn main() -> void { let p = Point { x: 1, y: 2 }; print p.x.y; }",
+    );
+    assert_eq!(diags[0].code, Some("E0302"));
+    assert_eq!(diags[0].message, "'p.x' is not a struct instance");
+}
+
+#[test]
+fn reports_recursive_struct() {
+    let diags = check(
+        "struct A { b: B }\n\
+         struct B { x: int, a: A }\n\
+         struct C { c: C }\n\
+         fn main() -> void { }",
+    );
+    let messages: Vec<_> = diags.iter().map(|x| x.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Recursive struct 'A' has infinite size",
+            "Recursive struct 'C' has infinite size"
+        ]
+    );
+    assert_eq!(diags[0].code, Some("E0205"));
+    assert_eq!(diags[0].labels.len(), 3);
+}
+
+#[test]
+fn reports_unknown_field_type() {
+    let diags = check("struct A { b: Missing }\nfn main() -> void { }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0303"));
+}
+
+#[test]
+fn reports_unknown_let_type() {
+    let diags = check("fn main() -> void { let e: Nope = 1; }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0303"));
+    assert_eq!(diags[0].message, "Unknown type 'Nope'");
+
+    let diags = check("fn main() -> void { let v: Vec<*Nope> = Vec<int>(); }");
+    assert_eq!(diags[0].message, "Unknown type 'Nope'");
+}
+
+#[test]
+fn checks_struct_init_expr() {
+    let src = "struct P { x: int, y: int }\n\
+               fn sum(p: P) -> int { return p.x + p.y; }\n\
+               fn main() -> void { print sum(P { x: 1, y: 2 }); }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check(
+        "struct P { x: int, y: int }\n\
+         fn main() -> void { let a = 1; a = P { x: 1, y: 2 }; }",
+    );
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'P' to variable 'a' of type 'Int'"
+    );
+}
+
+#[test]
+fn reports_nested_struct_init_fields() {
+    let diags = check(
+        "struct P { x: int, y: int }\n\
+         fn main() -> void { if (1) { print P { x: 1, x: 2, y: 3 }.x; } }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0202"));
+
+    let diags = check(
+        "struct P { x: int, y: int }\n\
+         fn f(p: P) -> int { return p.x; }\n\
+         fn main() -> void { loop { f(P { x: 1 }); } }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0203"));
+}
+
+#[test]
+fn checks_struct_return() {
+    let src = "struct Vec2 { x: float, y: float }\n\
+               fn vec2(x: float) -> Vec2 { return Vec2 { x: x, y: 0.0 }; }\n\
+               fn main() -> void { let v = vec2(1.5); v = vec2(v.x); print vec2(2.0).y; }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("fn one() -> float { return 1; }\nfn main() -> void { print one(); }");
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check(
+        "struct Vec2 { x: float, y: float }\n\
+         fn vec2(x: float) -> Vec2 { return x; }\n\
+         fn main() -> void { vec2(1.0); }",
+    );
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Cannot return value of type 'Float' from function returning 'Vec2'"
+    );
+}
+
+#[test]
+fn coerces_int_arguments_to_float() {
+    let diags = check(
+        "fn half(x: float) -> float { return x / 2.0; }\nfn main() -> void { print half(3); }",
+    );
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags =
+        check("fn twice(x: int) -> int { return x * 2; }\nfn main() -> void { print twice(1.5); }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(
+        diags[0].message,
+        "Mismatched type for argument 'x' of function 'twice'"
+    );
+}
+
+#[test]
+fn checks_typed_let() {
+    let src = "struct Vec2 { x: float, y: float }\n\
+               fn vec2(x: float) -> Vec2 { return Vec2 { x: x, y: x }; }\n\
+               fn main() -> void { let x: float = 3; x = 1.5; let v: Vec2 = vec2(x); }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("fn main() -> void { let x: int = 1.5; }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Cannot initialise 'x' of type 'Int' with value of type 'Float'"
+    );
+
+    // the annotation is the variable's type, not the initialiser's.
+    let diags = check("fn main() -> void { let x: float = 1; x = 2; }");
+    assert!(diags.is_empty(), "{:?}", diags);
+    let diags = check("fn main() -> void { let x: float = 1; x = \"s\"; }");
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'String' to variable 'x' of type 'Float'"
+    );
+}
+
+#[test]
+fn checks_globals() {
+    let src = "const WIDTH: int = 800;\n\
+               const RATIO: float = WIDTH / 2 * 1.5;\n\
+               static frames: int = 0;\n\
+               fn main() -> void { frames += WIDTH; let WIDTH = 1.5; print WIDTH * RATIO; }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("const A: int = 1;\nfn main() -> void { A = 2; }");
+    assert_eq!(diags.len(), 1);
+    assert_eq!(diags[0].code, Some("E0312"));
+    assert_eq!(diags[0].message, "Cannot assign to constant 'A'");
+
+    let diags = check("static a: float = 1.0;\nfn main() -> void { a = 2; }");
+    assert!(diags.is_empty(), "{:?}", diags);
+    let diags = check("static a: int = 1;\nfn main() -> void { a = 2.5; }");
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'Float' to variable 'a' of type 'Int'"
+    );
+}
+
+#[test]
+fn reports_invalid_global_initialisers() {
+    let diags = check(
+        "fn five() -> int { return 5; }\n\
+         const A: int = five();\n\
+         const B: int = C + 1;\n\
+         const C: int = 1;\n\
+         static D: int = 1.5;\n\
+         const E: int = 1 / 0;\n\
+         const C: int = 2;\n\
+         fn main() -> void {}",
+    );
+    let messages: Vec<_> = diags.iter().map(|x| (x.code, x.message.as_str())).collect();
+    assert_eq!(
+        messages,
+        vec![
+            (Some("E0313"), "Expected a constant expression"),
+            (Some("E0313"), "'C' is not a constant declared before this"),
+            (
+                Some("E0300"),
+                "Cannot initialise static 'D' of type 'Int' with value of type 'Float'"
+            ),
+            (Some("E0313"), "Division by zero in constant expression"),
+            (Some("E0314"), "'C' is declared more than once"),
+        ]
+    );
+}
+
+#[test]
+fn checks_arrays() {
+    let src = "struct Grid { cells: [[int; 2]; 2] }\n\
+               const N: int = 4;\n\
+               fn sum(a: [int; 4]) -> int { return a[0] + a[3]; }\n\
+               fn main() -> void {\n\
+                 let a = [1, 2, 3, 4]; a[1] = 5; print sum(a) + a[N - 1];\n\
+                 let g = Grid { cells: [[0; 2]; 2] }; g.cells[1][0] = 3;\n\
+               }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("fn main() -> void { let a = [1, 2.5]; }");
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Mismatched types in array literal, expected 'Int', found 'Float'"
+    );
+
+    let diags = check("fn main() -> void { let a = [1, 2]; a[0] = 1.5; }");
+    assert_eq!(
+        diags[0].message,
+        "Cannot assign value of type 'Float' to element of 'a' of type 'Int'"
+    );
+
+    let diags = check("fn main() -> void { let a: [float; 2] = [1.0; 3]; }");
+    assert_eq!(
+        diags[0].message,
+        "Cannot initialise 'a' of type '[Float; 2]' with value of type '[Float; 3]'"
+    );
+}
+
+#[test]
+fn reports_invalid_indexing() {
+    let diags = check("fn main() -> void { let a = 1; print a[0]; }");
+    assert_eq!(diags[0].code, Some("E0315"));
+    assert_eq!(diags[0].message, "'a' is not an array or a vector");
+
+    let diags = check("fn main() -> void { let a = [1, 2]; print a[1.0]; }");
+    assert_eq!(
+        diags[0].message,
+        "Array index must be of type 'Int', found 'Float'"
+    );
+
+    let diags = check("const LEN: int = 2;\nfn main() -> void { let a = [1, 2]; print a[LEN]; }");
+    assert_eq!(diags[0].code, Some("E0316"));
+    assert_eq!(
+        diags[0].message,
+        "Index 2 is out of bounds for array of length 2"
+    );
+
+    let diags = check("struct S { a: [S; 2] }\nfn main() -> void {}");
+    assert_eq!(diags[0].code, Some("E0205"));
+}
+
+#[test]
+fn checks_vectors() {
+    let src = "struct Point { x: int }\n\
+               struct Path { points: Vec<Point> }\n\
+               fn sum(v: Vec<float>) -> float {\n\
+                 let s = 0.0;\n\
+                 for (let i = 0; i < v.len(); i++) { s = s + v[i]; }\n\
+                 return s;\n\
+               }\n\
+               fn main() -> void {\n\
+                 let v = Vec<float>(); v.push(1); v.push(2.5); v[0] = 3.0; print sum(v) + v.pop();\n\
+                 let p = Path { points: Vec<Point>() }; p.points.push(Point { x: 1 }); print p.points[0].x;\n\
+               }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("fn main() -> void { let v = Vec<int>(); v.push(1.5); }");
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Mismatched type for argument of method 'push'"
+    );
+
+    let diags = check("fn main() -> void { let v: Vec<int> = Vec<float>(); }");
+    assert_eq!(
+        diags[0].message,
+        "Cannot initialise 'v' of type 'Vec<Int>' with value of type 'Vec<Float>'"
+    );
+
+    let diags = check("fn main() -> void { let v = Vec<int>(); print v; }");
+    assert_eq!(diags[0].message, "Cannot print value of type 'Vec<Int>'");
+}
+
+#[test]
+fn reports_invalid_vector_methods() {
+    let diags = check("fn main() -> void { let v = Vec<int>(); v.insert(1); }");
+    assert_eq!(diags[0].code, Some("E0317"));
+    assert_eq!(diags[0].message, "No method 'insert' on type 'Vec<Int>'");
+
+    let diags = check("fn main() -> void { let v = 1; v.push(1); }");
+    assert_eq!(diags[0].code, Some("E0317"));
+    assert_eq!(diags[0].message, "No method 'push' on type 'Int'");
+
+    let diags = check("fn main() -> void { let v = Vec<int>(); v.pop(1); }");
+    assert_eq!(diags[0].code, Some("E0305"));
+
+    let diags = check("fn main() -> void { let v = Vec<Foo>(); }");
+    assert_eq!(diags[0].code, Some("E0303"));
+}
+
+#[test]
+fn checks_pointers() {
+    let src = "struct Node { value: int, next: *Node }\n\
+               static COUNT: int = 0;\n\
+               fn bump(p: *int) -> void { *p += 1; }\n\
+               fn first(n: Node) -> *Node { return n.next; }\n\
+               fn main() -> void {\n\
+                 let x = 1; let p = &x; bump(p); bump(&COUNT); print *p;\n\
+                 let n = Node { value: 1, next: null }; let m = Node { value: 2, next: &n };\n\
+                 if (first(m) != null) { (*m.next).value = 3; }\n\
+                 let buf = extern malloc(16) -> *void; let ints: *int = buf; *ints = 5; extern free(buf);\n\
+               }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("fn main() -> void { let x = 1; let p: *float = &x; }");
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Cannot initialise 'p' of type '*Float' with value of type '*Int'"
+    );
+
+    let diags = check("fn main() -> void { let x = 1; let p = &x; print p < p; }");
+    assert_eq!(diags[0].code, Some("E0300"));
+}
+
+#[test]
+fn reports_invalid_pointer_use() {
+    let diags = check("const N: int = 1;\nfn main() -> void { let p = &N; }");
+    assert_eq!(diags[0].code, Some("E0318"));
+    assert_eq!(diags[0].message, "Cannot take the address of constant 'N'");
+
+    let diags = check("fn main() -> void { let x = 1; print *x; }");
+    assert_eq!(diags[0].code, Some("E0319"));
+    assert_eq!(diags[0].message, "'x' is not a pointer");
+
+    let diags = check("fn main() -> void { let p = null; print *p; }");
+    assert_eq!(diags[0].code, Some("E0319"));
+    assert_eq!(diags[0].message, "Cannot dereference a pointer to 'void'");
+}
+
+#[test]
+fn checks_enums() {
+    let src = "enum State { Idle, Running = 5, Done }\n\
+               struct Task { id: int, state: State }\n\
+               fn advance(s: State) -> State { if (s == State::Idle) { return State::Running; } return State::Done; }\n\
+               fn main() -> void {\n\
+                 let t = Task { id: 1, state: State::Idle };\n\
+                 let s: State = advance(t.state); t.state = s;\n\
+                 if (t.state >= State::Running) { print t.state; }\n\
+                 let all = [State::Idle, State::Done]; let v = Vec<State>(); v.push(all[1]);\n\
+               }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check("enum A { X }\nfn main() -> void { let a: int = A::X; }");
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Cannot initialise 'a' of type 'Int' with value of type 'A'"
+    );
+
+    let diags = check("enum A { X }\nenum B { X }\nfn main() -> void { print A::X == B::X; }");
+    assert_eq!(diags[0].code, Some("E0300"));
+
+    let diags = check("enum A { X }\nfn main() -> void { print A::X + A::X; }");
+    assert_eq!(diags[0].code, Some("E0300"));
+}
+
+#[test]
+fn checks_match_on_enums() {
+    let src = "enum Dir { Up, Down, Left = 7, Right }\n\
+               fn main() -> void {\n\
+                 let d = Dir::Left;\n\
+                 match (d) { Dir::Up => print 0; Dir::Down => {} Dir::Left => {} Dir::Right => {} }\n\
+                 match (d) { Dir::Up => print 0; _ => {} }\n\
+               }";
+    let diags = check(src);
+    assert!(diags.is_empty(), "{:?}", diags);
+
+    let diags = check(
+        "enum Dir { Up, Down, Left }\n\
+         fn main() -> void { match (Dir::Up) { Dir::Up => {} Dir::Left => {} Dir::Up => {} } }",
+    );
+    let codes: Vec<_> = diags.iter().map(|x| x.code).collect();
+    assert_eq!(codes, vec![Some("E0311"), Some("E0310")]);
+    assert_eq!(diags[0].message, "Unreachable match arm 'Dir::Up'");
+    assert_eq!(diags[1].labels[0].message, "value 'Dir::Down' not covered");
+
+    // variants sharing a value are covered together.
+    let diags =
+        check("enum A { X, Y = 0 }\nfn main() -> void { match (A::X) { A::X => {} A::Y => {} } }");
+    let codes: Vec<_> = diags.iter().map(|x| x.code).collect();
+    assert_eq!(codes, vec![Some("E0311")]);
+}
+
+#[test]
+fn reports_mismatched_match_pattern() {
+    let diags = check("enum A { X }\nfn main() -> void { match (1) { A::X => {} _ => {} } }");
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Pattern 'A::X' can't match value of type 'Int'"
+    );
+
+    let diags = check(
+        "enum A { X }\nenum B { X }\nfn main() -> void { match (A::X) { B::X => {} 1 => {} } }",
+    );
+    assert_eq!(diags[0].code, Some("E0300"));
+    assert_eq!(
+        diags[0].message,
+        "Pattern 'B::X' can't match value of type 'A'"
+    );
+
+    let diags = check("enum A { X }\nfn main() -> void { match (A::X) { A::Y => {} _ => {} } }");
+    assert_eq!(diags[0].code, Some("E0321"));
+}
+
+#[test]
+fn reports_invalid_enum_use() {
+    let diags = check("enum A { X }\nfn main() -> void { print A::Y; }");
+    assert_eq!(diags[0].code, Some("E0321"));
+    assert_eq!(diags[0].message, "Enum 'A' has no variant 'Y'");
+
+    let diags = check("fn main() -> void { print B::X; }");
+    assert_eq!(diags[0].code, Some("E0321"));
+    assert_eq!(diags[0].message, "Enum 'B' not defined");
+
+    let diags = check("enum A { X, Y, X }\nfn main() -> void {}");
+    assert_eq!(diags[0].code, Some("E0320"));
+    assert_eq!(
+        diags[0].message,
+        "Variant 'X' is declared more than once in enum 'A'"
+    );
+
+    let diags = check("struct A { x: int }\nenum A { X }\nfn main() -> void {}");
+    assert_eq!(diags[0].code, Some("E0320"));
+    assert_eq!(diags[0].message, "'A' is declared more than once");
+}